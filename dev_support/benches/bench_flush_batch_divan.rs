@@ -0,0 +1,21 @@
+//! Compares the overhead of recording the latencies of tiny spans as each span closes vs. in batches
+//! (see `LatencyTraceCfg::with_flush_batch`).
+
+use dev_support::bench_support::flush_batch::{run, set_up, FLUSH_BATCH};
+use divan::Bencher;
+
+#[divan::bench]
+fn per_close_bench(bencher: Bencher) {
+    let dispatch = set_up(None);
+    bencher.bench_local(|| run(&dispatch));
+}
+
+#[divan::bench]
+fn batched_bench(bencher: Bencher) {
+    let dispatch = set_up(Some(FLUSH_BATCH));
+    bencher.bench_local(|| run(&dispatch));
+}
+
+fn main() {
+    divan::main();
+}
@@ -0,0 +1,17 @@
+//! Measures the time and allocations of the instrumentation of spans nested 10 levels deep.
+
+use dev_support::bench_support::nested::{run, set_up};
+use divan::{AllocProfiler, Bencher};
+
+#[global_allocator]
+static ALLOC: AllocProfiler = AllocProfiler::system();
+
+#[divan::bench]
+fn nested_bench(bencher: Bencher) {
+    let dispatch = set_up();
+    bencher.bench_local(|| run(&dispatch));
+}
+
+fn main() {
+    divan::main();
+}
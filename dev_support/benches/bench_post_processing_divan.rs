@@ -0,0 +1,16 @@
+//! Benchmarks the post-processing of a synthetic trace with over 100k span groups.
+
+use dev_support::bench_support::post_processing::{collect, post_process, set_up};
+use divan::Bencher;
+
+#[divan::bench(sample_count = 10, sample_size = 1)]
+fn post_processing_bench(bencher: Bencher) {
+    let lt = set_up();
+    bencher
+        .with_inputs(|| collect(&lt))
+        .bench_local_values(|acc| post_process(&lt, acc));
+}
+
+fn main() {
+    divan::main();
+}
@@ -1,7 +1,7 @@
 //! Module to compare the difference of total latency for two closures and parse its output into CSV format.
 
 use hdrhistogram::Histogram;
-use latency_trace::summary_stats;
+use latency_trace::{SummaryStats, TimeUnit};
 use std::{
     hint::black_box,
     io::{stdout, Write},
@@ -98,10 +98,10 @@ pub fn bench_diff<U>(
 
     println!(" done\n");
 
-    let summary_f1 = summary_stats(&hist_f1);
-    let summary_f2 = summary_stats(&hist_f2);
-    let summary_f1_lt_f2 = summary_stats(&hist_f1_lt_f2);
-    let summary_f1_ge_f2 = summary_stats(&hist_f1_ge_f2);
+    let summary_f1 = SummaryStats::new_with_unit(&hist_f1, TimeUnit::Micros);
+    let summary_f2 = SummaryStats::new_with_unit(&hist_f2, TimeUnit::Micros);
+    let summary_f1_lt_f2 = SummaryStats::new_with_unit(&hist_f1_lt_f2, TimeUnit::Micros);
+    let summary_f1_ge_f2 = SummaryStats::new_with_unit(&hist_f1_ge_f2, TimeUnit::Micros);
 
     println!("summary_f1={summary_f1:?}");
     println!("\nsummary_f2={summary_f2:?}");
@@ -0,0 +1,34 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::hint::black_box;
+use tracing::{trace_span, Dispatch};
+use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+/// Number of tiny spans closed by each execution of [`tiny_spans`].
+pub const NSPANS: usize = 10_000;
+
+/// Batch size used for the batched recording mode (see [`LatencyTraceCfg::with_flush_batch`]).
+pub const FLUSH_BATCH: usize = 256;
+
+/// Closes `nspans` spans that do no work, so the cost is dominated by the instrumentation overhead.
+pub fn tiny_spans(nspans: usize) {
+    for _ in 0..nspans {
+        trace_span!("tiny").in_scope(|| black_box(()));
+    }
+}
+
+/// Returns a [`Dispatch`] whose subscriber records latencies with a [`LatencyTrace`] layer configured with the
+/// given `flush_batch`. A local dispatcher is used rather than the global default so that both recording modes can
+/// be benchmarked in the same process.
+pub fn set_up(flush_batch: Option<usize>) -> Dispatch {
+    let cfg = match flush_batch {
+        None => LatencyTraceCfg::default(),
+        Some(n) => LatencyTraceCfg::default().with_flush_batch(n),
+    };
+    let lt = LatencyTrace::new(cfg).unwrap();
+    Dispatch::new(Registry::default().with(lt))
+}
+
+/// Executes [`tiny_spans`] with `dispatch` as the current thread's default dispatcher.
+pub fn run(dispatch: &Dispatch) {
+    tracing::dispatcher::with_default(dispatch, || tiny_spans(black_box(NSPANS)));
+}
@@ -1,5 +1,8 @@
 pub mod common;
 pub mod deep;
+pub mod flush_batch;
+pub mod nested;
+pub mod post_processing;
 pub mod simple;
 pub mod simple_real;
 pub mod simple_real_j;
@@ -0,0 +1,25 @@
+use latency_trace::LatencyTrace;
+use std::hint::black_box;
+use tracing::{trace_span, Dispatch};
+use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+/// Nesting depth of the spans created by [`nested_spans`].
+pub const DEPTH: usize = 10;
+
+/// Creates a chain of `depth` nested spans that do no work.
+pub fn nested_spans(depth: usize) {
+    if depth == 0 {
+        return;
+    }
+    trace_span!("nested").in_scope(|| nested_spans(depth - 1));
+}
+
+/// Returns a [`Dispatch`] whose subscriber records latencies with a default [`LatencyTrace`] layer.
+pub fn set_up() -> Dispatch {
+    Dispatch::new(Registry::default().with(LatencyTrace::default()))
+}
+
+/// Executes [`nested_spans`] with [`DEPTH`] and `dispatch` as the current thread's default dispatcher.
+pub fn run(dispatch: &Dispatch) {
+    tracing::dispatcher::with_default(dispatch, || nested_spans(black_box(DEPTH)));
+}
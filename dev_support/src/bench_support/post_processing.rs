@@ -0,0 +1,52 @@
+use latency_trace::{
+    bench_support::{collect_raw_traces, report_timings},
+    group_by_all_fields, LatencyTrace, LatencyTraceCfg, RawTrace, Timings,
+};
+use std::hint::black_box;
+use tracing::{info_span, instrument};
+
+/// Number of `branch` span groups in the synthetic trace.
+pub const NBRANCHES: usize = 1000;
+
+/// Number of `leaf` span groups per `branch` span group in the synthetic trace.
+pub const NLEAVES: usize = 100;
+
+/// Number of span groups in the synthetic trace.
+pub const GROUP_COUNT: usize = 1 + NBRANCHES + NBRANCHES * NLEAVES;
+
+#[instrument(level = "trace")]
+fn leaf(leaf: usize) {}
+
+#[instrument(level = "trace")]
+fn branch(branch: usize, nleaves: usize) {
+    for i in 0..nleaves {
+        leaf(i);
+    }
+}
+
+/// Produces a trace with `1 + nbranches + nbranches * nleaves` span groups when spans are grouped by all fields.
+pub fn synthetic_trace(nbranches: usize, nleaves: usize) {
+    info_span!("root").in_scope(|| {
+        for i in 0..nbranches {
+            branch(i, nleaves);
+        }
+    });
+}
+
+pub fn set_up() -> LatencyTrace {
+    LatencyTrace::activated(LatencyTraceCfg::default().with_span_grouper(group_by_all_fields))
+        .unwrap()
+}
+
+/// Collects the thread-local data for the synthetic trace, without post-processing.
+pub fn collect(lt: &LatencyTrace) -> Vec<RawTrace> {
+    collect_raw_traces(lt, || {
+        synthetic_trace(black_box(NBRANCHES), black_box(NLEAVES))
+    })
+}
+
+pub fn post_process(lt: &LatencyTrace, acc: Vec<RawTrace>) -> Timings {
+    let timings = report_timings(lt, acc);
+    assert_eq!(timings.len(), GROUP_COUNT, "group count assertion");
+    black_box(timings)
+}
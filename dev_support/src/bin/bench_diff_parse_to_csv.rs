@@ -1,6 +1,6 @@
 //! Parses a file containing the outputs of successive [`bench_diff`] runs converts it to CSV format to `stdout`.
 
-use latency_trace::SummaryStats;
+use latency_trace::{SummaryStats, TimeUnit};
 use regex::Regex;
 use std::{
     fs::File,
@@ -41,6 +41,8 @@ fn print_summary(name: &str, s: &SummaryStats) {
         p95,
         p99,
         max,
+        unit: _,
+        percentiles: _,
     } = s;
 
     print!(",{name}");
@@ -100,12 +102,14 @@ fn default_summary_stats() -> SummaryStats {
         p95: 0,
         p99: 0,
         max: 0,
+        unit: TimeUnit::Micros,
+        percentiles: Vec::new(),
     }
 }
 
 fn parse_section(section_text: &str) -> Section {
     // Regular expression to match the summary statistics
-    let summary_re = Regex::new(r"summary_(\w+)=SummaryStats \{ count: (\d+), mean: (\d+\.\d+), stdev: (\d+\.\d+), min: (\d+), p1: (\d+), p5: (\d+), p10: (\d+), p25: (\d+), median: (\d+), p75: (\d+), p90: (\d+), p95: (\d+), p99: (\d+), max: (\d+)\ }").unwrap();
+    let summary_re = Regex::new(r"summary_(\w+)=SummaryStats \{ count: (\d+), mean: (\d+\.\d+), stdev: (\d+\.\d+), min: (\d+), p1: (\d+), p5: (\d+), p10: (\d+), p25: (\d+), median: (\d+), p75: (\d+), p90: (\d+), p95: (\d+), p99: (\d+), max: (\d+)(?:, unit: \w+)?(?:, percentiles: \[[^\]]*\])?\ }").unwrap();
 
     // Regular expression to match the arguments
     let args_re = Regex::new(r"\(([^)]*)\)").unwrap();
@@ -138,6 +142,8 @@ fn parse_section(section_text: &str) -> Section {
             p95: summary_cap.get(13).unwrap().as_str().parse().unwrap(),
             p99: summary_cap.get(14).unwrap().as_str().parse().unwrap(),
             max: summary_cap.get(15).unwrap().as_str().parse().unwrap(),
+            unit: TimeUnit::Micros,
+            percentiles: Vec::new(),
         };
 
         match name {
@@ -1,11 +1,7 @@
 use std::collections::BTreeMap;
 
 fn safe_div(x1: u64, x2: u64) -> u64 {
-    if x2 != 0 {
-        x1 / x2
-    } else {
-        0
-    }
+    x1.checked_div(x2).unwrap_or(0)
 }
 
 #[derive(Debug)]
@@ -0,0 +1,51 @@
+use latency_trace::{ActivationError, HistogramCfg, LatencyTrace, LatencyTraceCfg, TimeUnit};
+use tracing::trace_span;
+
+fn f() {
+    trace_span!("span").in_scope(|| {});
+}
+
+#[test]
+fn test_activated_twice() {
+    let cfg = LatencyTraceCfg::default().with_hist_high(1_000_000);
+    let lt = LatencyTrace::activated(cfg.clone()).unwrap();
+
+    // Activation with the same histogram configuration returns the active instance.
+    let lt2 = LatencyTrace::activated(cfg.clone().with_drop_root(true)).unwrap();
+    // Default activation returns the active instance, whatever its configuration.
+    let lt3 = LatencyTrace::activated_default().unwrap();
+
+    for lt in [lt, lt2, lt3] {
+        let timings = lt.measure_latencies(f);
+        let timing = timings.values().next().unwrap();
+        assert_eq!(timing.high(), 1_000_000);
+    }
+
+    // Activation with a different histogram configuration fails.
+    let existing = HistogramCfg {
+        time_unit: TimeUnit::Micros,
+        hist_low: cfg.hist_low(),
+        hist_high: 1_000_000,
+        hist_sigfig: cfg.hist_sigfig(),
+    };
+    match LatencyTrace::activated(cfg.with_hist_high(5_000_000)) {
+        Err(ActivationError::ConfigMismatch {
+            existing: e,
+            requested,
+        }) => {
+            assert_eq!(e, existing);
+            assert_eq!(
+                requested,
+                HistogramCfg {
+                    hist_high: 5_000_000,
+                    ..existing
+                }
+            );
+        }
+        other => panic!("unexpected result {:?}", other.err()),
+    }
+    assert!(matches!(
+        LatencyTrace::activated(LatencyTraceCfg::default()),
+        Err(ActivationError::ConfigMismatch { .. })
+    ));
+}
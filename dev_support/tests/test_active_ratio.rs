@@ -0,0 +1,51 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::time::{Duration, Instant};
+use tracing::{info_span, trace_span, Instrument};
+
+async fn f() {
+    async {
+        // Suspended most of the time.
+        tokio::time::sleep(Duration::from_millis(20))
+            .instrument(trace_span!("sleeping"))
+            .await;
+
+        // Busy all of the time.
+        async {
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_millis(10) {
+                std::hint::spin_loop();
+            }
+        }
+        .instrument(trace_span!("busy"))
+        .await;
+    }
+    .instrument(info_span!("root"))
+    .await
+}
+
+#[test]
+fn test_active_ratio() {
+    let cfg = LatencyTraceCfg::default().with_record_active_time(true);
+    let report = LatencyTrace::activated(cfg).unwrap().measure_report(|| {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(f())
+    });
+
+    let ratios = report.active_ratio();
+    assert_eq!(ratios.len(), report.timings().len());
+    let ratio_of = |name: &str| {
+        ratios
+            .iter()
+            .find(|(sg, _)| sg.name() == name)
+            .map(|(_, ratio)| *ratio)
+            .unwrap()
+    };
+
+    let sleeping = ratio_of("sleeping");
+    assert!(sleeping < 0.2, "sleeping={sleeping}");
+    let busy = ratio_of("busy");
+    assert!(busy > 0.8, "busy={busy}");
+}
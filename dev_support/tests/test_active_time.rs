@@ -0,0 +1,88 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg, Timings};
+use std::time::{Duration, Instant};
+use tracing::{info_span, trace_span, Instrument};
+
+async fn f() {
+    async {
+        // Suspended most of the time.
+        tokio::time::sleep(Duration::from_millis(20))
+            .instrument(trace_span!("waiting"))
+            .await;
+
+        // Busy all of the time.
+        trace_span!("busy").in_scope(|| {
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_millis(10) {
+                std::hint::spin_loop();
+            }
+        });
+    }
+    .instrument(info_span!("root"))
+    .await
+}
+
+fn max_by_name(timings: &Timings, name: &str) -> u64 {
+    timings
+        .iter()
+        .find(|(sg, _)| sg.name() == name)
+        .map(|(_, timing)| timing.max())
+        .unwrap()
+}
+
+#[test]
+fn test_active_time() {
+    let cfg = LatencyTraceCfg::default().with_record_active_time(true);
+    let lt = LatencyTrace::activated(cfg).unwrap();
+    let report = lt.measure_report(|| {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(f())
+    });
+    let latencies = report.timings();
+    let active_timings = report.active_timings();
+    let suspend_timings = report.suspend_timings();
+
+    assert_eq!(active_timings.len(), latencies.len());
+    assert_eq!(suspend_timings.len(), latencies.len());
+
+    let waiting_total = max_by_name(latencies, "waiting");
+    let waiting_active = max_by_name(active_timings, "waiting");
+    assert!(waiting_total >= 20_000, "waiting_total={waiting_total}");
+    assert!(
+        waiting_active < waiting_total / 2,
+        "waiting_active={waiting_active}, waiting_total={waiting_total}"
+    );
+
+    let waiting_suspend = max_by_name(suspend_timings, "waiting");
+    assert!(
+        waiting_suspend >= 20_000 && waiting_suspend <= waiting_total,
+        "waiting_suspend={waiting_suspend}, waiting_total={waiting_total}"
+    );
+
+    let busy_total = max_by_name(latencies, "busy");
+    let busy_active = max_by_name(active_timings, "busy");
+    assert!(busy_active >= 10_000, "busy_active={busy_active}");
+    assert!(
+        busy_active <= busy_total,
+        "busy_active={busy_active}, busy_total={busy_total}"
+    );
+    let busy_suspend = max_by_name(suspend_timings, "busy");
+    assert!(
+        busy_suspend < busy_total / 10,
+        "busy_suspend={busy_suspend}, busy_total={busy_total}"
+    );
+
+    // Each span's active and suspended times add up to its latency, up to histogram precision.
+    for (sg, timing) in latencies.iter() {
+        let total = timing.mean();
+        let active = active_timings.get(sg).unwrap().mean();
+        let suspend = suspend_timings.get(sg).unwrap().mean();
+        assert!(
+            (active + suspend - total).abs() <= total * 0.01 + 2.0,
+            "{}: active={active}, suspend={suspend}, total={total}",
+            sg.name()
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use latency_trace::LatencyTrace;
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        trace_span!("a").in_scope(|| {
+            trace_span!("aa").in_scope(|| {});
+        });
+        trace_span!("b").in_scope(|| {});
+        trace_span!("b").in_scope(|| {});
+    });
+}
+
+#[test]
+fn test_aggregate_by_depth() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    let by_depth = timings.aggregate_by_depth();
+    assert_eq!(by_depth.keys().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert_eq!(by_depth[&1].len(), 1);
+    assert_eq!(by_depth[&2].len(), 3);
+    assert_eq!(by_depth[&3].len(), 1);
+    assert!(!timings.aggregator_is_consistent(|sg| sg.depth()));
+}
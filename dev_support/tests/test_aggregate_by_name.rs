@@ -0,0 +1,35 @@
+use latency_trace::LatencyTrace;
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..3 {
+            trace_span!("work").in_scope(|| {});
+        }
+        trace_span!("work").in_scope(|| {});
+    });
+}
+
+#[test]
+fn test_aggregate_by_name() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+    assert_eq!(timings.len(), 3);
+
+    // The two `work` callsites are aggregated together.
+    let by_name = timings.aggregate_by_name();
+    assert_eq!(by_name.len(), 2);
+    assert_eq!(by_name["work"].len(), 4);
+    assert!(!timings.aggregator_is_consistent(|sg| sg.name()));
+
+    let by_code_line = timings.aggregate_by_code_line();
+    assert_eq!(by_code_line.len(), 3);
+    let mut work_counts: Vec<u64> = timings
+        .iter()
+        .filter(|(sg, _)| sg.name() == "work")
+        .map(|(sg, _)| by_code_line[sg.code_line()].len())
+        .collect();
+    work_counts.sort();
+    assert_eq!(work_counts, vec![1, 3]);
+}
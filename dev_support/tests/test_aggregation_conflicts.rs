@@ -0,0 +1,30 @@
+use latency_trace::LatencyTrace;
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        trace_span!("work").in_scope(|| {});
+        trace_span!("work").in_scope(|| {});
+        trace_span!("other").in_scope(|| {});
+    });
+}
+
+#[test]
+fn test_aggregation_conflicts() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    // The two `work` callsites conflict when aggregating by name.
+    let conflicts = timings.aggregation_conflicts(|sg| sg.name());
+    assert_eq!(conflicts.len(), 1);
+    let work = &conflicts["work"];
+    assert_eq!(work.len(), 2);
+    assert!(work.iter().all(|sg| sg.name() == "work"));
+    assert_ne!(work[0].code_line(), work[1].code_line());
+
+    // Aggregating by code line is consistent.
+    let conflicts = timings.aggregation_conflicts(|sg| sg.code_line().to_owned());
+    assert!(conflicts.is_empty());
+    assert!(timings.aggregator_is_consistent(|sg| sg.code_line().to_owned()));
+}
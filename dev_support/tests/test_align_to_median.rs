@@ -0,0 +1,58 @@
+use latency_trace::{LatencyTrace, Timings};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("anchor").in_scope(|| {
+        trace_span!("work").in_scope(|| {});
+    });
+}
+
+/// Replaces the recorded values of each span group with `offset + base + i` for `i` in `0..100`, where `base`
+/// depends on the span group.
+fn synthetic(timings: &Timings, offset: u64) -> Timings {
+    let mut timings = timings.clone();
+    for (sg, timing) in timings.iter_mut() {
+        let base = if sg.name() == "anchor" { 1_000 } else { 3_000 };
+        timing.reset();
+        for i in 0..100 {
+            timing.record(offset + base + i).unwrap();
+        }
+    }
+    timings
+}
+
+fn median(timings: &Timings, name: &str) -> u64 {
+    let (_, timing) = timings.iter().find(|(sg, _)| sg.name() == name).unwrap();
+    timing.value_at_quantile(0.5)
+}
+
+#[test]
+fn test_align_to_median() {
+    let latencies = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+    let anchor_id = latencies
+        .keys()
+        .find(|sg| sg.name() == "anchor")
+        .unwrap()
+        .id()
+        .to_owned();
+
+    let reference = synthetic(&latencies, 0);
+    let offset_run = synthetic(&latencies, 5_000);
+    assert!(median(&offset_run, "work") > median(&reference, "work") + 4_000);
+
+    let aligned = offset_run.align_to_median(&reference, &anchor_id).unwrap();
+    assert_eq!(median(&aligned, "anchor"), median(&reference, "anchor"));
+
+    let work_diff = median(&aligned, "work").abs_diff(median(&reference, "work"));
+    assert!(
+        work_diff <= median(&reference, "work") / 50,
+        "work medians should be aligned within histogram precision: diff={work_diff}"
+    );
+    for (sg, timing) in aligned.iter() {
+        assert_eq!(timing.len(), offset_run.get(sg).unwrap().len());
+    }
+
+    assert!(offset_run.align_to_median(&reference, "unknown").is_none());
+}
@@ -0,0 +1,45 @@
+use latency_trace::LatencyTrace;
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        trace_span!("a").in_scope(|| {
+            trace_span!("aa").in_scope(|| {});
+        });
+        trace_span!("b").in_scope(|| {});
+    });
+}
+
+#[test]
+fn test_ancestors_children() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    let by_name = |name: &str| {
+        timings
+            .span_groups()
+            .find(|sg| sg.name() == name)
+            .unwrap()
+            .clone()
+    };
+    let names =
+        |sgs: Vec<latency_trace::SpanGroup>| sgs.iter().map(|sg| sg.name()).collect::<Vec<_>>();
+
+    assert_eq!(names(timings.ancestors(&by_name("aa"))), ["a", "root"]);
+    assert!(timings.ancestors(&by_name("root")).is_empty());
+
+    let mut root_children = names(timings.children(&by_name("root")));
+    root_children.sort();
+    assert_eq!(root_children, ["a", "b"]);
+    assert_eq!(names(timings.children(&by_name("a"))), ["aa"]);
+    assert!(timings.children(&by_name("aa")).is_empty());
+
+    // Ancestors stop at the first one that is not in the timings.
+    let mut orphaned = timings.clone();
+    orphaned.retain_groups(|sg| sg.name() != "a");
+    assert!(orphaned.ancestors(&by_name("aa")).is_empty());
+    let mut without_root = timings.clone();
+    without_root.retain_groups(|sg| sg.name() != "root");
+    assert_eq!(names(without_root.ancestors(&by_name("aa"))), ["a"]);
+}
@@ -0,0 +1,46 @@
+use async_std::task;
+use latency_trace::LatencyTrace;
+use std::time::Duration;
+use tracing::{info_span, trace_span, Instrument};
+
+const NTASKS: u64 = 4;
+const NREPEATS: u64 = 5;
+
+async fn f() {
+    let handles = (0..NTASKS).map(|i| {
+        task::spawn(
+            async {
+                for _ in 0..NREPEATS {
+                    task::sleep(Duration::from_millis(1))
+                        .instrument(trace_span!("sleep"))
+                        .await;
+                }
+            }
+            .instrument(info_span!("root", task = i)),
+        )
+    });
+    for handle in handles.collect::<Vec<_>>() {
+        handle.await;
+    }
+}
+
+#[test]
+fn test_async_std() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies_async_std(f);
+
+    let count = |name: &str| -> u64 {
+        timings
+            .iter()
+            .filter(|(sg, _)| sg.name() == name)
+            .map(|(_, timing)| timing.len())
+            .sum()
+    };
+    assert_eq!(count("root"), NTASKS);
+    assert_eq!(count("sleep"), NTASKS * NREPEATS);
+    assert!(timings
+        .iter()
+        .filter(|(sg, _)| sg.name() == "sleep")
+        .all(|(_, timing)| timing.min() >= 1000));
+}
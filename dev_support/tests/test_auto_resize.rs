@@ -0,0 +1,39 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..5 {
+            trace_span!("fast").in_scope(|| {});
+        }
+        // Exceeds `hist_high`.
+        for _ in 0..3 {
+            trace_span!("slow").in_scope(|| thread::sleep(Duration::from_millis(2)));
+        }
+    });
+}
+
+#[test]
+fn test_auto_resize() {
+    assert!(LatencyTraceCfg::default().auto_resize());
+
+    let cfg = LatencyTraceCfg::default()
+        .with_hist_high(1000)
+        .with_auto_resize(false);
+    let report = LatencyTrace::activated(cfg).unwrap().measure_report(f);
+    let timings = report.timings();
+    let overflow_counts = report.overflow_counts();
+
+    for (sg, timing) in timings.iter() {
+        assert_eq!(timing.high(), 1000, "{}", sg.name());
+        let overflows = overflow_counts.get(sg).copied().unwrap_or(0);
+        match sg.name() {
+            "root" => assert_eq!((timing.len(), overflows), (0, 1)),
+            "fast" => assert_eq!((timing.len(), overflows), (5, 0)),
+            "slow" => assert_eq!((timing.len(), overflows), (0, 3)),
+            name => panic!("unexpected span group {name}"),
+        }
+    }
+    assert_eq!(overflow_counts.len(), 2);
+}
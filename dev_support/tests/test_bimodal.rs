@@ -0,0 +1,71 @@
+use hdrhistogram::Histogram;
+use latency_trace::{LatencyTrace, TimeUnit, Timing};
+use std::{thread, time::Duration};
+use tracing::{info_span, instrument};
+
+fn new_timing() -> Timing {
+    Timing::new(
+        Histogram::<u64>::new_with_bounds(1, 20 * 1000 * 1000, 2).unwrap(),
+        TimeUnit::Micros,
+    )
+}
+
+#[instrument(level = "trace")]
+fn cached_lookup(hit: bool) {
+    if hit {
+        // Simulated cache hit
+        thread::sleep(Duration::from_micros(100));
+    } else {
+        // Simulated cache miss
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..10 {
+            cached_lookup(true);
+            cached_lookup(false);
+        }
+    });
+}
+
+#[test]
+fn test_bimodal() {
+    // Synthetic bimodal histogram: half the values around 100, half around 10,000.
+    {
+        let mut timing = new_timing();
+        for i in 0..50 {
+            timing.record(100 + i).unwrap();
+            timing.record(10_000 + 10 * i).unwrap();
+        }
+        assert!(timing.is_bimodal(), "bimodal timing");
+    }
+
+    // Synthetic unimodal histogram: values spread around a single peak.
+    {
+        let mut timing = new_timing();
+        for i in 1..=100_u64 {
+            let n = 50 - i.abs_diff(50);
+            timing.record_n(1_000 + 10 * i, n + 1).unwrap();
+        }
+        assert!(!timing.is_bimodal(), "unimodal timing");
+    }
+
+    // Empty histogram.
+    assert!(!new_timing().is_bimodal(), "empty timing");
+
+    // Span groups.
+    {
+        let latencies = LatencyTrace::activated_default()
+            .unwrap()
+            .measure_latencies(f);
+
+        let bimodal_names: Vec<&str> = latencies
+            .bimodal_groups()
+            .into_iter()
+            .map(|sg| sg.name())
+            .collect();
+        assert_eq!(bimodal_names, vec!["cached_lookup"]);
+    }
+}
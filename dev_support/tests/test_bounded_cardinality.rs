@@ -0,0 +1,52 @@
+use latency_trace::{
+    bounded_cardinality, group_by_given_fields, LatencyTrace, LatencyTraceCfg, OVERFLOW_PROP,
+};
+use tracing::info_span;
+
+fn request(user_id: u64) {
+    info_span!("request", user_id).in_scope(|| {});
+}
+
+fn f() {
+    for user_id in 0..10 {
+        request(user_id);
+    }
+    // Admitted values keep their span groups after the bound is reached.
+    request(1);
+    // Spans without properties are not capped.
+    info_span!("health").in_scope(|| {});
+}
+
+#[test]
+fn test_bounded_cardinality() {
+    let grouper = bounded_cardinality(group_by_given_fields(&["user_id"]), 3, "other");
+    let cfg = LatencyTraceCfg::default().with_span_grouper(grouper);
+    let timings = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+
+    let health = timings
+        .span_groups()
+        .find(|sg| sg.name() == "health")
+        .unwrap();
+    assert!(health.props().is_empty());
+
+    let mut counts: Vec<(String, String, u64)> = timings
+        .iter()
+        .filter(|(sg, _)| sg.name() == "request")
+        .map(|(sg, timing)| {
+            let (k, v) = sg.props()[0].clone();
+            (k, v, timing.len())
+        })
+        .collect();
+    counts.sort();
+
+    let prop = |k: &str, v: &str, n: u64| (k.to_owned(), v.to_owned(), n);
+    assert_eq!(
+        counts,
+        vec![
+            prop(OVERFLOW_PROP, "other", 7),
+            prop("user_id", "0", 1),
+            prop("user_id", "1", 2),
+            prop("user_id", "2", 1),
+        ]
+    );
+}
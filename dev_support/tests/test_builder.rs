@@ -0,0 +1,36 @@
+use latency_trace::{group_by_given_fields, LatencyTrace, TimeUnit};
+use tracing::{info_span, trace_span, Level};
+
+fn f() {
+    for i in 0..4 {
+        info_span!("root", parity = i % 2).in_scope(|| {
+            trace_span!("child").in_scope(|| {});
+        });
+    }
+}
+
+#[test]
+fn test_builder() {
+    let builder = LatencyTrace::builder()
+        .time_unit(TimeUnit::Nanos)
+        .hist_high(60_000_000_000)
+        .sigfig(3)
+        .span_grouper(group_by_given_fields(&["parity"]))
+        .min_level(Level::INFO)
+        .cfg(|cfg| cfg.with_record_poll_counts(true));
+
+    let cfg = builder.clone().build_cfg();
+    assert_eq!(cfg.time_unit(), TimeUnit::Nanos);
+    assert_eq!(cfg.hist_high(), 60_000_000_000);
+    assert_eq!(cfg.hist_sigfig(), 3);
+    assert_eq!(cfg.min_level(), Level::INFO);
+    assert!(cfg.record_poll_counts());
+
+    // Only the root spans are at the `info` level.
+    let timings = builder.measure_latencies(f).unwrap();
+    assert_eq!(timings.len(), 2);
+    for (sg, timing) in timings.iter() {
+        assert_eq!(sg.name(), "root");
+        assert_eq!(timing.len(), 2);
+    }
+}
@@ -0,0 +1,21 @@
+use latency_trace::LatencyTrace;
+
+#[test]
+fn test_calibrate() {
+    let estimates: Vec<f64> = (0..3)
+        .map(|_| {
+            let estimate = LatencyTrace::calibrate();
+            assert!(estimate.spans_measured() > 0);
+            estimate.per_span_nanos()
+        })
+        .collect();
+
+    for nanos in &estimates {
+        assert!(*nanos > 0.0, "estimates={estimates:?}");
+    }
+
+    // Estimates are medians across batches, so they should be of the same order of magnitude.
+    let min = estimates.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = estimates.iter().copied().fold(0.0, f64::max);
+    assert!(max <= 5.0 * min, "estimates={estimates:?}");
+}
@@ -0,0 +1,75 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::path::Path;
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root", foo = 1, bar = 2).in_scope(|| {
+        trace_span!("child", foo = 3, bar = 4).in_scope(|| {});
+    });
+}
+
+#[test]
+fn test_cfg_from_reader() {
+    // Empty config is the default config.
+    {
+        let cfg = LatencyTraceCfg::from_reader("{}".as_bytes()).unwrap();
+        let default = LatencyTraceCfg::default();
+        assert_eq!(cfg.hist_high(), default.hist_high());
+        assert_eq!(cfg.hist_sigfig(), default.hist_sigfig());
+        assert_eq!(cfg.source_root(), None);
+        assert!(!cfg.separate_panicked());
+        assert_eq!(cfg.max_groups(), None);
+    }
+
+    // `max_groups` overrides or removes the cap of the preset.
+    {
+        let cfg = LatencyTraceCfg::from_reader(r#"{ "max_groups": 500 }"#.as_bytes()).unwrap();
+        assert_eq!(cfg.max_groups(), Some(500));
+        let json = r#"{ "preset": "server", "max_groups": null }"#;
+        let cfg = LatencyTraceCfg::from_reader(json.as_bytes()).unwrap();
+        assert_eq!(cfg.max_groups(), None);
+    }
+
+    // Server preset with overrides.
+    {
+        let json = r#"{
+            "preset": "server",
+            "hist_sigfig": 3,
+            "source_root": "/home/me/my_project",
+            "span_grouper": { "kind": "given_fields", "fields": ["foo"] }
+        }"#;
+        let cfg = LatencyTraceCfg::from_reader(json.as_bytes()).unwrap();
+        assert_eq!(
+            cfg.hist_high(),
+            LatencyTraceCfg::server_preset().hist_high()
+        );
+        assert_eq!(cfg.hist_sigfig(), 3);
+        assert_eq!(cfg.source_root(), Some(Path::new("/home/me/my_project")));
+        assert!(cfg.separate_panicked());
+        assert_eq!(cfg.max_groups(), Some(1000));
+
+        let latencies = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+        for span_group in latencies.keys() {
+            let props = span_group.props();
+            assert_eq!(props.len(), 1, "only the given field is used for grouping");
+            assert_eq!(props[0].0, "foo");
+        }
+    }
+
+    // Invalid configs.
+    {
+        let invalid = [
+            r#"{ "preset": "unknown" }"#,
+            r#"{ "span_grouper": { "kind": "unknown" } }"#,
+            r#"{ "unknown_setting": 1 }"#,
+            r#"{ "hist_high": "high" }"#,
+            "not json",
+        ];
+        for json in invalid {
+            assert!(
+                LatencyTraceCfg::from_reader(json.as_bytes()).is_err(),
+                "invalid config: {json}"
+            );
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use latency_trace::{checkpoint, LatencyTrace};
+use std::{thread, time::Duration};
+use tracing::info_span;
+
+fn f() {
+    info_span!("work").in_scope(|| {
+        thread::sleep(Duration::from_millis(2));
+        checkpoint("parse");
+        thread::sleep(Duration::from_millis(4));
+        checkpoint("execute");
+    });
+
+    // Ignored because there is no current span.
+    checkpoint("orphan");
+}
+
+#[test]
+fn test_checkpoint() {
+    let latencies = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    assert_eq!(latencies.len(), 3, "{:?}", latencies.keys());
+
+    let work = latencies.keys().find(|sg| sg.name() == "work").unwrap();
+
+    for (name, min_micros) in [("parse", 2000), ("execute", 4000)] {
+        let (sg, timing) = latencies
+            .iter()
+            .find(|(sg, _)| sg.props() == [("checkpoint".to_owned(), name.to_owned())])
+            .unwrap();
+        assert_eq!(sg.name(), "checkpoint");
+        assert_eq!(sg.parent_id(), Some(work.id()));
+        assert_eq!(sg.depth(), work.depth() + 1);
+        assert_eq!(timing.len(), 1);
+        // `max` is the highest value equivalent to the recorded value in the histogram.
+        assert!(timing.max() >= min_micros, "{name}: {}", timing.max());
+    }
+}
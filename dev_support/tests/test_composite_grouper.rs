@@ -0,0 +1,50 @@
+use latency_trace::{composite_grouper, FieldMap, LatencyTrace, LatencyTraceCfg};
+use tracing::info_span;
+
+fn request(method: &str, path: &str, cache_hit: bool) {
+    let cache = if cache_hit { "hit" } else { "miss" };
+    info_span!("request", method, path, cache, attempt = 1).in_scope(|| {});
+}
+
+fn f() {
+    request("GET", "/users", false);
+    request("GET", "/users", true);
+    request("GET", "/users", false);
+    request("POST", "/users", false);
+}
+
+fn label(fields: &FieldMap) -> Vec<(String, String)> {
+    let field = |name: &str| fields.get(name).map(String::as_str).unwrap_or("?");
+    let label = format!(
+        "{} {} [cache={}]",
+        field("method"),
+        field("path"),
+        field("cache")
+    );
+    vec![("label".to_owned(), label)]
+}
+
+#[test]
+fn test_composite_grouper() {
+    let cfg = LatencyTraceCfg::default().with_span_grouper(composite_grouper(label));
+    let latencies = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+
+    let mut counts: Vec<(&str, u64)> = latencies
+        .iter()
+        .map(|(sg, timing)| {
+            assert_eq!(sg.props().len(), 1);
+            assert_eq!(sg.props()[0].0, "label");
+            (sg.props()[0].1.as_str(), timing.len())
+        })
+        .collect();
+    counts.sort();
+
+    assert_eq!(
+        counts,
+        vec![
+            ("GET /users [cache=hit]", 1),
+            ("GET /users [cache=miss]", 2),
+            ("POST /users [cache=miss]", 1),
+        ]
+    );
+}
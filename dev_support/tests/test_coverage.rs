@@ -0,0 +1,30 @@
+use latency_trace::{testing::measure_latencies_isolated, LatencyTraceCfg};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..2 {
+            trace_span!("twice_a").in_scope(|| {});
+            trace_span!("twice_b").in_scope(|| {});
+        }
+        trace_span!("once").in_scope(|| {});
+    });
+}
+
+#[test]
+fn test_coverage() {
+    // All callsites recorded latencies.
+    let latencies = measure_latencies_isolated(LatencyTraceCfg::default(), f);
+    assert_eq!(latencies.group_count(), 4);
+    assert_eq!(latencies.coverage(), 1.0);
+
+    // Only the callsites reached twice record the second occurrence, so "root" and "once" record nothing.
+    let latencies =
+        measure_latencies_isolated(LatencyTraceCfg::default().with_record_only_nth(2), f);
+    assert_eq!(latencies.group_count(), 4);
+    assert_eq!(latencies.coverage(), 0.5);
+
+    // Nothing measured.
+    let latencies = measure_latencies_isolated(LatencyTraceCfg::default(), || {});
+    assert_eq!(latencies.coverage(), 0.0);
+}
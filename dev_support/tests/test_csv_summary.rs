@@ -0,0 +1,54 @@
+use latency_trace::{group_by_all_fields, summary_stats, LatencyTrace, LatencyTraceCfg};
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    for i in 0..2 {
+        info_span!("root", i, note = "a,b").in_scope(|| {
+            trace_span!("child").in_scope(|| thread::sleep(Duration::from_millis(1)));
+        });
+    }
+}
+
+#[test]
+fn test_csv_summary() {
+    let cfg = LatencyTraceCfg::default().with_span_grouper(group_by_all_fields);
+    let timings = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+
+    let csv = timings.to_csv_summary();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        "name,id,parent_id,depth,code_line,props,count,min,max,mean,median,p95,p99"
+    );
+    let rows: Vec<&str> = lines.collect();
+    assert_eq!(rows.len(), timings.len());
+
+    for ((sg, timing), row) in timings.iter().zip(&rows) {
+        let stats = summary_stats(timing);
+        assert!(
+            row.starts_with(&format!("{},{},", sg.name(), sg.id())),
+            "row={row}"
+        );
+        assert!(
+            row.ends_with(&format!(
+                ",{},{},{},{:.1},{},{},{}",
+                stats.count, stats.min, stats.max, stats.mean, stats.median, stats.p95, stats.p99
+            )),
+            "row={row}"
+        );
+        match sg.parent_id() {
+            Some(parent_id) => assert!(row.contains(&format!(",{parent_id},{},", sg.depth()))),
+            None => assert!(row.contains(&format!(",,{},", sg.depth()))),
+        }
+    }
+
+    // Props are rendered in a single column, quoted as needed.
+    for i in 0..2 {
+        let props_field = format!(",\"i={i};note=\"\"a,b\"\"\",");
+        assert!(
+            rows.iter().any(|row| row.contains(&props_field)),
+            "props_field={props_field}"
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use latency_trace::LatencyTrace;
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        trace_span!("level_2").in_scope(|| {
+            trace_span!("level_3").in_scope(|| {
+                trace_span!("deepest_a").in_scope(|| {});
+                trace_span!("deepest_b").in_scope(|| {});
+            });
+        });
+        trace_span!("shallow").in_scope(|| {});
+    });
+}
+
+#[test]
+fn test_deepest_groups() {
+    let latencies = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    assert_eq!(latencies.max_depth(), 4);
+
+    let mut deepest_names: Vec<&str> = latencies
+        .deepest_groups()
+        .into_iter()
+        .map(|sg| sg.name())
+        .collect();
+    deepest_names.sort();
+    assert_eq!(deepest_names, vec!["deepest_a", "deepest_b"]);
+}
@@ -0,0 +1,49 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg, TimeUnit};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..10 {
+            trace_span!("child_span").in_scope(|| {});
+        }
+    });
+}
+
+#[test]
+fn test_display_summary() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    let summary = timings.display_summary().to_string();
+    let lines: Vec<&str> = summary.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(
+        lines[0].starts_with("name        count  mean(µs)"),
+        "{summary}"
+    );
+
+    // Columns are aligned, so all lines have the same width.
+    let width = lines[0].chars().count();
+    assert!(
+        lines.iter().all(|line| line.chars().count() == width),
+        "{summary}"
+    );
+
+    // Rows are in the order of `timings`.
+    for (line, (sg, timing)) in lines[1..].iter().zip(timings.iter()) {
+        let cells: Vec<&str> = line.split_whitespace().collect();
+        assert_eq!(cells[0], sg.name());
+        assert_eq!(cells[1], timing.len().to_string());
+        assert_eq!(cells[6], timing.max().to_string());
+    }
+
+    // The unit is taken from the timings.
+    let timings = LatencyTrace::with_scoped(
+        LatencyTraceCfg::default().with_time_unit(TimeUnit::Nanos),
+        f,
+    )
+    .unwrap();
+    let summary = timings.display_summary().to_string();
+    assert!(summary.contains("mean(ns)"), "{summary}");
+}
@@ -0,0 +1,42 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use tracing::{info_span, instrument, trace_span};
+
+#[instrument(level = "trace")]
+fn inner() {}
+
+#[instrument(level = "info")]
+fn run() {
+    trace_span!("a").in_scope(inner);
+    trace_span!("b").in_scope(|| {});
+}
+
+#[test]
+fn test_drop_root() {
+    let latencies = LatencyTrace::activated(LatencyTraceCfg::default().with_drop_root(true))
+        .unwrap()
+        .measure_latencies(|| {
+            run();
+            info_span!("other_root").in_scope(|| {});
+        });
+
+    // Both roots are dropped.
+    let mut names: Vec<&str> = latencies.span_groups().map(|sg| sg.name()).collect();
+    names.sort();
+    assert_eq!(names, vec!["a", "b", "inner"]);
+
+    for sg in latencies.span_groups() {
+        match sg.name() {
+            "a" | "b" => {
+                assert_eq!(sg.depth(), 1);
+                assert_eq!(sg.parent_id(), None);
+            }
+            "inner" => {
+                let a = latencies.span_groups().find(|sg| sg.name() == "a").unwrap();
+                assert_eq!(sg.depth(), 2);
+                assert_eq!(sg.parent_id(), Some(a.id()));
+            }
+            _ => unreachable!(),
+        }
+    }
+    assert_eq!(latencies.max_depth(), 2);
+}
@@ -0,0 +1,52 @@
+use latency_trace::{DropTimer, LatencyTrace};
+use std::{thread, time::Duration};
+use tracing::info_span;
+
+struct Connection;
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        // Simulated connection teardown
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+struct Client {
+    _conn: DropTimer<Connection>,
+}
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..3 {
+            let client = Client {
+                _conn: DropTimer::new("conn", Connection),
+            };
+            drop(client);
+        }
+
+        // Unwrapped values are not recorded.
+        let conn = DropTimer::new("unwrapped", Connection).into_inner();
+        drop(conn);
+    });
+}
+
+#[test]
+fn test_drop_timer() {
+    let latencies = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    let drop_groups: Vec<_> = latencies
+        .span_groups()
+        .filter(|sg| sg.name() == "drop")
+        .collect();
+    assert_eq!(drop_groups.len(), 1);
+
+    let sg = drop_groups[0];
+    assert_eq!(sg.props(), &[("drop".to_owned(), "conn".to_owned())]);
+    assert_eq!(sg.depth(), 2);
+
+    let timing = &latencies[sg];
+    assert_eq!(timing.len(), 3);
+    assert!(timing.max() >= 10_000, "max={}", timing.max());
+}
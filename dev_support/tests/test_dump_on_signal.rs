@@ -0,0 +1,53 @@
+#![cfg(unix)]
+
+use latency_trace::LatencyTrace;
+use signal_hook::{consts::SIGUSR1, low_level::raise};
+use std::{sync::mpsc, thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+const NREPEATS: u64 = 30;
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..NREPEATS {
+            trace_span!("step").in_scope(|| thread::sleep(Duration::from_millis(10)));
+        }
+    });
+}
+
+#[test]
+fn test_dump_on_signal() {
+    let probed = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies_probed(f)
+        .unwrap();
+
+    let (tx, rx) = mpsc::channel();
+    probed
+        .dump_on_signal(SIGUSR1, move |timings| {
+            tx.send(timings.clone()).unwrap();
+        })
+        .unwrap();
+
+    thread::sleep(Duration::from_millis(100));
+    raise(SIGUSR1).unwrap();
+
+    let dumped = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    let dumped_steps = dumped
+        .iter()
+        .find(|(sg, _)| sg.name() == "step")
+        .map(|(_, timing)| timing.len())
+        .unwrap();
+    assert!(
+        dumped_steps > 0 && dumped_steps < NREPEATS,
+        "dumped_steps={dumped_steps}"
+    );
+
+    let timings = probed.wait_and_report();
+    let steps = timings
+        .iter()
+        .find(|(sg, _)| sg.name() == "step")
+        .map(|(_, timing)| timing.len())
+        .unwrap();
+    assert_eq!(steps, NREPEATS);
+}
@@ -0,0 +1,52 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::{
+    thread,
+    time::{Duration, SystemTime},
+};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        thread::sleep(Duration::from_millis(5));
+        trace_span!("early").in_scope(|| {});
+        thread::sleep(Duration::from_millis(20));
+        trace_span!("late").in_scope(|| {});
+    });
+}
+
+#[test]
+fn test_epoch_offsets() {
+    let epoch = SystemTime::now();
+    let cfg = LatencyTraceCfg::default().with_epoch(epoch);
+    assert_eq!(cfg.epoch(), Some(epoch));
+
+    let report = LatencyTrace::activated(cfg).unwrap().measure_report(f);
+    let latencies = report.timings();
+    let epoch_offsets = report.epoch_offsets();
+
+    assert_eq!(epoch_offsets.len(), latencies.len());
+    let start = |name: &str| {
+        let timing = epoch_offsets
+            .iter()
+            .find(|(sg, _)| sg.name() == name)
+            .map(|(_, timing)| timing)
+            .unwrap();
+        assert_eq!(timing.len(), 1);
+        timing.max()
+    };
+
+    // Spans created later have larger start offsets.
+    assert!(
+        start("root") < start("early"),
+        "root={}, early={}",
+        start("root"),
+        start("early")
+    );
+    assert!(
+        start("early") < start("late"),
+        "early={}, late={}",
+        start("early"),
+        start("late")
+    );
+    assert!(start("late") >= 25_000, "late={}", start("late"));
+}
@@ -0,0 +1,37 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::collections::BTreeMap;
+use tracing::{info, info_span, trace_span, warn, Level};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        info!("start");
+        for i in 0..4 {
+            trace_span!("child").in_scope(|| {
+                if i % 2 == 0 {
+                    warn!("even");
+                }
+            });
+        }
+    });
+    // Events outside of spans are not counted.
+    warn!("outside");
+}
+
+#[test]
+fn test_event_counts() {
+    let cfg = LatencyTraceCfg::default().with_event_counting(true);
+    assert!(cfg.event_counting());
+    let report = LatencyTrace::activated(cfg).unwrap().measure_report(f);
+    let (timings, event_counts) = (report.timings(), report.event_counts());
+
+    assert_eq!(timings.len(), 2);
+    assert_eq!(event_counts.len(), 2);
+    for (sg, counts) in event_counts.iter() {
+        let expected = match sg.name() {
+            "root" => BTreeMap::from([(Level::INFO, 1)]),
+            "child" => BTreeMap::from([(Level::WARN, 2)]),
+            name => panic!("unexpected span group {name}"),
+        };
+        assert_eq!(counts, &expected);
+    }
+}
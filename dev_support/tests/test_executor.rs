@@ -0,0 +1,77 @@
+use futures::channel::oneshot;
+use latency_trace::{BlockingExecutor, Executor, LatencyTrace};
+use std::{future::Future, thread, time::Duration};
+use tracing::{info_span, trace_span, Instrument};
+
+/// [`Executor`] for a caller-built runtime, adapting its `block_on` method.
+struct RuntimeExecutor(tokio::runtime::Runtime);
+
+impl Executor for RuntimeExecutor {
+    fn block_on<F>(&self, f: F)
+    where
+        F: Future<Output = ()>,
+    {
+        self.0.block_on(f);
+    }
+}
+
+async fn f() {
+    async {
+        // Pending until woken from another thread.
+        let (tx, rx) = oneshot::channel();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            tx.send(()).unwrap();
+        });
+        rx.await.unwrap();
+
+        trace_span!("inner").in_scope(|| {});
+    }
+    .instrument(info_span!("root"))
+    .await;
+}
+
+fn names(latencies: &latency_trace::Timings) -> Vec<&str> {
+    let mut names: Vec<&str> = latencies.span_groups().map(|sg| sg.name()).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_executor() {
+    let lt = LatencyTrace::activated_default().unwrap();
+
+    {
+        let latencies = lt.measure_latencies_with(&BlockingExecutor, f);
+        assert_eq!(names(&latencies), vec!["inner", "root"]);
+        for timing in latencies.values() {
+            assert_eq!(timing.len(), 1);
+        }
+        let (_, root_timing) = latencies
+            .iter()
+            .find(|(sg, _)| sg.name() == "root")
+            .unwrap();
+        // `max` is the highest value equivalent to the recorded value in the histogram.
+        assert!(root_timing.max() >= 2000, "root max={}", root_timing.max());
+    }
+
+    {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let latencies = lt.measure_latencies_with(&RuntimeExecutor(rt), f);
+        assert_eq!(names(&latencies), vec!["inner", "root"]);
+        for timing in latencies.values() {
+            assert_eq!(timing.len(), 1);
+        }
+    }
+
+    {
+        let probed = lt
+            .measure_latencies_probed_with(BlockingExecutor, f)
+            .unwrap();
+        let latencies = probed.wait_and_report();
+        assert_eq!(names(&latencies), vec!["inner", "root"]);
+    }
+}
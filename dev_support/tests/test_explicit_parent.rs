@@ -0,0 +1,42 @@
+use latency_trace::{LatencyTrace, SpanGroup, Timings};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    let other = info_span!("other");
+    info_span!("contextual").in_scope(|| {
+        trace_span!(parent: &other, "explicit_child").in_scope(|| {
+            trace_span!("grandchild").in_scope(|| {});
+        });
+        trace_span!(parent: None, "explicit_root").in_scope(|| {});
+        trace_span!("contextual_child").in_scope(|| {});
+    });
+}
+
+fn group<'a>(latencies: &'a Timings, name: &str) -> &'a SpanGroup {
+    let mut groups = latencies.keys().filter(|sg| sg.name() == name);
+    let sg = groups.next().unwrap();
+    assert!(groups.next().is_none(), "single group named {name}");
+    sg
+}
+
+#[test]
+fn test_explicit_parent() {
+    let latencies = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    assert_eq!(latencies.len(), 6);
+
+    let other = group(&latencies, "other");
+    let contextual = group(&latencies, "contextual");
+    let explicit_child = group(&latencies, "explicit_child");
+    let grandchild = group(&latencies, "grandchild");
+    let explicit_root = group(&latencies, "explicit_root");
+    let contextual_child = group(&latencies, "contextual_child");
+
+    assert_eq!(explicit_child.parent_id(), Some(other.id()));
+    assert_eq!(explicit_child.depth(), 2);
+    assert_eq!(grandchild.parent_id(), Some(explicit_child.id()));
+    assert_eq!(explicit_root.parent_id(), None);
+    assert_eq!(contextual_child.parent_id(), Some(contextual.id()));
+}
@@ -0,0 +1,39 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for i in 0..5 {
+            // Repeated values are sampled once.
+            for _ in 0..2 {
+                trace_span!("request", request_id = i).in_scope(|| {});
+            }
+        }
+    });
+}
+
+#[test]
+fn test_field_samples() {
+    let report = LatencyTrace::activated(LatencyTraceCfg::default().with_sample_fields(3))
+        .unwrap()
+        .measure_report(f);
+    let timings = report.timings();
+    let field_samples = report.field_samples();
+
+    // The default span grouper doesn't group by `request_id`.
+    assert_eq!(timings.group_count(), 2);
+    assert_eq!(field_samples.len(), 2);
+
+    for (sg, samples) in field_samples.iter() {
+        match sg.name() {
+            "root" => assert_eq!(samples, &vec![vec![]]),
+            "request" => {
+                let expected: Vec<Vec<(String, String)>> = (0..3)
+                    .map(|i| vec![("request_id".to_owned(), i.to_string())])
+                    .collect();
+                assert_eq!(samples, &expected);
+            }
+            name => panic!("unexpected span group {name}"),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::thread;
+use tracing::{info_span, trace_span};
+
+const NTHREADS: u64 = 3;
+const NREPEATS: u64 = 10;
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        thread::scope(|s| {
+            for _ in 0..NTHREADS {
+                s.spawn(|| {
+                    for _ in 0..NREPEATS {
+                        trace_span!("work").in_scope(|| {});
+                    }
+                });
+            }
+        });
+        // Buffered when the latencies are reported.
+        trace_span!("last").in_scope(|| {});
+    });
+}
+
+#[test]
+fn test_flush_batch() {
+    assert_eq!(LatencyTraceCfg::default().flush_batch(), None);
+    assert_eq!(
+        LatencyTraceCfg::default().with_flush_batch(0).flush_batch(),
+        Some(1)
+    );
+
+    let cfg = LatencyTraceCfg::default().with_flush_batch(4);
+    let timings = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+
+    assert_eq!(timings.len(), 3);
+    for (sg, timing) in timings.iter() {
+        match sg.name() {
+            "root" | "last" => assert_eq!(timing.len(), 1),
+            "work" => assert_eq!(timing.len(), NTHREADS * NREPEATS),
+            name => panic!("unexpected span group {name}"),
+        }
+    }
+}
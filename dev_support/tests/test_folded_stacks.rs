@@ -0,0 +1,77 @@
+use latency_trace::{group_by_all_fields, FoldedFrame, FoldedValue, LatencyTrace, LatencyTraceCfg};
+use std::{collections::BTreeMap, thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+const NREPEATS: u64 = 4;
+
+fn f() {
+    for _ in 0..NREPEATS {
+        info_span!("root").in_scope(|| {
+            trace_span!("child", kind = "a;b").in_scope(|| {
+                thread::sleep(Duration::from_millis(2));
+                trace_span!("grandchild").in_scope(|| thread::sleep(Duration::from_millis(3)));
+            });
+        });
+    }
+    info_span!("other_root").in_scope(|| thread::sleep(Duration::from_millis(1)));
+}
+
+fn parse(folded: &str) -> BTreeMap<&str, u64> {
+    folded
+        .lines()
+        .map(|line| {
+            let (stack, value) = line.rsplit_once(' ').unwrap();
+            (stack, value.parse().unwrap())
+        })
+        .collect()
+}
+
+#[test]
+fn test_folded_stacks() {
+    let cfg = LatencyTraceCfg::default().with_span_grouper(group_by_all_fields);
+    let timings = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+
+    let folded = timings.to_folded_stacks(FoldedFrame::Name, FoldedValue::Sum, false);
+    let lines = parse(&folded);
+    let grandchild = lines["root;child;grandchild"];
+    let child = lines["root;child"];
+    assert!(grandchild >= NREPEATS * 3_000, "grandchild={grandchild}");
+    assert!(child >= NREPEATS * 2_000, "child={child}");
+    assert!(child < grandchild, "child={child}, grandchild={grandchild}");
+    assert!(lines["other_root"] >= 1_000);
+
+    // The self values add up to the total latency of each root.
+    let root_timing = timings
+        .iter()
+        .find(|(sg, _)| sg.name() == "root")
+        .map(|(_, timing)| timing)
+        .unwrap();
+    let root_sum = root_timing.mean() * root_timing.len() as f64;
+    let stacks_sum: u64 = lines
+        .iter()
+        .filter(|(stack, _)| stack.starts_with("root"))
+        .map(|(_, value)| value)
+        .sum();
+    assert!(
+        (stacks_sum as f64 - root_sum).abs() <= 3.0,
+        "stacks_sum={stacks_sum}, root_sum={root_sum}"
+    );
+
+    let folded = timings.to_folded_stacks(FoldedFrame::Name, FoldedValue::Mean, true);
+    let lines = parse(&folded);
+    let grandchild = lines["root;child[kind=\"a,b\"];grandchild"];
+    assert!(
+        (3_000..6_000).contains(&grandchild),
+        "grandchild={grandchild}"
+    );
+
+    let folded = timings.to_folded_stacks(FoldedFrame::CodeLine, FoldedValue::Sum, false);
+    for stack in parse(&folded).keys() {
+        assert!(
+            stack
+                .split(';')
+                .all(|frame| frame.starts_with("dev_support/tests/test_folded_stacks.rs:")),
+            "stack={stack}"
+        );
+    }
+}
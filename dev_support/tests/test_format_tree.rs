@@ -0,0 +1,41 @@
+use latency_trace::LatencyTrace;
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        trace_span!("a").in_scope(|| {
+            trace_span!("aa").in_scope(|| {});
+        });
+        trace_span!("b").in_scope(|| {});
+    });
+    info_span!("other_root").in_scope(|| {});
+}
+
+#[test]
+fn test_format_tree() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    let tree = timings.format_tree(|sg, timing| format!("{} n={}", sg.name(), timing.len()));
+    let expected = "\
+other_root n=1
+root n=1
+├── a n=1
+│   └── aa n=1
+└── b n=1
+";
+    assert_eq!(tree, expected);
+
+    // Span groups whose parent is not in the timings are rendered as roots.
+    let mut orphaned = timings.clone();
+    orphaned.retain_groups(|sg| sg.name() != "root");
+    let tree = orphaned.format_tree(|sg, timing| format!("{} n={}", sg.name(), timing.len()));
+    let expected = "\
+a n=1
+└── aa n=1
+b n=1
+other_root n=1
+";
+    assert_eq!(tree, expected);
+}
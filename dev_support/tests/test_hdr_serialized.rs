@@ -0,0 +1,45 @@
+use latency_trace::{HdrDeserializationError, LatencyTrace, TimeUnit, Timings};
+use std::collections::BTreeMap;
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..10 {
+            trace_span!("child").in_scope(|| {});
+        }
+    });
+}
+
+#[test]
+fn test_hdr_serialized() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    let serialized = timings.to_hdr_serialized();
+    assert_eq!(serialized.len(), 2);
+
+    let span_groups: Vec<_> = timings.span_groups().cloned().collect();
+    let restored =
+        Timings::from_hdr_serialized(span_groups.clone(), &serialized, TimeUnit::Micros).unwrap();
+    assert_eq!(restored.len(), timings.len());
+    for ((sg, timing), (restored_sg, restored_timing)) in timings.iter().zip(restored.iter()) {
+        assert_eq!(sg, restored_sg);
+        assert_eq!(**timing, **restored_timing);
+    }
+
+    let missing =
+        Timings::from_hdr_serialized(span_groups.clone(), &BTreeMap::new(), TimeUnit::Micros);
+    assert!(matches!(
+        missing,
+        Err(HdrDeserializationError::MissingHistogram { .. })
+    ));
+
+    let mut corrupted = serialized.clone();
+    corrupted.values_mut().for_each(|v| *v = "AAAA".to_owned());
+    let invalid = Timings::from_hdr_serialized(span_groups, &corrupted, TimeUnit::Micros);
+    assert!(matches!(
+        invalid,
+        Err(HdrDeserializationError::InvalidHistogram { .. })
+    ));
+}
@@ -0,0 +1,29 @@
+use latency_trace::{ActivationError, LatencyTrace, LatencyTraceCfg};
+
+fn activation_error(cfg: LatencyTraceCfg) -> String {
+    match LatencyTrace::activated(cfg) {
+        Err(e @ ActivationError::HistogramConfigError { .. }) => e.to_string(),
+        Err(e) => panic!("unexpected error {e:?}"),
+        Ok(_) => panic!("activation should fail"),
+    }
+}
+
+#[test]
+fn test_hist_config_error() {
+    let msg = activation_error(LatencyTraceCfg::default().with_hist_low(0));
+    assert!(msg.contains("hist_low (0)"), "{msg}");
+
+    let msg = activation_error(
+        LatencyTraceCfg::default()
+            .with_hist_low(100)
+            .with_hist_high(100),
+    );
+    assert!(msg.contains("hist_high (100)"), "{msg}");
+    assert!(msg.contains("hist_low (100)"), "{msg}");
+
+    let msg = activation_error(LatencyTraceCfg::default().with_hist_sigfig(6));
+    assert!(msg.contains("hist_sigfig (6)"), "{msg}");
+
+    // The defaults are valid.
+    assert!(LatencyTrace::activated(LatencyTraceCfg::default()).is_ok());
+}
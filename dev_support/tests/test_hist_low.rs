@@ -0,0 +1,29 @@
+use latency_trace::{ActivationError, LatencyTrace, LatencyTraceCfg, TimeUnit};
+use std::{thread, time::Duration};
+use tracing::trace_span;
+
+fn f() {
+    trace_span!("sleep").in_scope(|| thread::sleep(Duration::from_millis(2)));
+}
+
+#[test]
+fn test_hist_low() {
+    assert_eq!(LatencyTraceCfg::default().hist_low(), 1);
+
+    // `hist_high` must be at least twice `hist_low`.
+    let invalid = LatencyTraceCfg::default().with_hist_low(20_000_000);
+    assert!(matches!(
+        LatencyTrace::activated(invalid),
+        Err(ActivationError::HistogramConfigError { .. })
+    ));
+
+    let cfg = LatencyTraceCfg::default()
+        .with_time_unit(TimeUnit::Nanos)
+        .with_hist_low(1000);
+    assert_eq!(cfg.with_time_unit(TimeUnit::Micros).hist_low(), 1);
+
+    let timings = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+    let timing = timings.values().next().unwrap();
+    assert_eq!(timing.low(), 1000);
+    assert!(timing.min() >= 2_000_000, "min={}", timing.min());
+}
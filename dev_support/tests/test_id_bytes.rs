@@ -0,0 +1,41 @@
+use latency_trace::{testing::measure_latencies_isolated, LatencyTraceCfg, Timings};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        trace_span!("child").in_scope(|| {});
+    });
+}
+
+fn id_lens(latencies: &Timings) -> Vec<usize> {
+    latencies.span_groups().map(|sg| sg.id().len()).collect()
+}
+
+#[test]
+fn test_id_bytes() {
+    assert_eq!(LatencyTraceCfg::default().id_bytes(), 8);
+
+    // Base64 encoding with padding: 4 chars per 3 bytes, rounded up.
+    for (id_bytes, expected_len) in [(8, 12), (4, 8), (16, 24), (32, 44), (1, 8), (100, 44)] {
+        let latencies =
+            measure_latencies_isolated(LatencyTraceCfg::default().with_id_bytes(id_bytes), f);
+        assert_eq!(
+            id_lens(&latencies),
+            vec![expected_len; 2],
+            "id_bytes={id_bytes}"
+        );
+    }
+
+    // Root IDs with more bytes extend those with fewer bytes, as both are prefixes of the same hash. This doesn't
+    // hold for child IDs, whose hashes depend on their parent IDs.
+    let root_id = |id_bytes: usize| {
+        let latencies =
+            measure_latencies_isolated(LatencyTraceCfg::default().with_id_bytes(id_bytes), f);
+        let root = latencies
+            .span_groups()
+            .find(|sg| sg.name() == "root")
+            .unwrap();
+        root.id().to_owned()
+    };
+    assert!(root_id(12).starts_with(&root_id(6)));
+}
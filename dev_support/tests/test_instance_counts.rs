@@ -0,0 +1,31 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..10 {
+            trace_span!("inner").in_scope(|| {});
+        }
+    });
+}
+
+#[test]
+fn test_instance_counts() {
+    let cfg = LatencyTraceCfg::default().with_record_only_nth(3);
+    let report = LatencyTrace::activated(cfg).unwrap().measure_report(f);
+    let timings = report.timings();
+    let instance_counts = report.instance_counts();
+
+    assert_eq!(instance_counts.len(), timings.len());
+    for (sg, timing) in timings.iter() {
+        let count = instance_counts[sg];
+        match sg.name() {
+            "root" => assert_eq!(count, 1),
+            "inner" => {
+                assert_eq!(count, 10);
+                assert_eq!(timing.len(), 1);
+            }
+            name => panic!("unexpected span group {name}"),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use latency_trace::{group_by_given_fields, LatencyTrace, LatencyTraceCfg, SpanGroup};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    for name in ["z", "a"] {
+        info_span!("root", name).in_scope(|| {
+            trace_span!("b").in_scope(|| {
+                trace_span!("bb").in_scope(|| {});
+            });
+            trace_span!("a").in_scope(|| {});
+        });
+    }
+}
+
+#[test]
+fn test_iter_topological() {
+    let cfg = LatencyTraceCfg::default().with_span_grouper(group_by_given_fields(&["name"]));
+    let timings = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+    assert_eq!(timings.len(), 8);
+
+    let ordered: Vec<&SpanGroup> = timings.iter_topological().map(|(sg, _)| sg).collect();
+    assert_eq!(ordered.len(), timings.len());
+
+    for (i, sg) in ordered.iter().enumerate() {
+        // Each span group comes after its parent and after its siblings with lower ids.
+        if let Some(pid) = sg.parent_id() {
+            let parent_pos = ordered.iter().position(|p| p.id() == pid).unwrap();
+            assert!(parent_pos < i);
+            for sibling in ordered[parent_pos + 1..i].iter() {
+                if sibling.parent_id() == Some(pid) {
+                    assert!(sibling.id() < sg.id());
+                }
+            }
+        }
+
+        // The subtree of each span group immediately follows it.
+        let n_descendants = ordered
+            .iter()
+            .filter(|d| timings.ancestors(d).iter().any(|a| a.id() == sg.id()))
+            .count();
+        for d in ordered[i + 1..i + 1 + n_descendants].iter() {
+            assert!(timings.ancestors(d).iter().any(|a| a.id() == sg.id()));
+        }
+    }
+
+    let roots: Vec<_> = ordered
+        .iter()
+        .filter(|sg| sg.parent_id().is_none())
+        .collect();
+    assert_eq!(roots.len(), 2);
+    assert!(roots[0].id() < roots[1].id());
+}
@@ -0,0 +1,53 @@
+use latency_trace::{json_schema, LatencyTrace, JSON_SCHEMA_VERSION};
+use serde_json::Value;
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    for i in 0..3 {
+        info_span!("root", i).in_scope(|| {
+            trace_span!("child").in_scope(|| thread::sleep(Duration::from_millis(1)));
+        });
+    }
+}
+
+/// Checks that `value` has the keys required and allowed by `schema`, recursively.
+fn check_keys(schema: &Value, value: &Value, path: &str) {
+    if let Some(props) = schema["properties"].as_object() {
+        let obj = value
+            .as_object()
+            .unwrap_or_else(|| panic!("{path} is not an object"));
+        for key in schema["required"].as_array().unwrap() {
+            let key = key.as_str().unwrap();
+            assert!(obj.contains_key(key), "{path} is missing {key}");
+        }
+        for (key, child) in obj {
+            let child_schema = props
+                .get(key)
+                .unwrap_or_else(|| panic!("{path}.{key} is not in the schema"));
+            check_keys(child_schema, child, &format!("{path}.{key}"));
+        }
+    }
+    if schema["type"] == "array" {
+        for (i, item) in value.as_array().unwrap().iter().enumerate() {
+            check_keys(&schema["items"], item, &format!("{path}[{i}]"));
+        }
+    }
+}
+
+#[test]
+fn test_json_schema() {
+    let schema: Value = serde_json::from_str(json_schema()).unwrap();
+    assert_eq!(
+        schema["properties"]["schema_version"]["const"],
+        JSON_SCHEMA_VERSION
+    );
+
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+    let json: Value = serde_json::from_str(&timings.to_json()).unwrap();
+    assert_eq!(json["schema_version"], JSON_SCHEMA_VERSION);
+    assert_eq!(json["timings"].as_array().unwrap().len(), timings.len());
+    check_keys(&schema, &json, "$");
+}
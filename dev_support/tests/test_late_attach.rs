@@ -0,0 +1,30 @@
+use latency_trace::LatencyTrace;
+use tracing::{dispatcher::with_default, info_span, trace_span, Dispatch};
+use tracing_subscriber::{filter::LevelFilter, layer::SubscriberExt, reload, Registry};
+
+#[test]
+fn test_late_attach() {
+    let lt = LatencyTrace::default();
+    let (layer, handle) = reload::Layer::new(None::<LatencyTrace>);
+    // The level filter keeps spans enabled while no `LatencyTrace` is attached.
+    let dispatch = Dispatch::new(Registry::default().with(layer).with(LevelFilter::TRACE));
+
+    let timings = with_default(&dispatch, || {
+        lt.measure_latencies(|| {
+            // Created before `lt` is attached, so it has no `SpanTiming` extension.
+            let early = info_span!("early");
+            handle.reload(Some(lt.clone())).unwrap();
+
+            early.in_scope(|| {
+                // The span group of a child of `early` can't be determined, so it is skipped.
+                trace_span!("early_child").in_scope(|| {});
+            });
+            drop(early);
+
+            info_span!("late").in_scope(|| {});
+        })
+    });
+
+    let names: Vec<_> = timings.span_groups().map(|sg| sg.name()).collect();
+    assert_eq!(names, ["late"]);
+}
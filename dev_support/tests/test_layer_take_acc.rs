@@ -0,0 +1,45 @@
+use latency_trace::{ActivationError, LatencyTrace, LatencyTraceCfg};
+use std::thread;
+use tracing::{dispatcher::with_default, info_span, trace_span, Dispatch};
+use tracing_subscriber::{filter::LevelFilter, prelude::*, Registry};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..3 {
+            trace_span!("child").in_scope(|| {});
+        }
+    });
+}
+
+#[test]
+fn test_layer_take_acc() {
+    assert!(matches!(
+        LatencyTrace::new(LatencyTraceCfg::default().with_hist_low(0)),
+        Err(ActivationError::HistogramConfigError { .. })
+    ));
+
+    let lt = LatencyTrace::new(LatencyTraceCfg::default()).unwrap();
+
+    // `lt` is one of the layers of a subscriber that is not the global default.
+    let subscriber = Registry::default()
+        .with(lt.clone())
+        .with(tracing_subscriber::fmt::layer().with_filter(LevelFilter::OFF));
+    let dispatch = Dispatch::new(subscriber);
+
+    with_default(&dispatch, f);
+    thread::scope(|s| {
+        s.spawn(|| with_default(&dispatch, f));
+    });
+
+    let acc = lt.take_acc_timings();
+    let timings = lt.report_timings(acc);
+    assert_eq!(timings.group_count(), 2);
+    for (sg, timing) in timings.iter() {
+        let expected = if sg.name() == "root" { 2 } else { 6 };
+        assert_eq!(timing.len(), expected, "{}", sg.name());
+    }
+
+    // The collected information was taken.
+    let timings = lt.report_timings(lt.take_acc_timings());
+    assert_eq!(timings.group_count(), 0);
+}
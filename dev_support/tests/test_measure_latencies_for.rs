@@ -0,0 +1,29 @@
+use latency_trace::LatencyTrace;
+use std::{
+    cell::Cell,
+    thread,
+    time::{Duration, Instant},
+};
+use tracing::info_span;
+
+#[test]
+fn test_measure_latencies_for() {
+    let duration = Duration::from_millis(200);
+    let invocations = Cell::new(0_u64);
+
+    let start = Instant::now();
+    let latencies = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies_for(duration, || {
+            invocations.set(invocations.get() + 1);
+            info_span!("work").in_scope(|| thread::sleep(Duration::from_millis(5)));
+        });
+    let elapsed = start.elapsed();
+
+    assert!(elapsed >= duration, "elapsed={elapsed:?}");
+    assert!(invocations.get() > 1, "invocations={}", invocations.get());
+
+    assert_eq!(latencies.len(), 1);
+    let timing = latencies.values().next().unwrap();
+    assert_eq!(timing.len(), invocations.get());
+}
@@ -0,0 +1,38 @@
+use latency_trace::LatencyTrace;
+use std::time::Duration;
+use tracing::{info_span, trace_span, Instrument};
+
+fn sum(n: u64) -> u64 {
+    info_span!("sum").in_scope(|| (1..=n).map(|i| trace_span!("add").in_scope(|| i)).sum())
+}
+
+async fn sum_async(n: u64) -> u64 {
+    let mut total = 0;
+    for i in 1..=n {
+        tokio::time::sleep(Duration::from_millis(1))
+            .instrument(trace_span!("add_async"))
+            .await;
+        total += i;
+    }
+    total
+}
+
+#[test]
+fn test_measure_latencies_r() {
+    let lt = LatencyTrace::activated_default().unwrap();
+
+    let (total, timings) = lt.measure_latencies_r(|| sum(10));
+    assert_eq!(total, 55);
+    let add = timings.iter().find(|(sg, _)| sg.name() == "add").unwrap().1;
+    assert_eq!(add.len(), 10);
+
+    let (total, timings) =
+        lt.measure_latencies_tokio_r(|| sum_async(5).instrument(info_span!("sum_async")));
+    assert_eq!(total, 15);
+    let add = timings
+        .iter()
+        .find(|(sg, _)| sg.name() == "add_async")
+        .unwrap()
+        .1;
+    assert_eq!(add.len(), 5);
+}
@@ -0,0 +1,33 @@
+use latency_trace::LatencyTrace;
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+const N_WORKERS: u64 = 4;
+const N_ITERS: u64 = 5;
+
+fn work() {
+    for _ in 0..N_ITERS {
+        info_span!("worker").in_scope(|| {
+            trace_span!("step").in_scope(|| {
+                thread::sleep(Duration::from_millis(1));
+            });
+        });
+    }
+}
+
+#[test]
+fn test_measure_latencies_scoped() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies_scoped(|s| {
+            // The workers are not explicitly joined.
+            for _ in 0..N_WORKERS {
+                s.spawn(work);
+            }
+        });
+
+    assert_eq!(timings.group_count(), 2);
+    for (sg, timing) in timings.iter() {
+        assert_eq!(timing.len(), N_WORKERS * N_ITERS, "{}", sg.name());
+    }
+}
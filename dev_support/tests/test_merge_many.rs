@@ -0,0 +1,48 @@
+use latency_trace::{LatencyTrace, Timings};
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+fn run(nrepeats: u64, extra: bool) {
+    for _ in 0..nrepeats {
+        info_span!("root").in_scope(|| {
+            trace_span!("child").in_scope(|| thread::sleep(Duration::from_millis(1)));
+            if extra {
+                trace_span!("extra").in_scope(|| thread::sleep(Duration::from_millis(1)));
+            }
+        });
+    }
+}
+
+fn count_of(timings: &Timings, name: &str) -> u64 {
+    timings
+        .iter()
+        .find(|(sg, _)| sg.name() == name)
+        .map(|(_, timing)| timing.len())
+        .unwrap()
+}
+
+#[test]
+fn test_merge_many() {
+    let lt = LatencyTrace::activated_default().unwrap();
+    // Simulated shards of a load test.
+    let shards = vec![
+        lt.measure_latencies(|| run(2, false)),
+        lt.measure_latencies(|| run(3, true)),
+        lt.measure_latencies(|| run(4, false)),
+    ];
+    let max_child = shards
+        .iter()
+        .map(|t| t.values().map(|t| t.max()).max().unwrap())
+        .max();
+
+    let merged = Timings::merge_many(shards);
+
+    assert_eq!(merged.len(), 3);
+    assert_eq!(count_of(&merged, "root"), 9);
+    assert_eq!(count_of(&merged, "child"), 9);
+    assert_eq!(count_of(&merged, "extra"), 3);
+    assert_eq!(merged.values().map(|t| t.max()).max(), max_child);
+    assert_eq!(merged.validate(), Ok(()));
+
+    assert!(Timings::merge_many(Vec::new()).is_empty());
+}
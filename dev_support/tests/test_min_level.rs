@@ -0,0 +1,35 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use tracing::{debug_span, info_span, trace_span, Level};
+
+fn f() {
+    for _ in 0..3 {
+        info_span!("root").in_scope(|| {
+            debug_span!("filtered").in_scope(|| {
+                info_span!("leaf").in_scope(|| {
+                    trace_span!("filtered_leaf").in_scope(|| {});
+                });
+            });
+        });
+    }
+}
+
+#[test]
+fn test_min_level() {
+    assert_eq!(LatencyTraceCfg::default().min_level(), Level::TRACE);
+
+    let cfg = LatencyTraceCfg::default().with_min_level(Level::INFO);
+    let timings = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+
+    let names: Vec<&str> = timings.span_groups().map(|sg| sg.name()).collect();
+    assert_eq!(names, vec!["leaf", "root"]);
+
+    // The leaf's parent is its nearest enabled ancestor.
+    let sg_to_parent = timings.span_group_to_parent();
+    let leaf = timings
+        .span_groups()
+        .find(|sg| sg.name() == "leaf")
+        .unwrap();
+    assert_eq!(leaf.depth(), 2);
+    assert_eq!(sg_to_parent[leaf].as_ref().unwrap().name(), "root");
+    assert_eq!(timings[leaf].len(), 3);
+}
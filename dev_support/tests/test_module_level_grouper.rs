@@ -0,0 +1,57 @@
+use latency_trace::{module_level_grouper, LatencyTrace, LatencyTraceCfg};
+use tracing::info_span;
+
+mod a {
+    pub mod b {
+        use tracing::info_span;
+
+        pub mod c {
+            use tracing::info_span;
+
+            pub fn f() {
+                info_span!("c_span").in_scope(|| {});
+            }
+        }
+
+        pub fn f() {
+            info_span!("b_span").in_scope(|| {});
+        }
+    }
+}
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        a::b::c::f();
+        a::b::f();
+    });
+}
+
+#[test]
+fn test_module_level_grouper() {
+    // Integration test module paths start with the name of the test crate.
+    let crate_name = module_path!();
+
+    let cfg = LatencyTraceCfg::default().with_span_grouper(module_level_grouper(3));
+    let latencies = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+
+    let mut modules: Vec<(&str, String)> = latencies
+        .keys()
+        .map(|sg| {
+            assert_eq!(sg.props().len(), 1);
+            assert_eq!(sg.props()[0].0, "module");
+            (sg.name(), sg.props()[0].1.clone())
+        })
+        .collect();
+    modules.sort();
+
+    assert_eq!(
+        modules,
+        vec![
+            ("b_span", format!("{crate_name}::a::b")),
+            // `a::b::c` at depth 3 (including the crate) is grouped as `a::b`.
+            ("c_span", format!("{crate_name}::a::b")),
+            // The module path of `root` is shorter than the depth.
+            ("root", crate_name.to_owned()),
+        ]
+    );
+}
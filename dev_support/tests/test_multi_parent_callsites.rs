@@ -0,0 +1,46 @@
+use latency_trace::LatencyTrace;
+use tracing::{info_span, instrument, trace_span};
+
+#[instrument(level = "trace")]
+fn shared() {}
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        trace_span!("a").in_scope(shared);
+        trace_span!("b").in_scope(|| {
+            shared();
+            shared();
+        });
+        trace_span!("c").in_scope(|| {});
+    });
+}
+
+#[test]
+fn test_multi_parent_callsites() {
+    let latencies = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    let multi = latencies.multi_parent_callsites();
+    assert_eq!(multi.keys().copied().collect::<Vec<_>>(), vec!["shared"]);
+
+    let sgs = &multi["shared"];
+    assert_eq!(sgs.len(), 2);
+    let mut parent_names: Vec<&str> = sgs
+        .iter()
+        .map(|sg| {
+            latencies
+                .span_groups()
+                .find(|p| Some(p.id()) == sg.parent_id())
+                .unwrap()
+                .name()
+        })
+        .collect();
+    parent_names.sort();
+    assert_eq!(parent_names, vec!["a", "b"]);
+
+    // The callsite's count is split across its span groups.
+    let mut counts: Vec<u64> = sgs.iter().map(|sg| latencies[sg].len()).collect();
+    counts.sort();
+    assert_eq!(counts, vec![1, 2]);
+}
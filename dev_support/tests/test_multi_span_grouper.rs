@@ -0,0 +1,62 @@
+use latency_trace::{group_by_given_fields, LatencyTrace, LatencyTraceCfg};
+use tracing::{info_span, trace_span};
+
+fn request(endpoint: &str, status: u16) {
+    info_span!("request", endpoint, status).in_scope(|| {
+        trace_span!("db").in_scope(|| {});
+    });
+}
+
+fn f() {
+    request("a", 200);
+    request("b", 200);
+    request("a", 500);
+}
+
+#[test]
+fn test_multi_span_grouper() {
+    let by_endpoint = group_by_given_fields(&["endpoint"]);
+    let by_status = group_by_given_fields(&["status"]);
+    let cfg = LatencyTraceCfg::default()
+        .with_multi_span_grouper(move |attrs| vec![by_endpoint(attrs), by_status(attrs)]);
+
+    let latencies = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+
+    let mut counts: Vec<(String, u64)> = latencies
+        .iter()
+        .map(|(sg, timing)| {
+            let props: Vec<String> = sg.props().iter().map(|(k, v)| format!("{k}={v}")).collect();
+            (format!("{}{:?}", sg.name(), props), timing.len())
+        })
+        .collect();
+    counts.sort();
+
+    // Property sets of `db` spans are both empty, so they are only recorded once.
+    let mut expected: Vec<(String, u64)> = vec![
+        (r#"db[]"#, 2),
+        (r#"db[]"#, 1),
+        (r#"request["endpoint=\"a\""]"#, 2),
+        (r#"request["endpoint=\"b\""]"#, 1),
+        (r#"request["status=200"]"#, 2),
+        (r#"request["status=500"]"#, 1),
+    ]
+    .into_iter()
+    .map(|(group, count)| (group.to_owned(), count))
+    .collect();
+    expected.sort();
+    assert_eq!(counts, expected);
+
+    // Each span contributes to two distinct sibling groups, and child span groups are children of the
+    // primary (endpoint) span groups.
+    let parents = latencies.span_group_to_parent();
+    for (sg, parent) in parents.iter() {
+        match sg.name() {
+            "request" => assert!(parent.is_none()),
+            "db" => {
+                let parent = parent.as_ref().unwrap();
+                assert_eq!(parent.props()[0].0, "endpoint");
+            }
+            _ => unreachable!(),
+        }
+    }
+}
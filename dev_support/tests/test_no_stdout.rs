@@ -0,0 +1,41 @@
+use latency_trace::LatencyTrace;
+use std::{env, process::Command};
+use tracing::{info_span, trace_span};
+
+const CHILD_ENV: &str = "LATENCY_TRACE_TEST_NO_STDOUT_CHILD";
+const BEGIN: &str = "<<begin>>";
+const END: &str = "<<end>>";
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..10 {
+            trace_span!("child").in_scope(|| {});
+        }
+    });
+}
+
+/// The test re-runs itself in a child process without output capture, and checks that nothing is written to stdout
+/// between the markers printed by the child around the measurement.
+#[test]
+fn test_no_stdout() {
+    if env::var_os(CHILD_ENV).is_some() {
+        let lt = LatencyTrace::activated_default().unwrap();
+        println!("{BEGIN}");
+        let timings = lt.measure_latencies(f);
+        println!("{END}");
+        assert_eq!(timings.len(), 2);
+        return;
+    }
+
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["test_no_stdout", "--exact", "--nocapture"])
+        .env(CHILD_ENV, "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let begin = stdout.find(BEGIN).expect("begin marker") + BEGIN.len();
+    let end = stdout.find(END).expect("end marker");
+    assert_eq!(stdout[begin..end].trim(), "", "stdout={stdout}");
+}
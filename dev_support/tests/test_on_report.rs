@@ -0,0 +1,43 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg, Timings};
+use std::sync::{Arc, Mutex};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..3 {
+            trace_span!("child").in_scope(|| {});
+        }
+    });
+}
+
+#[test]
+fn test_on_report() {
+    let reports: Arc<Mutex<Vec<Timings>>> = Arc::default();
+
+    let cfg = LatencyTraceCfg::default().with_on_report({
+        let reports = reports.clone();
+        move |timings| reports.lock().unwrap().push(timings.clone())
+    });
+    let lt = LatencyTrace::activated(cfg).unwrap();
+
+    let latencies = lt.measure_latencies(f);
+    {
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0], latencies);
+    }
+
+    // Per-thread reports don't invoke the callback.
+    let per_thread = lt.measure_latencies_per_thread(f);
+    assert_eq!(per_thread.len(), 1);
+    assert_eq!(reports.lock().unwrap().len(), 1);
+
+    // Probed reports also invoke the callback.
+    let probed = lt.measure_latencies_probed(f).unwrap();
+    let probed_latencies = probed.probe_latencies();
+    let final_latencies = probed.wait_and_report();
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len(), 3);
+    assert_eq!(reports[1], probed_latencies);
+    assert_eq!(reports[2], final_latencies);
+}
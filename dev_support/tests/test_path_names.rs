@@ -0,0 +1,57 @@
+use latency_trace::{group_by_given_fields, LatencyTrace, LatencyTraceCfg};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    for i in 0..2 {
+        info_span!("root", i).in_scope(|| {
+            trace_span!("child").in_scope(|| {
+                trace_span!("grandchild").in_scope(|| {});
+            });
+        });
+    }
+}
+
+#[test]
+fn test_path_names() {
+    let cfg = LatencyTraceCfg::default()
+        .with_path_names(true)
+        .with_span_grouper(group_by_given_fields(&["i"]));
+    assert!(cfg.path_names());
+    let timings = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+
+    // Grouped the same as without path names.
+    assert_eq!(timings.len(), 6);
+
+    for (sg, timing) in timings.iter() {
+        let expected_path = match sg.name() {
+            "root" => "root",
+            "child" => "root/child",
+            "grandchild" => "root/child/grandchild",
+            name => panic!("unexpected span group {name}"),
+        };
+        let props = sg.props();
+        // The path precedes the properties from the span grouper.
+        assert_eq!(props[0], ("path".to_owned(), expected_path.to_owned()));
+        assert_eq!(timing.len(), 1);
+    }
+
+    let mut root_props: Vec<_> = timings
+        .span_groups()
+        .filter(|sg| sg.name() == "root")
+        .map(|sg| sg.props().to_vec())
+        .collect();
+    root_props.sort();
+    assert_eq!(
+        root_props,
+        [
+            vec![
+                ("path".to_owned(), "root".to_owned()),
+                ("i".to_owned(), "0".to_owned())
+            ],
+            vec![
+                ("path".to_owned(), "root".to_owned()),
+                ("i".to_owned(), "1".to_owned())
+            ],
+        ]
+    );
+}
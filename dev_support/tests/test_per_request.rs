@@ -0,0 +1,48 @@
+use latency_trace::{group_by_given_fields, LatencyTrace, LatencyTraceCfg};
+use tracing::{info_span, trace_span};
+
+fn handle(request_id: u32, nqueries: usize) {
+    info_span!("request", request_id).in_scope(|| {
+        trace_span!("auth").in_scope(|| {});
+        for _ in 0..nqueries {
+            trace_span!("query").in_scope(|| {});
+        }
+    });
+}
+
+fn f() {
+    info_span!("server").in_scope(|| {
+        handle(1, 1);
+        handle(2, 3);
+    });
+}
+
+#[test]
+fn test_per_request() {
+    let cfg = LatencyTraceCfg::default().with_span_grouper(group_by_given_fields(&["request_id"]));
+    let latencies = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+
+    let per_request = latencies.per_request("request_id");
+    assert_eq!(
+        per_request.keys().map(String::as_str).collect::<Vec<_>>(),
+        vec!["1", "2"]
+    );
+
+    for (id, nqueries) in [("1", 1), ("2", 3)] {
+        let timings = &per_request[id];
+        let mut counts: Vec<(&str, u64)> = timings
+            .iter()
+            .map(|(sg, timing)| (sg.name(), timing.len()))
+            .collect();
+        counts.sort();
+        assert_eq!(
+            counts,
+            vec![("auth", 1), ("query", nqueries), ("request", 1)],
+            "request {id}"
+        );
+    }
+
+    // The subtrees are disjoint and exclude the enclosing "server" span group.
+    let total: usize = per_request.values().map(|timings| timings.len()).sum();
+    assert_eq!(total, latencies.len() - 1);
+}
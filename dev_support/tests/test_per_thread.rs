@@ -0,0 +1,55 @@
+use latency_trace::LatencyTrace;
+use std::thread;
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        let parent = trace_span!("parent");
+        thread::scope(|s| {
+            for n in 1..=3 {
+                let parent = parent.clone();
+                s.spawn(move || {
+                    for _ in 0..n {
+                        trace_span!(parent: &parent, "work").in_scope(|| {});
+                    }
+                });
+            }
+        });
+    });
+}
+
+#[test]
+fn test_per_thread() {
+    let lt = LatencyTrace::activated_default().unwrap();
+    let timings_per_thread = lt.measure_latencies_per_thread(f);
+
+    // The spawned threads, plus the main thread, which closed "root" and "parent".
+    assert_eq!(timings_per_thread.len(), 4);
+    let main_timings = &timings_per_thread[&thread::current().id()];
+    let mut main_names: Vec<_> = main_timings
+        .iter()
+        .filter(|(_, timing)| !timing.is_empty())
+        .map(|(sg, _)| sg.name())
+        .collect();
+    main_names.sort();
+    assert_eq!(main_names, ["parent", "root"]);
+
+    let mut work_counts: Vec<_> = timings_per_thread
+        .iter()
+        .filter(|(thread_id, _)| **thread_id != thread::current().id())
+        .map(|(_, timings)| {
+            // The ancestors of "work", which were closed on the main thread, are reported with empty histograms.
+            let recorded: Vec<_> = timings
+                .iter()
+                .filter(|(_, timing)| !timing.is_empty())
+                .collect();
+            assert_eq!(timings.len(), 3);
+            assert_eq!(recorded.len(), 1);
+            let (sg, timing) = recorded[0];
+            assert_eq!(sg.name(), "work");
+            timing.len()
+        })
+        .collect();
+    work_counts.sort();
+    assert_eq!(work_counts, [1, 2, 3]);
+}
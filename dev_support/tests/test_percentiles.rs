@@ -0,0 +1,34 @@
+use latency_trace::LatencyTrace;
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..10 {
+            trace_span!("child").in_scope(|| {});
+        }
+    });
+}
+
+#[test]
+fn test_percentiles() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    let p99 = timings.percentile(99.0);
+    let ps = timings.percentiles(&[50.0, 99.0, 100.0]);
+    assert_eq!(p99.len(), 2);
+    assert_eq!(ps.len(), 2);
+
+    for (sg, timing) in timings.iter() {
+        assert_eq!(p99[sg], timing.value_at_percentile(99.0));
+        assert_eq!(
+            ps[sg],
+            vec![
+                timing.value_at_percentile(50.0),
+                timing.value_at_percentile(99.0),
+                timing.max()
+            ]
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg, PollCounts};
+use tracing::{info_span, Instrument};
+
+const YIELDS: u64 = 3;
+
+async fn f() {
+    info_span!("sync_root").in_scope(|| {});
+
+    async {
+        for _ in 0..YIELDS {
+            tokio::task::yield_now().await;
+        }
+    }
+    .instrument(info_span!("async_root"))
+    .await;
+}
+
+fn poll_count(poll_counts: &PollCounts, name: &str) -> (u64, u64) {
+    let (_, hist) = poll_counts
+        .iter()
+        .find(|(sg, _)| sg.name() == name)
+        .unwrap();
+    (hist.len(), hist.max())
+}
+
+#[test]
+fn test_poll_counts() {
+    let lt =
+        LatencyTrace::activated(LatencyTraceCfg::default().with_record_poll_counts(true)).unwrap();
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let report = lt.measure_report(|| rt.block_on(f()));
+    let latencies = report.timings();
+    let poll_counts = report.poll_counts();
+
+    assert_eq!(latencies.len(), 2);
+    assert_eq!(poll_counts.len(), 2);
+
+    // Entered once by `in_scope`.
+    assert_eq!(poll_count(poll_counts, "sync_root"), (1, 1));
+
+    // Polled once initially and once after each yield, plus entered once when the instrumented future is dropped.
+    assert_eq!(poll_count(poll_counts, "async_root"), (1, YIELDS + 2));
+}
@@ -0,0 +1,52 @@
+use dev_support::bench_support::post_processing::synthetic_trace;
+use latency_trace::{group_by_all_fields, LatencyTrace, LatencyTraceCfg};
+use sha2::{Digest, Sha256};
+use std::{collections::BTreeMap, fmt::Write};
+
+const NBRANCHES: usize = 50;
+const NLEAVES: usize = 20;
+
+/// SHA-256 of the span group structure of the report, as produced by the original (unoptimized) post-processing.
+/// It depends on the source location of the spans in `dev_support::bench_support::post_processing`.
+const EXPECTED_FINGERPRINT: &str =
+    "ee37c6d9cbf45b6ae964fa241b37c21b53886799d25ff28a3756e562f6c68f2c";
+
+#[test]
+fn test_post_processing() {
+    let latencies =
+        LatencyTrace::activated(LatencyTraceCfg::default().with_span_grouper(group_by_all_fields))
+            .unwrap()
+            .measure_latencies(|| synthetic_trace(NBRANCHES, NLEAVES));
+
+    assert_eq!(latencies.len(), 1 + NBRANCHES + NBRANCHES * NLEAVES);
+
+    let by_id: BTreeMap<&str, _> = latencies.keys().map(|sg| (sg.id(), sg)).collect();
+    assert_eq!(by_id.len(), latencies.len(), "span group ids are unique");
+
+    let mut structure = String::new();
+    for (id, sg) in &by_id {
+        match sg.parent_id() {
+            None => assert_eq!(sg.depth(), 1),
+            Some(parent_id) => {
+                let parent = by_id[parent_id];
+                assert_eq!(sg.depth(), parent.depth() + 1);
+            }
+        }
+
+        // Each span group has a single span as all spans have distinct field values.
+        assert_eq!(latencies[*sg].len(), 1);
+
+        writeln!(
+            structure,
+            "{id} {:?} {} {} {:?}",
+            sg.parent_id(),
+            sg.name(),
+            sg.code_line(),
+            sg.props()
+        )
+        .unwrap();
+    }
+
+    let fingerprint = format!("{:x}", Sha256::digest(structure.as_bytes()));
+    assert_eq!(fingerprint, EXPECTED_FINGERPRINT);
+}
@@ -0,0 +1,74 @@
+use latency_trace::{LatencyTrace, ProbeMode, Timings};
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+use tracing::{info_span, trace_span};
+
+const NTHREADS: u64 = 4;
+const NREPEATS: u64 = 200;
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        thread::scope(|s| {
+            for _ in 0..NTHREADS {
+                s.spawn(|| {
+                    for _ in 0..NREPEATS {
+                        trace_span!("a").in_scope(|| thread::sleep(Duration::from_micros(100)));
+                        STARTED.store(true, Ordering::Relaxed);
+                        trace_span!("b").in_scope(|| thread::sleep(Duration::from_micros(100)));
+                    }
+                });
+            }
+        });
+    });
+}
+
+fn count(latencies: &Timings, name: &str) -> u64 {
+    latencies
+        .iter()
+        .filter(|(sg, _)| sg.name() == name)
+        .map(|(_, timing)| timing.len())
+        .sum()
+}
+
+/// Each thread closes an "a" span before each "b" span, so any snapshot has at least as many "a" spans as "b" spans
+/// and at most one extra "a" span per thread.
+fn assert_interim(latencies: &Timings, mode: ProbeMode) {
+    let a = count(latencies, "a");
+    let b = count(latencies, "b");
+    assert!(b <= a && a <= b + NTHREADS, "{mode:?}: a={a}, b={b}");
+    assert!(a <= NTHREADS * NREPEATS, "{mode:?}: a={a}");
+    assert_eq!(count(latencies, "root"), 0, "{mode:?}: root is still open");
+}
+
+#[test]
+fn test_probe_mode() {
+    let probed = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies_probed(f)
+        .unwrap();
+
+    while !STARTED.load(Ordering::Relaxed) {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    for mode in [
+        ProbeMode::NonBlocking,
+        ProbeMode::Blocking,
+        ProbeMode::NonBlocking,
+        ProbeMode::Blocking,
+    ] {
+        let latencies = probed.probe_latencies_with_mode(mode);
+        assert_interim(&latencies, mode);
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    let latencies = probed.wait_and_report();
+    assert_eq!(count(&latencies, "a"), NTHREADS * NREPEATS);
+    assert_eq!(count(&latencies, "b"), NTHREADS * NREPEATS);
+    assert_eq!(count(&latencies, "root"), 1);
+}
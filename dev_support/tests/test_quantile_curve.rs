@@ -0,0 +1,33 @@
+use latency_trace::LatencyTrace;
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for i in 0..20 {
+            trace_span!("work").in_scope(|| thread::sleep(Duration::from_micros(100 * i)));
+        }
+    });
+}
+
+#[test]
+fn test_quantile_curve() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+    let (work, timing) = timings.iter().find(|(sg, _)| sg.name() == "work").unwrap();
+
+    let curve: Vec<(f64, u64)> = timings.quantile_curve(work, 5).unwrap().collect();
+    assert!(curve.len() > 1, "curve={curve:?}");
+    assert!(curve
+        .windows(2)
+        .all(|w| w[0].0 <= w[1].0 && w[0].1 <= w[1].1));
+    let (last_quantile, last_value) = *curve.last().unwrap();
+    assert_eq!(last_quantile, 1.0);
+    assert!(timing.equivalent(last_value, timing.max()));
+
+    let (root, _) = timings.iter().find(|(sg, _)| sg.name() == "root").unwrap();
+    let mut other = timings.clone();
+    other.retain_groups(|sg| sg.name() != "root");
+    assert!(other.quantile_curve(root, 5).is_none());
+}
@@ -0,0 +1,52 @@
+use hdrhistogram::Histogram;
+use latency_trace::{TimeUnit, Timing};
+
+fn new_timing() -> Timing {
+    Timing::new(
+        Histogram::<u64>::new_with_bounds(1, 20 * 1000 * 1000, 2).unwrap(),
+        TimeUnit::Micros,
+    )
+}
+
+#[test]
+fn test_quantile_interpolated() {
+    // Empty histogram.
+    assert_eq!(new_timing().value_at_quantile_interpolated(0.5), 0.0);
+
+    // All values in a single bucket, so `value_at_quantile` is the same for all quantiles.
+    {
+        let mut timing = new_timing();
+        timing.record_n(10_000, 100).unwrap();
+        let low = timing.lowest_equivalent(10_000) as f64;
+        let high = timing.highest_equivalent(10_000) as f64;
+        assert!(high - low > 1.0, "bucket should span multiple values");
+        assert_eq!(
+            timing.value_at_quantile(0.25),
+            timing.value_at_quantile(0.75)
+        );
+
+        let mut prev = low;
+        for q in [0.0, 0.1, 0.25, 0.5, 0.75, 0.9, 1.0] {
+            let v = timing.value_at_quantile_interpolated(q);
+            assert!(low <= v && v <= high, "q={q}: {v} not in [{low}, {high}]");
+            assert!(v >= prev, "q={q}: {v} < {prev}");
+            prev = v;
+        }
+        let q_25 = timing.value_at_quantile_interpolated(0.25);
+        let q_75 = timing.value_at_quantile_interpolated(0.75);
+        assert!(q_25 < q_75, "interpolation should avoid stair-stepping");
+    }
+
+    // Values in two buckets: the interpolated median is in the bucket of the exact median.
+    {
+        let mut timing = new_timing();
+        timing.record_n(1_000, 60).unwrap();
+        timing.record_n(50_000, 40).unwrap();
+        let v = timing.value_at_quantile_interpolated(0.5);
+        assert!(timing.lowest_equivalent(1_000) as f64 <= v);
+        assert!(v <= timing.highest_equivalent(1_000) as f64);
+        let v = timing.value_at_quantile_interpolated(0.8);
+        assert!(timing.lowest_equivalent(50_000) as f64 <= v);
+        assert!(v <= timing.highest_equivalent(50_000) as f64);
+    }
+}
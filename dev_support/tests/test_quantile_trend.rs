@@ -0,0 +1,51 @@
+use latency_trace::{quantile_trend, testing::measure_latencies_isolated, LatencyTraceCfg};
+use std::{thread, time::Duration};
+use tracing::{info_span, instrument};
+
+#[instrument(level = "trace")]
+fn g(micros: u64) {
+    thread::sleep(Duration::from_micros(micros));
+}
+
+fn run(micros: u64) -> impl FnOnce() + Send + 'static {
+    move || {
+        info_span!("root").in_scope(|| {
+            for _ in 0..5 {
+                g(micros);
+            }
+        })
+    }
+}
+
+#[test]
+fn test_quantile_trend() {
+    let runs = [
+        (
+            "commit1",
+            measure_latencies_isolated(LatencyTraceCfg::default(), run(1000)),
+        ),
+        (
+            "commit2",
+            measure_latencies_isolated(LatencyTraceCfg::default(), run(2000)),
+        ),
+        (
+            "commit3",
+            measure_latencies_isolated(LatencyTraceCfg::default(), run(4000)),
+        ),
+    ];
+
+    let trend = quantile_trend(runs.iter().map(|(label, t)| (*label, t)), 0.5);
+
+    assert_eq!(trend.len(), 2);
+    for (sg, points) in trend.iter() {
+        let labels: Vec<&str> = points.iter().map(|(label, _)| *label).collect();
+        assert_eq!(labels, vec!["commit1", "commit2", "commit3"], "{:?}", sg);
+
+        assert!(points.iter().all(|(_, value)| *value > 0), "{:?}", sg);
+    }
+
+    let csv = trend.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "span_group_id,span_group_name,label,value");
+    assert_eq!(lines.len(), 1 + 2 * 3);
+}
@@ -0,0 +1,39 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+const NTH: u64 = 3;
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for i in 1..=5 {
+            trace_span!("step").in_scope(|| {
+                if i == NTH {
+                    thread::sleep(Duration::from_millis(20));
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn test_record_only_nth() {
+    let latencies = LatencyTrace::activated(LatencyTraceCfg::default().with_record_only_nth(NTH))
+        .unwrap()
+        .measure_latencies(f);
+
+    assert_eq!(latencies.len(), 2);
+    for (span_group, timing) in latencies.iter() {
+        match span_group.name() {
+            // Only the slow occurrence is recorded.
+            "step" => {
+                assert_eq!(timing.len(), 1);
+                // `max` is the highest value equivalent to the recorded value in the histogram.
+                assert!(timing.max() >= 20 * 1000, "max={}", timing.max());
+            }
+            // Fewer than `NTH` occurrences.
+            "root" => assert_eq!(timing.len(), 0),
+            name => panic!("unexpected span group {name}"),
+        }
+    }
+}
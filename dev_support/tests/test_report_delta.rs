@@ -0,0 +1,47 @@
+use latency_trace::{LatencyTrace, Timings};
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+const NREPEATS: u64 = 20;
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..NREPEATS {
+            trace_span!("step").in_scope(|| thread::sleep(Duration::from_millis(10)));
+        }
+    });
+}
+
+fn step_count(timings: &Timings) -> u64 {
+    timings
+        .iter()
+        .find(|(sg, _)| sg.name() == "step")
+        .map(|(_, timing)| timing.len())
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_report_delta() {
+    let probed = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies_probed(f)
+        .unwrap();
+
+    thread::sleep(Duration::from_millis(80));
+    let count1 = step_count(&probed.report_delta());
+    thread::sleep(Duration::from_millis(80));
+    let count2 = step_count(&probed.report_delta());
+    // Let `f` complete.
+    thread::sleep(Duration::from_millis(500));
+    let count3 = step_count(&probed.report_delta());
+    let count4 = step_count(&probed.report_delta());
+
+    assert!(count1 > 0 && count1 < NREPEATS, "count1={count1}");
+    assert!(count2 > 0 && count2 < NREPEATS, "count2={count2}");
+    assert!(count3 > 0, "count3={count3}");
+    assert_eq!(count4, 0);
+    assert_eq!(count1 + count2 + count3, NREPEATS);
+
+    let timings = probed.wait_and_report();
+    assert_eq!(step_count(&timings), NREPEATS);
+}
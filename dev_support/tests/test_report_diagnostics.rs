@@ -0,0 +1,59 @@
+use latency_trace::LatencyTrace;
+use std::{
+    sync::mpsc,
+    thread::{self, JoinHandle},
+};
+use tracing::{info_span, trace_span};
+
+#[test]
+fn test_report_diagnostics() {
+    let lt = LatencyTrace::activated_default().unwrap();
+
+    // All threads joined, so all spans are closed.
+    {
+        let report = lt.measure_report(|| {
+            info_span!("root").in_scope(|| {
+                thread::spawn(|| trace_span!("worker").in_scope(|| {}))
+                    .join()
+                    .unwrap();
+            });
+        });
+        let (latencies, diagnostics) = (report.timings(), report.diagnostics());
+        assert_eq!(latencies.len(), 2);
+        assert!(diagnostics.threads_captured() >= 2);
+        assert_eq!(diagnostics.threads_with_open_spans(), 0);
+        assert_eq!(diagnostics.open_spans(), 0);
+        assert!(diagnostics.is_complete());
+    }
+
+    // Worker thread not joined and still inside its span when the report is produced.
+    {
+        let (opened_tx, opened_rx) = mpsc::channel::<()>();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let mut worker: Option<JoinHandle<()>> = None;
+
+        let report = lt.measure_report(|| {
+            info_span!("root").in_scope(|| {
+                worker = Some(thread::spawn(move || {
+                    trace_span!("unjoined_worker").in_scope(|| {
+                        opened_tx.send(()).unwrap();
+                        release_rx.recv().unwrap();
+                    });
+                }));
+                opened_rx.recv().unwrap();
+            });
+        });
+        let (latencies, diagnostics) = (report.timings(), report.diagnostics());
+
+        assert!(
+            latencies.keys().all(|sg| sg.name() != "unjoined_worker"),
+            "latency of unjoined worker span is missing"
+        );
+        assert_eq!(diagnostics.threads_with_open_spans(), 1);
+        assert_eq!(diagnostics.open_spans(), 1);
+        assert!(!diagnostics.is_complete());
+
+        release_tx.send(()).unwrap();
+        worker.unwrap().join().unwrap();
+    }
+}
@@ -0,0 +1,50 @@
+use latency_trace::{LatencyTrace, Timings};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+use tracing::{info_span, trace_span};
+
+const NREPEATS: u64 = 20;
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..NREPEATS {
+            trace_span!("step").in_scope(|| thread::sleep(Duration::from_millis(10)));
+        }
+    });
+}
+
+fn step_count(timings: &Timings) -> u64 {
+    timings
+        .iter()
+        .find(|(sg, _)| sg.name() == "step")
+        .map(|(_, timing)| timing.len())
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_report_every() {
+    let probed = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies_probed(f)
+        .unwrap();
+
+    let reports: Arc<Mutex<Vec<Timings>>> = Arc::default();
+    probed.report_every(Duration::from_millis(50), {
+        let reports = reports.clone();
+        move |timings| reports.lock().unwrap().push(timings)
+    });
+
+    let timings = probed.wait_and_report();
+    assert_eq!(step_count(&timings), NREPEATS);
+
+    // The final report is delivered before `wait_and_report` returns.
+    let reports = reports.lock().unwrap();
+    assert!(reports.len() >= 2, "reports.len()={}", reports.len());
+    let counts: Vec<u64> = reports.iter().map(step_count).collect();
+    assert!(counts.windows(2).all(|w| w[0] <= w[1]), "counts={counts:?}");
+    assert!(counts[0] < NREPEATS, "counts={counts:?}");
+    assert_eq!(*counts.last().unwrap(), NREPEATS);
+}
@@ -0,0 +1,51 @@
+use latency_trace::{LatencyTrace, Timings};
+use std::thread;
+use tracing::{info_span, trace_span};
+
+fn phase(name: &'static str, n: u64) {
+    info_span!("phase", name).in_scope(|| {
+        thread::scope(|s| {
+            for _ in 0..2 {
+                s.spawn(|| {
+                    for _ in 0..n {
+                        trace_span!("work").in_scope(|| {});
+                    }
+                });
+            }
+        });
+    });
+}
+
+fn work_count(timings: &Timings) -> u64 {
+    timings
+        .iter()
+        .filter(|(sg, _)| sg.name() == "work")
+        .map(|(_, timing)| timing.len())
+        .sum()
+}
+
+#[test]
+fn test_reset() {
+    let lt = LatencyTrace::activated_default().unwrap();
+
+    phase("a", 10);
+    lt.reset();
+    let timings = lt.measure_latencies(|| phase("b", 3));
+    assert_eq!(work_count(&timings), 6);
+
+    // A span that is open during the reset is still reported.
+    let timings = lt.measure_latencies(|| {
+        info_span!("outer").in_scope(|| {
+            phase("c", 5);
+            lt.reset();
+            phase("d", 1);
+        });
+    });
+    assert_eq!(work_count(&timings), 2);
+    let outer = timings
+        .iter()
+        .find(|(sg, _)| sg.name() == "outer")
+        .unwrap()
+        .1;
+    assert_eq!(outer.len(), 1);
+}
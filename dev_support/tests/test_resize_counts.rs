@@ -0,0 +1,29 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        // Increasing latencies that exceed a low `hist_high`.
+        for millis in [1, 2, 4, 8] {
+            trace_span!("growing").in_scope(|| thread::sleep(Duration::from_millis(millis)));
+        }
+    });
+}
+
+#[test]
+fn test_resize_counts() {
+    let cfg = LatencyTraceCfg::default().with_hist_high(100);
+    let report = LatencyTrace::activated(cfg).unwrap().measure_report(f);
+    let timings = report.timings();
+    let resize_counts = report.resize_counts();
+
+    let growing = timings
+        .span_groups()
+        .find(|sg| sg.name() == "growing")
+        .unwrap();
+    let count = resize_counts.get(growing).copied().unwrap_or(0);
+    assert!(count > 0, "count={count}");
+    assert!(count <= 4, "count={count}");
+    assert!(resize_counts.keys().all(|sg| timings.contains_key(sg)));
+}
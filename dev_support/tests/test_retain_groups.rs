@@ -0,0 +1,54 @@
+use latency_trace::{ConsistencyError, FoldedFrame, FoldedValue, LatencyTrace, Timings};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        trace_span!("empty").in_scope(|| {
+            trace_span!("empty_child").in_scope(|| {});
+        });
+        trace_span!("work").in_scope(|| {});
+    });
+}
+
+fn names(timings: &Timings) -> Vec<&str> {
+    timings.span_groups().map(|sg| sg.name()).collect()
+}
+
+#[test]
+fn test_retain_groups() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+    assert_eq!(timings.len(), 4);
+
+    // Only the matching span group is removed, orphaning its child.
+    let mut retained = timings.clone();
+    retained.retain_groups(|sg| sg.name() != "empty");
+    assert_eq!(names(&retained), vec!["empty_child", "root", "work"]);
+    let errors = retained.validate().unwrap_err();
+    assert!(matches!(errors[0], ConsistencyError::OrphanParent { .. }));
+
+    // The orphaned child is treated as a root.
+    let sg_to_parent = retained.span_group_to_parent();
+    let empty_child = retained
+        .span_groups()
+        .find(|sg| sg.name() == "empty_child")
+        .unwrap();
+    assert_eq!(sg_to_parent[empty_child], None);
+    let folded = retained.to_folded_stacks(FoldedFrame::Name, FoldedValue::Sum, false);
+    assert!(
+        folded.lines().any(|line| line.starts_with("empty_child ")),
+        "{folded}"
+    );
+    assert!(!folded.contains("empty;"), "{folded}");
+
+    // The whole subtree is removed.
+    let mut retained = timings.clone();
+    retained.retain_subtrees(|sg| sg.name() != "empty");
+    assert_eq!(names(&retained), vec!["root", "work"]);
+    assert_eq!(retained.validate(), Ok(()));
+
+    let mut retained = timings.clone();
+    retained.retain_subtrees(|sg| sg.name() != "root");
+    assert!(retained.is_empty());
+}
@@ -0,0 +1,51 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tracing::info_span;
+
+#[test]
+fn test_run_until_stable() {
+    let reports = Arc::new(AtomicUsize::new(0));
+    let cfg = LatencyTraceCfg::default().with_on_report({
+        let reports = reports.clone();
+        move |_| {
+            reports.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+    let lt = LatencyTrace::activated(cfg).unwrap();
+    // Stable workload.
+    let f = || {
+        for _ in 0..5 {
+            info_span!("work").in_scope(|| thread::sleep(Duration::from_millis(2)));
+        }
+    };
+
+    // Obtain the span group ID.
+    let timings = lt.measure_latencies(f);
+    let group_id = timings.keys().next().unwrap().id().to_owned();
+
+    let batches = Cell::new(0_u64);
+    let max_duration = Duration::from_secs(10);
+    let start = Instant::now();
+    let timings = lt.run_until_stable(&group_id, 0.5, 0.2, max_duration, || {
+        batches.set(batches.get() + 1);
+        f();
+    });
+    let elapsed = start.elapsed();
+
+    assert!(elapsed < max_duration / 2, "elapsed={elapsed:?}");
+    assert!(batches.get() >= 2, "batches={}", batches.get());
+
+    let timing = &timings[timings.keys().next().unwrap()];
+    assert_eq!(timing.len(), batches.get() * 5);
+
+    // One report for each measurement, not for each probe.
+    assert_eq!(reports.load(Ordering::Relaxed), 2);
+}
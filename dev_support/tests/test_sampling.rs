@@ -0,0 +1,36 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..100 {
+            trace_span!("hot").in_scope(|| {});
+        }
+        trace_span!("rare").in_scope(|| {});
+    });
+}
+
+#[test]
+fn test_sampling() {
+    let cfg = LatencyTraceCfg::default().with_sampling(0.1);
+    assert_eq!(cfg.sampling(), Some(0.1));
+    assert_eq!(
+        LatencyTraceCfg::default().with_sampling(1.0).sampling(),
+        None
+    );
+
+    let report = LatencyTrace::activated(cfg).unwrap().measure_report(f);
+    let timings = report.timings();
+    let instance_counts = report.instance_counts();
+    assert_eq!(timings.group_count(), 3);
+
+    for (sg, timing) in timings.iter() {
+        let (recorded, closed) = match sg.name() {
+            "hot" => (10, 100),
+            "rare" | "root" => (1, 1),
+            name => panic!("unexpected span group {name}"),
+        };
+        assert_eq!(timing.len(), recorded, "{}", sg.name());
+        assert_eq!(instance_counts[sg], closed, "{}", sg.name());
+    }
+}
@@ -0,0 +1,25 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        trace_span!("fast").in_scope(|| {});
+        // Exceeds a low `hist_high`.
+        trace_span!("slow").in_scope(|| thread::sleep(Duration::from_millis(2)));
+    });
+}
+
+#[test]
+fn test_saturated_groups() {
+    let cfg = LatencyTraceCfg::default().with_hist_high(1000);
+    let timings = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+
+    let names: Vec<&str> = timings
+        .saturated_groups()
+        .into_iter()
+        .map(|sg| sg.name())
+        .collect();
+    assert!(names.contains(&"slow"), "names={names:?}");
+    assert!(!names.contains(&"fast"), "names={names:?}");
+}
@@ -0,0 +1,35 @@
+use latency_trace::LatencyTrace;
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    for _ in 0..4 {
+        info_span!("root").in_scope(|| {
+            thread::sleep(Duration::from_millis(10));
+            for _ in 0..2 {
+                trace_span!("child").in_scope(|| {
+                    thread::sleep(Duration::from_millis(5));
+                });
+            }
+        });
+    }
+}
+
+#[test]
+fn test_self_time() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+    let self_time = timings.self_time();
+    assert_eq!(self_time.len(), 2);
+
+    let ms = |micros: f64| micros / 1000.0;
+    for (sg, self_time) in self_time.iter() {
+        match sg.name() {
+            // 20ms total minus 2 children of 5ms each.
+            "root" => assert!((10.0..13.0).contains(&ms(*self_time)), "{self_time}"),
+            "child" => assert!((5.0..7.0).contains(&ms(*self_time)), "{self_time}"),
+            name => panic!("unexpected span group {name}"),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::{panic, thread, time::Duration};
+use tracing::{info_span, instrument};
+
+#[instrument(level = "trace")]
+fn g(fail: bool) {
+    thread::sleep(Duration::from_micros(100));
+    if fail {
+        panic!("injected fault");
+    }
+}
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..4 {
+            g(false);
+        }
+        for _ in 0..2 {
+            let res = panic::catch_unwind(|| g(true));
+            assert!(res.is_err());
+        }
+    });
+}
+
+#[test]
+fn test_separate_panicked() {
+    // Suppress panic messages from the injected faults.
+    panic::set_hook(Box::new(|_| {}));
+
+    let lt_cfg = LatencyTraceCfg::default().with_separate_panicked(true);
+    let latencies = LatencyTrace::activated(lt_cfg)
+        .unwrap()
+        .measure_latencies(f);
+
+    let g_groups: Vec<_> = latencies
+        .iter()
+        .filter(|(sg, _)| sg.name() == "g")
+        .collect();
+    assert_eq!(g_groups.len(), 2, "g groups: {:?}", g_groups);
+
+    for (sg, timing) in g_groups {
+        let panicked = sg
+            .props()
+            .contains(&("panicked".to_owned(), "true".to_owned()));
+        let expected_count = if panicked { 2 } else { 4 };
+        assert_eq!(timing.len(), expected_count, "span group {:?}", sg);
+    }
+
+    // The root span was not closed while panicking.
+    let root_groups = latencies.keys().filter(|sg| sg.name() == "root").count();
+    assert_eq!(root_groups, 1);
+}
@@ -0,0 +1,44 @@
+use latency_trace::{group_by_all_fields, LatencyTrace, LatencyTraceCfg, OVERFLOW_PROP};
+use tracing::info_span;
+
+#[test]
+fn test_server_preset() {
+    let default = LatencyTraceCfg::default();
+    let preset = LatencyTraceCfg::server_preset();
+
+    assert_eq!(default.hist_high(), 20 * 1000 * 1000);
+    assert_eq!(preset.hist_high(), 600 * 1000 * 1000);
+
+    assert_eq!(preset.hist_sigfig(), default.hist_sigfig());
+    assert_eq!(preset.source_root(), default.source_root());
+
+    assert!(!default.separate_panicked());
+    assert!(preset.separate_panicked());
+
+    assert_eq!(default.max_groups(), None);
+    assert_eq!(preset.max_groups(), Some(1000));
+
+    // The cap applies to a span grouper set after the preset.
+    let cfg = LatencyTraceCfg::server_preset()
+        .with_max_groups(Some(2))
+        .with_span_grouper(group_by_all_fields);
+    let timings = LatencyTrace::with_scoped(cfg, || {
+        for user in 0..5 {
+            info_span!("request", user).in_scope(|| {});
+        }
+    })
+    .unwrap();
+    let mut groups: Vec<_> = timings
+        .iter()
+        .map(|(sg, timing)| (sg.props().to_vec(), timing.len()))
+        .collect();
+    groups.sort();
+    assert_eq!(
+        groups,
+        [
+            (vec![(OVERFLOW_PROP.to_owned(), "other".to_owned())], 3),
+            (vec![("user".to_owned(), "0".to_owned())], 1),
+            (vec![("user".to_owned(), "1".to_owned())], 1),
+        ]
+    );
+}
@@ -0,0 +1,67 @@
+use latency_trace::{group_by_all_fields, LatencyTrace, LatencyTraceCfg, Timings};
+use tracing::{info_span, trace_span};
+
+fn warm_up() {
+    for _ in 0..3 {
+        info_span!("warm_up").in_scope(|| {});
+    }
+}
+
+fn workload(id: u64) {
+    info_span!("workload", id).in_scope(|| {
+        trace_span!("step").in_scope(|| {});
+    });
+}
+
+/// Returns the count of each span group, identified by its name and property values.
+fn counts(latencies: &Timings) -> Vec<(String, u64)> {
+    latencies
+        .iter()
+        .map(|(sg, timing)| {
+            let values: Vec<&str> = sg.props().iter().map(|(_, v)| v.as_str()).collect();
+            (format!("{}{:?}", sg.name(), values), timing.len())
+        })
+        .collect()
+}
+
+#[test]
+fn test_snapshot_restore() {
+    let lt =
+        LatencyTrace::activated(LatencyTraceCfg::default().with_span_grouper(group_by_all_fields))
+            .unwrap();
+
+    warm_up();
+    let snapshot = lt.snapshot();
+    // The snapshot is not attributed to the thread that recorded it.
+    assert!(lt
+        .report_timings_per_thread(vec![snapshot.clone()])
+        .is_empty());
+
+    let latencies_a = lt.measure_latencies(|| workload(1));
+
+    lt.restore(snapshot.clone());
+    let latencies_b = lt.measure_latencies(|| workload(2));
+
+    // Both branches include the warm-up and only their own workload.
+    for (latencies, own, other) in [(&latencies_a, 1, 2), (&latencies_b, 2, 1)] {
+        let counts = counts(latencies);
+        assert_eq!(counts.len(), 3);
+        assert!(counts.contains(&("warm_up[]".to_owned(), 3)));
+        let has_workload = |id: u64| {
+            counts
+                .iter()
+                .any(|(group, _)| *group == format!("workload[\"{id}\"]"))
+        };
+        assert!(has_workload(own));
+        assert!(!has_workload(other));
+    }
+
+    // Restoring discards the latencies collected since the last report.
+    workload(3);
+    lt.restore(snapshot);
+    let latencies_restored = lt.measure_latencies(|| {});
+    assert_eq!(
+        counts(&latencies_restored),
+        vec![("warm_up[]".to_owned(), 3)]
+    );
+}
@@ -0,0 +1,56 @@
+use latency_trace::{testing::measure_latencies_isolated, LatencyTraceCfg, Timings};
+use tracing::{info_span, instrument};
+
+#[instrument(level = "trace")]
+fn g() {}
+
+fn f() {
+    info_span!("root").in_scope(g);
+}
+
+fn ids(latencies: &Timings) -> Vec<&str> {
+    latencies.keys().map(|sg| sg.id()).collect()
+}
+
+#[test]
+fn test_source_root() {
+    let latencies_full = measure_latencies_isolated(LatencyTraceCfg::default(), f);
+    let latencies_rel = measure_latencies_isolated(
+        LatencyTraceCfg::default().with_source_root("dev_support"),
+        f,
+    );
+    let latencies_rel_slash = measure_latencies_isolated(
+        LatencyTraceCfg::default().with_source_root("dev_support/"),
+        f,
+    );
+    let latencies_other = measure_latencies_isolated(
+        LatencyTraceCfg::default().with_source_root("some/other/dir"),
+        f,
+    );
+
+    assert_eq!(latencies_full.len(), 2);
+    for sg in latencies_full.keys() {
+        assert!(
+            sg.code_line()
+                .starts_with("dev_support/tests/test_source_root.rs:"),
+            "code_line={}",
+            sg.code_line()
+        );
+    }
+
+    assert_eq!(latencies_rel.len(), 2);
+    for sg in latencies_rel.keys() {
+        assert!(
+            sg.code_line().starts_with("tests/test_source_root.rs:"),
+            "code_line={}",
+            sg.code_line()
+        );
+    }
+
+    // Ids are computed from the relative paths.
+    assert_ne!(ids(&latencies_full), ids(&latencies_rel));
+    assert_eq!(ids(&latencies_rel), ids(&latencies_rel_slash));
+
+    // Paths not under the source root are left unchanged.
+    assert_eq!(ids(&latencies_full), ids(&latencies_other));
+}
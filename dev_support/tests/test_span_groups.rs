@@ -0,0 +1,31 @@
+use latency_trace::LatencyTrace;
+use std::collections::BTreeSet;
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..3 {
+            trace_span!("a").in_scope(|| {
+                trace_span!("b").in_scope(|| {});
+            });
+        }
+        trace_span!("b").in_scope(|| {});
+    });
+}
+
+#[test]
+fn test_span_groups() {
+    let latencies = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    // Groups: root, root/a, root/a/b, root/b.
+    assert_eq!(latencies.group_count(), 4);
+
+    let ids: BTreeSet<&str> = latencies.span_groups().map(|sg| sg.id()).collect();
+    assert_eq!(ids.len(), latencies.group_count());
+
+    let names: Vec<&str> = latencies.span_groups().map(|sg| sg.name()).collect();
+    let expected_names: Vec<&str> = latencies.keys().map(|sg| sg.name()).collect();
+    assert_eq!(names, expected_names);
+}
@@ -0,0 +1,48 @@
+use latency_trace::{LatencyTrace, Timings};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root", foo = 1).in_scope(|| {
+        trace_span!("child").in_scope(|| {});
+    });
+}
+
+/// Replaces the recorded values of each span group with values around `base`, shifted by `jitter`.
+fn synthetic(timings: &Timings, base: u64, jitter: u64) -> Timings {
+    let mut timings = timings.clone();
+    for (sg, timing) in timings.iter_mut() {
+        let base = base * sg.depth() as u64;
+        timing.reset();
+        for i in 0..100 {
+            timing.record(base + 2 * i + jitter).unwrap();
+        }
+    }
+    timings
+}
+
+#[test]
+fn test_stable_text() {
+    let latencies = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    let text1 = synthetic(&latencies, 12_100, 0).to_stable_text();
+    let text2 = synthetic(&latencies, 12_100, 3).to_stable_text();
+
+    // Near-identical latencies produce identical text.
+    assert_eq!(text1, text2);
+
+    // A real change shows up.
+    let text3 = synthetic(&latencies, 24_200, 0).to_stable_text();
+    assert_ne!(text1, text3);
+
+    // One line per span group, sorted by id.
+    let lines: Vec<&str> = text1.lines().collect();
+    assert_eq!(lines.len(), latencies.group_count());
+    let mut ids: Vec<&str> = latencies.span_groups().map(|sg| sg.id()).collect();
+    ids.sort();
+    for (line, id) in lines.iter().zip(ids) {
+        assert!(line.starts_with(id), "line={line}, id={id}");
+    }
+    assert!(text1.contains(r#"count=     100 mean=   12000 p50=   12000"#));
+}
@@ -0,0 +1,31 @@
+use hdrhistogram::Histogram;
+use latency_trace::{summary_stats, TimeUnit, Timing};
+
+#[test]
+fn test_summary_stats_cv() {
+    let mut hist = Timing::new(
+        Histogram::<u64>::new_with_bounds(1, 1_000_000, 3).unwrap(),
+        TimeUnit::Micros,
+    );
+    for value in [1000, 1000, 1000, 1000] {
+        hist.record(value).unwrap();
+    }
+    let stats = summary_stats(&hist);
+    assert_eq!(stats.count, 4);
+    assert_eq!(stats.stdev, 0.0);
+    assert_eq!(stats.cv(), 0.0);
+
+    for value in [3000, 3000, 3000, 3000] {
+        hist.record(value).unwrap();
+    }
+    let stats = summary_stats(&hist);
+    assert_eq!(stats.count, 8);
+    assert!((stats.cv() - stats.stdev / stats.mean).abs() < 1e-12);
+    assert!((stats.cv() - 0.5).abs() < 0.01, "cv={}", stats.cv());
+
+    let empty = Timing::new(
+        Histogram::<u64>::new_with_bounds(1, 1_000_000, 3).unwrap(),
+        TimeUnit::Micros,
+    );
+    assert_eq!(summary_stats(&empty).cv(), 0.0);
+}
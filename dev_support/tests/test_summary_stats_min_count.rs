@@ -0,0 +1,51 @@
+use latency_trace::LatencyTrace;
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..3 {
+            trace_span!("sparse").in_scope(|| {});
+        }
+        for _ in 0..200 {
+            trace_span!("dense").in_scope(|| {});
+        }
+    });
+}
+
+#[test]
+fn test_summary_stats_min_count() {
+    let latencies = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    let stats = latencies.summary_stats_min_count(100);
+    let by_name = |name: &str| {
+        stats
+            .iter()
+            .find(|(sg, _)| sg.name() == name)
+            .map(|(_, stats)| stats.clone())
+            .unwrap()
+    };
+
+    let sparse = by_name("sparse");
+    assert_eq!(sparse.count, 3);
+    assert!(sparse.stats.is_none());
+    assert_eq!(sparse.p99(), None);
+
+    let dense = by_name("dense");
+    assert_eq!(dense.count, 200);
+    let dense_p99 = dense.p99().unwrap();
+    assert_eq!(dense_p99, dense.stats.as_ref().unwrap().p99);
+    assert_eq!(
+        dense.mean,
+        latencies
+            .summary_stats()
+            .values()
+            .find(|s| s.count == 200)
+            .unwrap()
+            .mean
+    );
+
+    // The root span group has a single sample.
+    assert_eq!(by_name("root").median(), None);
+}
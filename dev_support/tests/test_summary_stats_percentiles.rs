@@ -0,0 +1,45 @@
+use latency_trace::{
+    summary_stats, summary_stats_with_percentiles, LatencyTrace, DEFAULT_PERCENTILES,
+};
+use std::{thread, time::Duration};
+use tracing::trace_span;
+
+fn f() {
+    for i in 0..20 {
+        trace_span!("work").in_scope(|| thread::sleep(Duration::from_micros(100 * i)));
+    }
+}
+
+#[test]
+fn test_summary_stats_percentiles() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+    let timing = timings.values().next().unwrap();
+
+    let pctls = [50.0, 90.0, 99.0, 99.9];
+    let stats = summary_stats_with_percentiles(timing, &pctls);
+    let requested: Vec<f64> = stats.percentiles.iter().map(|(p, _)| *p).collect();
+    assert_eq!(requested, pctls);
+    for (p, value) in stats.percentiles.iter() {
+        assert_eq!(*value, timing.value_at_percentile(*p));
+    }
+    assert_eq!(stats.percentile(50.0), Some(stats.median));
+    assert_eq!(
+        stats.percentile(99.9),
+        Some(timing.value_at_percentile(99.9))
+    );
+    assert_eq!(stats.percentile(75.0), None);
+
+    // The default percentiles are used by `summary_stats`.
+    let default_stats = summary_stats(timing);
+    let default_pctls: Vec<f64> = default_stats.percentiles.iter().map(|(p, _)| *p).collect();
+    assert_eq!(default_pctls, DEFAULT_PERCENTILES);
+    assert_eq!(default_stats.percentile(99.0), Some(default_stats.p99));
+
+    let stats_map = timings.summary_stats_with_percentiles(&pctls);
+    assert_eq!(
+        stats_map.values().next().unwrap().percentiles,
+        stats.percentiles
+    );
+}
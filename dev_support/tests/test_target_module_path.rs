@@ -0,0 +1,28 @@
+use latency_trace::LatencyTrace;
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        trace_span!(target: "my_subsystem", "child").in_scope(|| {});
+    });
+}
+
+#[test]
+fn test_target_module_path() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+    assert_eq!(timings.group_count(), 2);
+
+    for sg in timings.span_groups() {
+        let expected_target = match sg.name() {
+            "root" => module_path!(),
+            _ => "my_subsystem",
+        };
+        assert_eq!(sg.target(), expected_target);
+        assert_eq!(sg.module_path(), Some(module_path!()));
+    }
+
+    let by_target = timings.aggregate(|sg| sg.target());
+    assert_eq!(by_target.len(), 2);
+}
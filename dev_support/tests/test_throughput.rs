@@ -0,0 +1,36 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+const NREPEATS: u64 = 20;
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        for _ in 0..NREPEATS {
+            trace_span!("request").in_scope(|| thread::sleep(Duration::from_millis(5)));
+        }
+    });
+}
+
+#[test]
+fn test_throughput() {
+    // Only a fraction of the latencies is recorded, but all closed spans are counted.
+    let cfg = LatencyTraceCfg::default().with_record_only_nth(2);
+    let report = LatencyTrace::activated(cfg).unwrap().measure_report(f);
+    let duration = report.duration();
+    assert!(
+        duration >= Duration::from_millis(5 * NREPEATS),
+        "{duration:?}"
+    );
+
+    let request = report
+        .timings()
+        .span_groups()
+        .find(|sg| sg.name() == "request")
+        .unwrap();
+    assert_eq!(report.timings()[request].len(), 1);
+    let throughput = report.throughput(request).unwrap();
+    assert_eq!(throughput, NREPEATS as f64 / duration.as_secs_f64());
+    // At most one request per 5 ms.
+    assert!(throughput > 0.0 && throughput <= 200.0, "{throughput}");
+}
@@ -0,0 +1,47 @@
+use latency_trace::LatencyTrace;
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    for _ in 0..5 {
+        info_span!("handler").in_scope(|| {
+            thread::sleep(Duration::from_millis(2));
+            for _ in 0..2 {
+                trace_span!("db").in_scope(|| thread::sleep(Duration::from_millis(5)));
+            }
+            trace_span!("cache").in_scope(|| thread::sleep(Duration::from_millis(1)));
+        });
+    }
+}
+
+#[test]
+fn test_time_excluding_child() {
+    let latencies = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    let timing_of = |name: &str| latencies.iter().find(|(sg, _)| sg.name() == name).unwrap();
+    let (handler, handler_timing) = timing_of("handler");
+    let (_, db_timing) = timing_of("db");
+
+    let excluded = latencies.time_excluding_child(handler.id(), "db").unwrap();
+
+    // The mean drops by the db time per handler span: 2 db spans per handler span.
+    let db_contribution = db_timing.mean() * db_timing.len() as f64 / handler_timing.len() as f64;
+    let expected_mean = handler_timing.mean() - db_contribution;
+    assert_eq!(excluded.len(), handler_timing.len());
+    assert!(
+        (excluded.mean() - expected_mean).abs() <= expected_mean * 0.02,
+        "excluded mean={}, expected={expected_mean}",
+        excluded.mean()
+    );
+    assert!(excluded.mean() < handler_timing.mean() - 9000.0);
+
+    // Excluding a child name that doesn't exist leaves the timing unchanged.
+    let unchanged = latencies
+        .time_excluding_child(handler.id(), "unknown")
+        .unwrap();
+    assert_eq!(unchanged.mean(), handler_timing.mean());
+
+    assert!(latencies.time_excluding_child("unknown", "db").is_none());
+}
@@ -0,0 +1,34 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg, TimeUnit};
+use std::{thread, time::Duration};
+use tracing::trace_span;
+
+fn f() {
+    trace_span!("sleep").in_scope(|| thread::sleep(Duration::from_millis(5)));
+}
+
+#[test]
+fn test_time_unit() {
+    let cfg = LatencyTraceCfg::default();
+    assert_eq!(cfg.time_unit(), TimeUnit::Micros);
+
+    let cfg = cfg.with_time_unit(TimeUnit::Nanos);
+    assert_eq!(cfg.time_unit(), TimeUnit::Nanos);
+    assert_eq!(cfg.hist_high(), 20_000_000_000);
+    assert_eq!(cfg.with_time_unit(TimeUnit::Millis).hist_high(), 20_000);
+
+    let lt = LatencyTrace::activated(cfg).unwrap();
+    let timings = lt.measure_latencies(f);
+    let timing = timings.values().next().unwrap();
+    assert!(timing.min() >= 5_000_000, "min={}", timing.min());
+
+    assert_eq!(timing.unit(), TimeUnit::Nanos);
+
+    // Summary statistics take the configured unit from the timings.
+    let stats = timings.summary_stats();
+    let stats = stats.values().next().unwrap();
+    assert_eq!(stats.unit, TimeUnit::Nanos);
+    assert!(stats.to_string().contains(&format!("max={}ns", stats.max)));
+    let stats = timings.summary_stats_min_count(1);
+    let stats = stats.values().next().unwrap().stats.as_ref().unwrap();
+    assert_eq!(stats.unit, TimeUnit::Nanos);
+}
@@ -0,0 +1,49 @@
+use latency_trace::{LatencyTrace, SpanGroupDiff};
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+fn work(millis: u64) {
+    trace_span!("work").in_scope(|| thread::sleep(Duration::from_millis(millis)));
+}
+
+fn run(millis: u64, extra: impl FnOnce()) {
+    info_span!("root").in_scope(|| {
+        for _ in 0..5 {
+            work(millis);
+        }
+        extra();
+    });
+}
+
+fn baseline() {
+    run(1, || trace_span!("old").in_scope(|| {}));
+}
+
+fn current() {
+    run(5, || trace_span!("new").in_scope(|| {}));
+}
+
+#[test]
+fn test_timings_diff() {
+    let lt = LatencyTrace::activated_default().unwrap();
+    let baseline = lt.measure_latencies(baseline);
+    let current = lt.measure_latencies(current);
+
+    let diff = current.diff(&baseline);
+    assert_eq!(diff.len(), 4);
+
+    for (sg, sg_diff) in diff.iter() {
+        match (sg.name(), sg_diff) {
+            ("work", SpanGroupDiff::Changed(stats_diff)) => {
+                assert_eq!(stats_diff.count, (5, 5));
+                // The median is compared because the tail is sensitive to scheduling jitter.
+                assert!(stats_diff.median.abs > 2000.0, "{stats_diff:?}");
+                assert!(stats_diff.median.pct.unwrap() > 100.0, "{stats_diff:?}");
+            }
+            ("root", SpanGroupDiff::Changed(stats_diff)) => assert!(stats_diff.mean.abs > 0.0),
+            ("new", SpanGroupDiff::Added(stats)) => assert_eq!(stats.count, 1),
+            ("old", SpanGroupDiff::Removed(stats)) => assert_eq!(stats.count, 1),
+            (name, sg_diff) => panic!("unexpected diff for {name}: {sg_diff:?}"),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use latency_trace::{LatencyTrace, JSON_SCHEMA_VERSION};
+use serde_json::Value;
+use std::{collections::BTreeMap, thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    for i in 0..3 {
+        info_span!("root", i).in_scope(|| {
+            trace_span!("child").in_scope(|| {
+                trace_span!("grandchild").in_scope(|| thread::sleep(Duration::from_millis(1)));
+            });
+        });
+    }
+}
+
+#[test]
+fn test_timings_json() {
+    let timings = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    let json: Value = serde_json::from_str(&timings.to_json()).unwrap();
+    assert_eq!(json["schema_version"], JSON_SCHEMA_VERSION);
+    let entries = json["timings"].as_array().unwrap();
+    assert_eq!(entries.len(), timings.len());
+
+    for ((sg, timing), entry) in timings.iter().zip(entries) {
+        let span_group = &entry["span_group"];
+        assert_eq!(span_group["name"], sg.name());
+        assert_eq!(span_group["id"], sg.id());
+        assert_eq!(span_group["code_line"], sg.code_line());
+        assert_eq!(span_group["depth"], sg.depth());
+        assert_eq!(
+            span_group["props"].as_array().unwrap().len(),
+            sg.props().len()
+        );
+
+        let summary = &entry["timing"];
+        assert_eq!(summary["count"], timing.len());
+        assert_eq!(summary["max"], timing.max());
+        assert_eq!(
+            summary["percentiles"]["p50"],
+            timing.value_at_percentile(50.0)
+        );
+    }
+
+    // The span group tree can be reconstructed from the ids and parent ids.
+    let id_to_name: BTreeMap<&str, &str> = entries
+        .iter()
+        .map(|e| {
+            let sg = &e["span_group"];
+            (sg["id"].as_str().unwrap(), sg["name"].as_str().unwrap())
+        })
+        .collect();
+    let json_parent_names: BTreeMap<&str, Option<&str>> = entries
+        .iter()
+        .map(|e| {
+            let sg = &e["span_group"];
+            let parent_name = sg["parent_id"].as_str().map(|id| id_to_name[id]);
+            (sg["name"].as_str().unwrap(), parent_name)
+        })
+        .collect();
+    let parent_names: BTreeMap<&str, Option<&str>> = timings
+        .span_group_to_parent()
+        .iter()
+        .map(|(sg, parent)| (sg.name(), parent.as_ref().map(|p| p.name())))
+        .collect();
+    assert_eq!(json_parent_names, parent_names);
+
+    let json: Value = serde_json::from_str(&timings.to_json_with_percentiles(&[99.9])).unwrap();
+    let percentiles = json["timings"][0]["timing"]["percentiles"]
+        .as_object()
+        .unwrap();
+    assert_eq!(
+        percentiles.keys().collect::<Vec<_>>(),
+        vec![&"p99.9".to_owned()]
+    );
+}
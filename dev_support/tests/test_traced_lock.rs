@@ -0,0 +1,62 @@
+use latency_trace::{LatencyTrace, TracedMutex, TracedRwLock};
+use std::{thread, time::Duration};
+use tracing::info_span;
+
+const NTHREADS: usize = 4;
+
+fn f() {
+    let counter = TracedMutex::new("counter", 0);
+    let config = TracedRwLock::new("config", "initial");
+
+    thread::scope(|s| {
+        for _ in 0..NTHREADS {
+            s.spawn(|| {
+                info_span!("worker").in_scope(|| {
+                    // Each thread holds the lock for a while, so the other threads wait for it.
+                    let mut count = counter.lock().unwrap();
+                    thread::sleep(Duration::from_millis(10));
+                    *count += 1;
+                    drop(count);
+
+                    let _ = *config.read().unwrap();
+                })
+            });
+        }
+    });
+
+    *config.write().unwrap() = "updated";
+    assert_eq!(counter.into_inner().unwrap(), NTHREADS);
+}
+
+#[test]
+fn test_traced_lock() {
+    let latencies = LatencyTrace::activated_default()
+        .unwrap()
+        .measure_latencies(f);
+
+    let lock_group = |name: &str| {
+        let groups: Vec<_> = latencies
+            .span_groups()
+            .filter(|sg| sg.name() == name)
+            .collect();
+        assert_eq!(groups.len(), 1, "name={name}");
+        groups[0]
+    };
+
+    let sg = lock_group("lock");
+    assert_eq!(sg.props(), &[("lock".to_owned(), "counter".to_owned())]);
+    assert_eq!(sg.depth(), 2);
+    let timing = &latencies[sg];
+    assert_eq!(timing.len(), NTHREADS as u64);
+    // The last thread to acquire the lock waits for all the others to release it.
+    let expected_max_wait = (NTHREADS as u64 - 1) * 10_000;
+    assert!(timing.max() >= expected_max_wait, "max={}", timing.max());
+
+    let sg = lock_group("read_lock");
+    assert_eq!(sg.props(), &[("lock".to_owned(), "config".to_owned())]);
+    assert_eq!(latencies[sg].len(), NTHREADS as u64);
+
+    let sg = lock_group("write_lock");
+    assert_eq!(sg.props(), &[("lock".to_owned(), "config".to_owned())]);
+    assert_eq!(sg.depth(), 1);
+}
@@ -0,0 +1,61 @@
+use latency_trace::{testing::measure_latencies_isolated, ConsistencyError, LatencyTraceCfg};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("root").in_scope(|| {
+        trace_span!("a").in_scope(|| {
+            trace_span!("b").in_scope(|| {});
+        });
+    });
+}
+
+#[test]
+fn test_validate() {
+    let full = measure_latencies_isolated(LatencyTraceCfg::default(), f);
+    let dropped = measure_latencies_isolated(LatencyTraceCfg::default().with_drop_root(true), f);
+
+    assert_eq!(full.validate(), Ok(()));
+    assert_eq!(dropped.validate(), Ok(()));
+
+    let sg = |timings: &latency_trace::Timings, name: &str| {
+        timings.keys().find(|sg| sg.name() == name).unwrap().clone()
+    };
+
+    // Orphan parent_id: remove the root span group.
+    {
+        let mut timings = full.clone();
+        let root = sg(&full, "root");
+        timings.remove(&root);
+        let a = sg(&full, "a");
+        assert_eq!(
+            timings.validate(),
+            Err(vec![ConsistencyError::OrphanParent {
+                id: a.id().to_owned(),
+                parent_id: root.id().to_owned(),
+            }])
+        );
+    }
+
+    // Wrong depth: add the span group `b` of depth 3 to a report whose span group `a` has depth 1.
+    // IDs are unchanged by `drop_root`, so `b` is also duplicated.
+    {
+        let mut timings = dropped.clone();
+        let b = sg(&full, "b");
+        timings.insert(b.clone(), full[&b].clone());
+        let mut errors = timings.validate().unwrap_err();
+        errors.sort();
+        assert_eq!(
+            errors,
+            vec![
+                ConsistencyError::DuplicateId {
+                    id: b.id().to_owned(),
+                },
+                ConsistencyError::WrongDepth {
+                    id: b.id().to_owned(),
+                    depth: 3,
+                    expected: 2,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg};
+use std::{thread, time::Duration};
+use tracing::{info_span, trace_span};
+
+fn f() {
+    info_span!("parent").in_scope(|| {
+        trace_span!("first_child").in_scope(|| thread::sleep(Duration::from_millis(5)));
+        trace_span!("second_child").in_scope(|| thread::sleep(Duration::from_millis(5)));
+    });
+}
+
+#[test]
+fn test_waterfall() {
+    let cfg = LatencyTraceCfg::default().with_record_offsets(true);
+    let report = LatencyTrace::activated(cfg).unwrap().measure_report(f);
+    let latencies = report.timings();
+    let offsets = report.offsets();
+
+    assert_eq!(offsets.len(), latencies.len());
+    let offset = |name: &str| {
+        offsets
+            .iter()
+            .find(|(sg, _)| sg.name() == name)
+            .map(|(_, offset)| *offset)
+            .unwrap()
+    };
+
+    assert_eq!(offset("parent"), 0);
+    assert!(
+        offset("second_child") > offset("first_child"),
+        "first_child={}, second_child={}",
+        offset("first_child"),
+        offset("second_child")
+    );
+    assert!(offset("second_child") >= 5_000);
+
+    let waterfall = latencies.to_waterfall(offsets);
+    let position = |name: &str| waterfall.find(&format!("\"name\": \"{name}\"")).unwrap();
+    assert!(position("parent") < position("first_child"));
+    assert!(position("first_child") < position("second_child"));
+    assert!(waterfall.contains("\"start_offset\": 0"));
+    assert!(waterfall.contains("\"median\""));
+}
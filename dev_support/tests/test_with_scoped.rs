@@ -0,0 +1,53 @@
+use latency_trace::{group_by_given_fields, LatencyTrace, LatencyTraceCfg};
+use std::thread;
+use tracing::{dispatcher, info_span, trace_span};
+
+fn global() {
+    info_span!("global", user = 1).in_scope(|| {});
+}
+
+fn scoped() {
+    for user in 0..2 {
+        info_span!("scoped", user).in_scope(|| {
+            trace_span!("scoped_child").in_scope(|| {});
+        });
+    }
+    // A spawned thread only records if it sets the scoped subscriber as its default.
+    let dispatch = dispatcher::get_default(|d| d.clone());
+    thread::scope(|s| {
+        s.spawn(|| dispatcher::with_default(&dispatch, || info_span!("spawned").in_scope(|| {})));
+    });
+}
+
+#[test]
+fn test_with_scoped() {
+    let lt = LatencyTrace::activated_default().unwrap();
+
+    let global_timings = lt.measure_latencies(global);
+    assert_eq!(global_timings.len(), 1);
+
+    let cfg = LatencyTraceCfg::default().with_span_grouper(group_by_given_fields(&["user"]));
+    for _ in 0..2 {
+        let scoped_timings = LatencyTrace::with_scoped(cfg.clone(), scoped).unwrap();
+        let mut names: Vec<_> = scoped_timings.keys().map(|sg| sg.name()).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "scoped",
+                "scoped",
+                "scoped_child",
+                "scoped_child",
+                "spawned"
+            ]
+        );
+        for (sg, timing) in scoped_timings.iter() {
+            assert_eq!(timing.len(), 1, "{sg:?}");
+        }
+    }
+
+    // The scoped spans are not recorded by the global instance.
+    let global_timings = lt.measure_latencies(global);
+    assert_eq!(global_timings.len(), 1);
+    assert_eq!(global_timings.values().next().unwrap().len(), 1);
+}
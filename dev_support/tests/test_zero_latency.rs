@@ -0,0 +1,19 @@
+use latency_trace::{LatencyTrace, LatencyTraceCfg, TimeUnit};
+use tracing::trace_span;
+
+fn f() {
+    for _ in 0..100 {
+        // Closed immediately, so its latency is 0 in milliseconds.
+        drop(trace_span!("empty"));
+    }
+}
+
+#[test]
+fn test_zero_latency() {
+    let cfg = LatencyTraceCfg::default().with_time_unit(TimeUnit::Millis);
+    let timings = LatencyTrace::activated(cfg).unwrap().measure_latencies(f);
+    let timing = timings.values().next().unwrap();
+    assert_eq!(timing.len(), 100);
+    assert_eq!(timing.min(), 1);
+    assert_eq!(timing.max(), 1);
+}
@@ -1,6 +1,6 @@
 //! Undocumented functions to support benchmarks in `dev_support` crate.]
 
-use crate::LatencyTrace;
+use crate::{LatencyTrace, RawTrace, Timings};
 use std::{future::Future, hint::black_box};
 
 /// Set-up for measurement of latencies.
@@ -27,3 +27,15 @@ where
             .block_on(f());
     })
 }
+
+/// Executes tracing up to completion of instrumented function and returns the collected thread-local data, before
+/// aggregation.
+pub fn collect_raw_traces(lt: &LatencyTrace, f: impl FnOnce()) -> Vec<RawTrace> {
+    f();
+    lt.0.take_acc_timings()
+}
+
+/// Post-processing of the thread-local data returned by [`collect_raw_traces`].
+pub fn report_timings(lt: &LatencyTrace, acc: Vec<RawTrace>) -> Timings {
+    lt.0.report_timings(acc)
+}
@@ -0,0 +1,60 @@
+//! Estimation of the overhead of the instrumentation itself.
+
+use crate::{LatencyTrace, LatencyTraceCfg};
+use std::{hint::black_box, time::Instant};
+
+/// Number of batches of empty spans measured by [`LatencyTrace::calibrate`].
+const CALIBRATION_BATCHES: usize = 7;
+
+/// Number of empty spans per batch measured by [`LatencyTrace::calibrate`].
+const CALIBRATION_BATCH_SIZE: u32 = 2000;
+
+/// Estimate of the overhead of the instrumentation, returned by [`LatencyTrace::calibrate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverheadEstimate {
+    per_span_nanos: f64,
+    spans_measured: u64,
+}
+
+impl OverheadEstimate {
+    /// Estimated time, in nanoseconds, to create, enter, exit, close, and record a span.
+    pub fn per_span_nanos(&self) -> f64 {
+        self.per_span_nanos
+    }
+
+    /// Number of empty spans measured to compute the estimate.
+    pub fn spans_measured(&self) -> u64 {
+        self.spans_measured
+    }
+}
+
+impl LatencyTrace {
+    /// Estimates the per-span overhead of the instrumentation, i.e., the time taken by the *tracing* framework and
+    /// [`LatencyTrace`] to create, enter, exit, close, and record a span, by measuring many empty spans. The estimate
+    /// can be subtracted from measured latencies to correct for the instrumentation overhead of child spans.
+    ///
+    /// Empty spans are measured in batches with [`LatencyTrace::with_scoped`] and the default configuration, so this
+    /// does not affect any active [`LatencyTrace`]. The
+    /// estimate is the median across batches of the mean time per span, which reduces the impact of outliers
+    /// caused by preemption.
+    pub fn calibrate() -> OverheadEstimate {
+        let mut batch_nanos: Vec<f64> = Vec::with_capacity(CALIBRATION_BATCHES);
+        // The measured latencies are discarded.
+        LatencyTrace::with_scoped(LatencyTraceCfg::default(), || {
+            batch_nanos.extend((0..CALIBRATION_BATCHES).map(|_| {
+                let start = Instant::now();
+                for _ in 0..CALIBRATION_BATCH_SIZE {
+                    tracing::trace_span!("calibration").in_scope(|| black_box(()));
+                }
+                start.elapsed().as_nanos() as f64 / CALIBRATION_BATCH_SIZE as f64
+            }));
+        })
+        .expect("default configuration is valid");
+
+        batch_nanos.sort_by(f64::total_cmp);
+        OverheadEstimate {
+            per_span_nanos: batch_nanos[CALIBRATION_BATCHES / 2],
+            spans_measured: CALIBRATION_BATCHES as u64 * CALIBRATION_BATCH_SIZE as u64,
+        }
+    }
+}
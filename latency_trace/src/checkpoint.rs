@@ -0,0 +1,34 @@
+//! Intra-span latency measurement with checkpoints, which is cheaper than the creation of child spans.
+
+use tracing::Event;
+
+use crate::span_groupers::StrFieldReader;
+
+/// Target of the events emitted by [`checkpoint`].
+pub(crate) const CHECKPOINT_TARGET: &str = "latency_trace::checkpoint";
+
+/// Name of the span groups of checkpoint intervals.
+pub(crate) const CHECKPOINT_NAME: &str = "checkpoint";
+
+/// Name of the event field and span group property that holds the checkpoint name.
+pub(crate) const CHECKPOINT_FIELD: &str = "checkpoint";
+
+/// Records the time elapsed since the previous checkpoint in the current span (or since the creation of the current
+/// span if this is its first checkpoint) as a latency of a checkpoint span group.
+///
+/// The checkpoint span group is a child of the current span's span group. Its [`SpanGroup::name`](crate::SpanGroup::name)
+/// is `"checkpoint"` and its [`SpanGroup::props`](crate::SpanGroup::props) are `[("checkpoint", name)]`. Thus,
+/// checkpoints with different names in the same span produce different span groups, with each group recording the
+/// intervals that end at the checkpoint with the corresponding name. The time between the last checkpoint and the
+/// closing of the span is not recorded in any checkpoint span group.
+///
+/// Checkpoints are `trace`-level [`tracing`] events that are processed by [`LatencyTrace`](crate::LatencyTrace), so
+/// they are ignored if there is no current span or if `trace`-level events are filtered out.
+pub fn checkpoint(name: &'static str) {
+    tracing::trace!(target: CHECKPOINT_TARGET, checkpoint = name);
+}
+
+/// Extracts the checkpoint name from an event emitted by [`checkpoint`].
+pub(crate) fn checkpoint_name(event: &Event<'_>) -> Option<String> {
+    StrFieldReader::read(CHECKPOINT_FIELD, |reader| event.record(reader))
+}
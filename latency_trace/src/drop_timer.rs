@@ -0,0 +1,82 @@
+//! Measurement of the latency of [`Drop`] implementations, which is otherwise only captured by manual instrumentation.
+
+use std::{
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+};
+use tracing::span::Attributes;
+
+use crate::span_groupers::StrFieldReader;
+
+/// Target of the spans created by [`DropTimer`].
+pub(crate) const DROP_TARGET: &str = "latency_trace::drop_timer";
+
+/// Name of the span field and span group property that holds the [`DropTimer`] name.
+pub(crate) const DROP_FIELD: &str = "drop";
+
+/// Wrapper that records the latency of dropping the wrapped value.
+///
+/// When a `DropTimer` is dropped, it drops the wrapped value inside a `trace`-level span, so the latency of the
+/// value's [`Drop`] implementation (including the drop of its fields) is recorded in a span group. The span group is a
+/// child of the span group of the span that is current when the value is dropped. Its
+/// [`SpanGroup::name`](crate::SpanGroup::name) is `"drop"` and its [`SpanGroup::props`](crate::SpanGroup::props) are
+/// `[("drop", name)]`, regardless of the configured span grouper.
+///
+/// `DropTimer` dereferences to the wrapped value, so it can replace the value's type in struct fields. As with other
+/// spans, nothing is recorded if `trace`-level spans are filtered out.
+pub struct DropTimer<T> {
+    name: &'static str,
+    value: ManuallyDrop<T>,
+}
+
+impl<T> DropTimer<T> {
+    /// Wraps `value`, recording the latency of its drop in the span group with the given `name`.
+    pub fn new(name: &'static str, value: T) -> Self {
+        Self {
+            name,
+            value: ManuallyDrop::new(value),
+        }
+    }
+
+    /// Returns the name of the span group in which the drop latency is recorded.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Unwraps the value, without recording the latency of its drop.
+    pub fn into_inner(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never used again and its `Drop` implementation does not run.
+        unsafe { ManuallyDrop::take(&mut this.value) }
+    }
+}
+
+impl<T> Deref for DropTimer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for DropTimer<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> Drop for DropTimer<T> {
+    fn drop(&mut self) {
+        let span = tracing::trace_span!(target: DROP_TARGET, "drop", drop = self.name);
+        span.in_scope(|| {
+            // SAFETY: `self.value` is not used after this.
+            unsafe { ManuallyDrop::drop(&mut self.value) }
+        });
+    }
+}
+
+/// Extracts the name from the `name_field` of the attributes of a span created by a [`DropTimer`] or by a traced lock
+/// (see [`TracedMutex`](crate::TracedMutex)).
+pub(crate) fn wrapper_name(attrs: &Attributes<'_>, name_field: &str) -> Option<String> {
+    StrFieldReader::read(name_field, |reader| attrs.record(reader))
+}
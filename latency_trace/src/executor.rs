@@ -0,0 +1,90 @@
+//! Abstraction of the async runtimes used to execute instrumented async functions.
+
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Wake, Waker},
+    thread::{self, Thread},
+};
+
+/// Async executor that can run a future to completion on the current thread, used by
+/// [`LatencyTrace::measure_latencies_with`](crate::LatencyTrace::measure_latencies_with) and
+/// [`LatencyTrace::measure_latencies_probed_with`](crate::LatencyTrace::measure_latencies_probed_with)
+/// to measure async code on any async runtime.
+///
+/// Any async runtime that provides a `block_on`-like function can be adapted by implementing this trait for a type
+/// that holds the runtime, e.g., a caller-built `tokio::runtime::Runtime`.
+pub trait Executor {
+    /// Runs the future `f` to completion, blocking the current thread.
+    fn block_on<F>(&self, f: F)
+    where
+        F: Future<Output = ()>;
+}
+
+/// Fallback [`Executor`] that polls the future on the current thread, parking the thread while the future is pending.
+///
+/// It doesn't provide timers, IO, or task spawning, so it only supports futures that don't depend on the
+/// facilities of a specific async runtime (e.g., `tokio::time::sleep` requires the `tokio` runtime).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BlockingExecutor;
+
+/// Wakes the parked thread that is polling the future.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+impl Executor for BlockingExecutor {
+    fn block_on<F>(&self, f: F)
+    where
+        F: Future<Output = ()>,
+    {
+        let mut f = pin!(f);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        while f.as_mut().poll(&mut cx).is_pending() {
+            thread::park();
+        }
+    }
+}
+
+/// [`Executor`] that runs the future on a new multi-threaded `tokio` runtime.
+/// Present only when the **"tokio"** feature flag is enabled.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioExecutor;
+
+#[cfg(feature = "tokio")]
+impl Executor for TokioExecutor {
+    fn block_on<F>(&self, f: F)
+    where
+        F: Future<Output = ()>,
+    {
+        tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("Tokio runtime error")
+            .block_on(f);
+    }
+}
+
+/// [`Executor`] that runs the future on the global `async-std` runtime, whose tasks are executed on a pool of
+/// threads.
+/// Present only when the **"async-std"** feature flag is enabled.
+#[cfg(feature = "async-std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncStdExecutor;
+
+#[cfg(feature = "async-std")]
+impl Executor for AsyncStdExecutor {
+    fn block_on<F>(&self, f: F)
+    where
+        F: Future<Output = ()>,
+    {
+        async_std::task::block_on(f);
+    }
+}
@@ -0,0 +1,101 @@
+//! Export of [`Timings`] in the folded-stack format of flamegraph tools.
+
+use crate::{SpanGroup, Timings};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// Label of the stack frames produced by [`Timings::to_folded_stacks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoldedFrame {
+    /// The [`SpanGroup::name`].
+    Name,
+    /// The [`SpanGroup::code_line`].
+    CodeLine,
+}
+
+/// Value of the stacks produced by [`Timings::to_folded_stacks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoldedValue {
+    /// The sum of the latencies of all the spans of each span group.
+    Sum,
+    /// The sum of the latencies of all the spans of each span group divided by the number of spans of its root
+    /// span group, i.e., the mean latency of the span group per root span (e.g., per request).
+    Mean,
+}
+
+impl Timings {
+    /// Renders `self` in the folded-stack format used by flamegraph tools (e.g., `inferno` and Brendan Gregg's
+    /// `flamegraph.pl`), with one `root;child;grandchild <value>` line per span group, where each span group is a
+    /// frame and the stack is its path from its root span group. Each root span group produces its own set of lines.
+    ///
+    /// Frames are labeled according to `frame`, with the span group's props appended as `[k1=v1,k2=v2]` if
+    /// `with_props` is `true` and the span group has props. Any `;` in a label is replaced by `,` as it is the frame
+    /// separator.
+    ///
+    /// The value of each line is the span group's self value, in the configured unit, i.e., its value according to
+    /// `value` minus the values of its child span groups (saturating at `0`), as flamegraph tools add the values of
+    /// the children to those of their parents. Lines with a self value of `0` are omitted.
+    pub fn to_folded_stacks(
+        &self,
+        frame: FoldedFrame,
+        value: FoldedValue,
+        with_props: bool,
+    ) -> String {
+        let sg_to_parent = self.span_group_to_parent();
+        let mut children: BTreeMap<&SpanGroup, Vec<&SpanGroup>> = BTreeMap::new();
+        let mut roots: Vec<&SpanGroup> = Vec::new();
+        for (sg, parent) in sg_to_parent.iter() {
+            match parent {
+                Some(parent) => children.entry(parent).or_default().push(sg),
+                None => roots.push(sg),
+            }
+        }
+
+        let sum = |sg: &SpanGroup| {
+            let timing = &self[sg];
+            timing.mean() * timing.len() as f64
+        };
+
+        let mut folded = String::new();
+        for root in roots {
+            let divisor = match value {
+                FoldedValue::Sum => 1.0,
+                FoldedValue::Mean => self[root].len().max(1) as f64,
+            };
+            let mut stack: Vec<(&SpanGroup, String)> =
+                vec![(root, frame_label(root, frame, with_props))];
+            while let Some((sg, path)) = stack.pop() {
+                let sg_children = children.get(sg).map(Vec::as_slice).unwrap_or_default();
+                let children_sum: f64 = sg_children.iter().map(|child| sum(child)).sum();
+                let self_value = ((sum(sg) - children_sum).max(0.0) / divisor).round() as u64;
+                if self_value > 0 {
+                    writeln!(folded, "{path} {self_value}")
+                        .expect("writing to a String can't fail");
+                }
+                // Reversed so that children are popped in order.
+                for child in sg_children.iter().rev() {
+                    let child_path = format!("{path};{}", frame_label(child, frame, with_props));
+                    stack.push((child, child_path));
+                }
+            }
+        }
+        folded
+    }
+}
+
+/// Returns the label of the frame of `sg` in folded stacks.
+fn frame_label(sg: &SpanGroup, frame: FoldedFrame, with_props: bool) -> String {
+    let mut label = match frame {
+        FoldedFrame::Name => sg.name().to_owned(),
+        FoldedFrame::CodeLine => sg.code_line().to_owned(),
+    };
+    if with_props && !sg.props().is_empty() {
+        let props = sg
+            .props()
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        write!(label, "[{props}]").expect("writing to a String can't fail");
+    }
+    label.replace(';', ",")
+}
@@ -37,6 +37,21 @@
 #![doc = include_str!("lib2.md")]
 #![deny(clippy::unwrap_used)]
 
+mod calibration;
+pub use calibration::*;
+
+mod checkpoint;
+pub use checkpoint::checkpoint;
+
+mod drop_timer;
+pub use drop_timer::DropTimer;
+
+mod executor;
+pub use executor::*;
+
+mod folded_stacks;
+pub use folded_stacks::*;
+
 mod lt_collect_g;
 mod lt_refine_g;
 mod lt_report_g;
@@ -52,6 +67,12 @@ pub use span_groupers::*;
 mod summary_stats;
 pub use summary_stats::*;
 
+mod traced_lock;
+pub use traced_lock::{TracedMutex, TracedRwLock};
+
+mod trend;
+pub use trend::*;
+
 mod wrapper;
 pub use wrapper::*;
 
@@ -61,6 +82,23 @@ mod lt_report_g_tokio;
 #[cfg(feature = "tokio")]
 mod lt_pub_tokio;
 
+#[cfg(feature = "async-std")]
+mod lt_report_g_async_std;
+
+#[cfg(feature = "async-std")]
+mod lt_pub_async_std;
+
+#[cfg(all(unix, feature = "signals"))]
+mod probed_trace_signals;
+
+#[cfg(feature = "serde")]
+mod lt_pub_serde;
+#[cfg(feature = "serde")]
+pub use lt_pub_serde::*;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
 #[cfg(feature = "dev")]
 #[doc(hidden)]
 pub mod bench_support;
@@ -1,18 +1,34 @@
 //! Collection of timing information in an efficient way that is not convenient to display.
 
-use hdrhistogram::Histogram;
+use hdrhistogram::{errors::RecordError, Histogram};
 use std::{
-    collections::HashMap,
-    fmt::Debug,
-    hash::Hash,
-    sync::Arc,
+    borrow::Borrow,
+    collections::{BTreeMap, HashMap},
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    ops::{Deref, DerefMut},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    },
     thread::{self, ThreadId},
-    time::Instant,
+    time::{Duration, SystemTime},
+};
+use tracing::{
+    callsite::Identifier, span::Attributes, subscriber::Interest, Event, Id, Level, Metadata,
+    Subscriber,
 };
-use tracing::{callsite::Identifier, span::Attributes, Id, Subscriber};
 use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
 
-use crate::tlc_param::{TlcBase, TlcDirect, TlcParam};
+use crate::{
+    checkpoint::{checkpoint_name, CHECKPOINT_FIELD, CHECKPOINT_NAME, CHECKPOINT_TARGET},
+    drop_timer::{wrapper_name, DROP_FIELD, DROP_TARGET},
+    lt_refine_g::Timings,
+    span_groupers::{bounded_cardinality, group_by_all_fields},
+    tlc_param::{TlcBase, TlcDirect, TlcParam},
+    traced_lock::{LOCK_FIELD, LOCK_TARGET},
+};
 
 //=================
 // Callsite
@@ -22,6 +38,8 @@ use crate::tlc_param::{TlcBase, TlcDirect, TlcParam};
 pub(crate) struct CallsiteInfo {
     pub(crate) callsite_id: Identifier,
     pub(crate) name: &'static str,
+    pub(crate) target: &'static str,
+    pub(crate) module_path: Option<&'static str>,
     pub(crate) file: Option<String>,
     pub(crate) line: Option<u32>,
     pub(crate) parent: Option<Identifier>,
@@ -34,54 +52,244 @@ pub(crate) struct CallsiteInfo {
 
 type CallsiteIdPath = Vec<Identifier>;
 pub(crate) type Props = Vec<(String, String)>;
-type PropsPath = Vec<Arc<Props>>;
+
+/// Node of the cons-list that represents a [`SpanGroupPriv`] path. Nodes are shared through [`Arc`]s by all
+/// the span groups with a common prefix, so a child span group is derived from its parent without copying
+/// the parent's path.
+#[derive(Debug)]
+struct PathNode {
+    callsite_id: Identifier,
+    props: Arc<Props>,
+    parent: Option<Arc<PathNode>>,
+    /// Number of nodes in the path ending at this node.
+    depth: usize,
+}
 
 /// Private form of [`crate::SpanGroup`] used during trace collection, more efficient than [`crate::SpanGroup`] for trace
 /// data collection.
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+///
+/// Cloning is a reference count increment. Equality and hashing are structural, i.e., based on the callsite IDs
+/// and properties of the span group and its ancestors.
+#[derive(Debug, Clone)]
 pub(crate) struct SpanGroupPriv {
-    /// Callsite ID of the span group preceded by the callsite IDs of its ancestors.
-    pub(crate) callsite_id_path: CallsiteIdPath,
-
-    /// Properties of the span group preceded by the properties of its ancestors.
-    pub(crate) props_path: PropsPath,
+    path: Arc<PathNode>,
 }
 
 impl SpanGroupPriv {
+    /// Span group of a root span.
+    pub(crate) fn root(callsite_id: Identifier, props: Arc<Props>) -> Self {
+        SpanGroupPriv {
+            path: Arc::new(PathNode {
+                callsite_id,
+                props,
+                parent: None,
+                depth: 1,
+            }),
+        }
+    }
+
+    /// Span group of a span whose parent span belongs to `self`.
+    pub(crate) fn child(&self, callsite_id: Identifier, props: Arc<Props>) -> Self {
+        SpanGroupPriv {
+            path: Arc::new(PathNode {
+                callsite_id,
+                props,
+                parent: Some(self.path.clone()),
+                depth: self.path.depth + 1,
+            }),
+        }
+    }
+
+    /// Span group with the same ancestors and callsite as `self` but with properties `props`.
+    pub(crate) fn with_props(&self, props: Arc<Props>) -> Self {
+        SpanGroupPriv {
+            path: Arc::new(PathNode {
+                callsite_id: self.path.callsite_id.clone(),
+                props,
+                parent: self.path.parent.clone(),
+                depth: self.path.depth,
+            }),
+        }
+    }
+
     pub(crate) fn parent(&self) -> Option<Self> {
-        let len = self.callsite_id_path.len();
-        if len == 1 {
-            return None;
+        let path = self.path.parent.clone()?;
+        Some(SpanGroupPriv { path })
+    }
+
+    /// Number of span groups in the path from the root span group to `self`, inclusive.
+    pub(crate) fn depth(&self) -> usize {
+        self.path.depth
+    }
+
+    pub(crate) fn props(&self) -> &Arc<Props> {
+        &self.path.props
+    }
+
+    /// Callsite ID of the span group preceded by the callsite IDs of its ancestors.
+    pub(crate) fn callsite_id_path(&self) -> CallsiteIdPath {
+        let mut path = Vec::with_capacity(self.path.depth);
+        let mut node = Some(&self.path);
+        while let Some(n) = node {
+            path.push(n.callsite_id.clone());
+            node = n.parent.as_ref();
+        }
+        path.reverse();
+        path
+    }
+}
+
+impl PartialEq for SpanGroupPriv {
+    fn eq(&self, other: &Self) -> bool {
+        if self.path.depth != other.path.depth {
+            return false;
+        }
+        let mut a = Some(&self.path);
+        let mut b = Some(&other.path);
+        while let (Some(x), Some(y)) = (a, b) {
+            // Shared prefixes are common, so identical nodes are detected without a structural comparison.
+            if Arc::ptr_eq(x, y) {
+                return true;
+            }
+            if x.callsite_id != y.callsite_id || x.props != y.props {
+                return false;
+            }
+            a = x.parent.as_ref();
+            b = y.parent.as_ref();
         }
-        Some(SpanGroupPriv {
-            callsite_id_path: self.callsite_id_path[0..len - 1].into(),
-            props_path: self.props_path[0..len - 1].into(),
-        })
+        true
     }
 }
 
+impl Eq for SpanGroupPriv {}
+
+impl Hash for SpanGroupPriv {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.path.depth.hash(state);
+        let mut node = Some(&self.path);
+        while let Some(n) = node {
+            n.callsite_id.hash(state);
+            n.props.hash(state);
+            node = n.parent.as_ref();
+        }
+    }
+}
+
+/// Property added to the span group of spans closed while their thread is panicking, when
+/// [`LatencyTraceCfg::with_separate_panicked`] is enabled.
+pub(crate) const PANICKED_PROP: (&str, &str) = ("panicked", "true");
+
+/// Name of the property added to the span groups of spans, when [`LatencyTraceCfg::with_path_names`] is enabled,
+/// whose value is the names of the span's ancestors and the span itself, from the root, separated by
+/// [`PATH_SEPARATOR`].
+pub(crate) const PATH_PROP: &str = "path";
+
+/// Separator of the span names in the value of [`PATH_PROP`].
+pub(crate) const PATH_SEPARATOR: &str = "/";
+
+/// Returns `props` followed by [`PANICKED_PROP`].
+fn panicked_props(props: &Props) -> Arc<Props> {
+    let mut panicked_props = props.clone();
+    panicked_props.push((PANICKED_PROP.0.to_owned(), PANICKED_PROP.1.to_owned()));
+    Arc::new(panicked_props)
+}
+
 //=================
 // Timing and Timings
 
-/// Alias of [`Histogram<u64>`].
-pub type Timing = Histogram<u64>;
+/// [`Histogram<u64>`] of recorded values together with their [`TimeUnit`]; inherits all [`Histogram`] methods.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    pub(crate) hist: Histogram<u64>,
+    pub(crate) unit: TimeUnit,
+}
+
+impl Deref for Timing {
+    type Target = Histogram<u64>;
+    fn deref(&self) -> &Self::Target {
+        &self.hist
+    }
+}
 
-/// Constructs a [`Timing`]. The arguments correspond to [Histogram::high] and [Histogram::sigfig].
-pub(crate) fn new_timing(hist_high: u64, hist_sigfig: u8) -> Timing {
-    let mut hist = Histogram::<u64>::new_with_bounds(1, hist_high, hist_sigfig)
+impl DerefMut for Timing {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.hist
+    }
+}
+
+impl AsRef<Histogram<u64>> for Timing {
+    fn as_ref(&self) -> &Histogram<u64> {
+        &self.hist
+    }
+}
+
+impl Borrow<Histogram<u64>> for Timing {
+    fn borrow(&self) -> &Histogram<u64> {
+        &self.hist
+    }
+}
+
+/// Constructs a [`Timing`] in `unit`. The other arguments correspond to [Histogram::low], [Histogram::high], and
+/// [Histogram::sigfig].
+pub(crate) fn new_timing(hist_low: u64, hist_high: u64, hist_sigfig: u8, unit: TimeUnit) -> Timing {
+    let mut hist = Histogram::<u64>::new_with_bounds(hist_low, hist_high, hist_sigfig)
         .expect("should not happen given histogram construction");
     hist.auto(true);
-    hist
+    Timing::new(hist, unit)
 }
 
-#[doc(hidden)]
 /// Type of latency information internally collected for span groups. The key is [SpanGroupPriv], which is as
 /// light as possible to minimize processing overhead when accessing the map. Therefore, part of the information
 /// required to produce the ultimate results is kept as a separate `callsite_infos` map keyed by [`Identifier`].
+///
+/// Publicly, this is an opaque snapshot of the latency information collected by a
+/// [`LatencyTrace`](crate::LatencyTrace) (see [`LatencyTrace::snapshot`](crate::LatencyTrace::snapshot)).
 #[derive(Clone)]
 pub struct RawTrace {
     pub(crate) timings: HashMap<SpanGroupPriv, Timing>,
     pub(crate) callsite_infos: HashMap<Identifier, CallsiteInfo>,
+    /// Distributions of the number of times spans were entered, only recorded if
+    /// [`LatencyTraceCfg::with_record_poll_counts`] is enabled.
+    pub(crate) poll_counts: HashMap<SpanGroupPriv, Timing>,
+    /// Number of closed spans per span group, only counted if [`LatencyTraceCfg::with_record_only_nth`]
+    /// or [`LatencyTraceCfg::with_sampling`] is set.
+    pub(crate) occurrences: HashMap<SpanGroupPriv, u64>,
+    /// Start offsets, in the configured [`TimeUnit`], of the first closed span of each span group relative to the creation of its
+    /// root span, only recorded if [`LatencyTraceCfg::with_record_offsets`] is enabled.
+    pub(crate) offsets: HashMap<SpanGroupPriv, u64>,
+    /// Distributions of the span start times, in the configured [`TimeUnit`] since the configured epoch, only recorded if
+    /// [`LatencyTraceCfg::with_epoch`] is set.
+    pub(crate) epoch_offsets: HashMap<SpanGroupPriv, Timing>,
+    /// Distributions of the active times of spans, i.e., the time spent between entering and exiting the spans,
+    /// only recorded if [`LatencyTraceCfg::with_record_active_time`] is enabled.
+    pub(crate) active_times: HashMap<SpanGroupPriv, Timing>,
+    /// Distributions of the suspended times of spans, i.e., their latencies minus their active times, only recorded
+    /// if [`LatencyTraceCfg::with_record_active_time`] is enabled.
+    pub(crate) suspend_times: HashMap<SpanGroupPriv, Timing>,
+    /// Number of times the latency histogram of each span group was auto-resized because a recorded value exceeded
+    /// its highest trackable value. Only span groups with at least one resize are present.
+    pub(crate) resize_counts: HashMap<SpanGroupPriv, u64>,
+    /// Number of closed spans per span group, whether or not their latencies are recorded.
+    pub(crate) instance_counts: HashMap<SpanGroupPriv, u64>,
+    /// Number of latencies of each span group that were not recorded because they exceeded the highest trackable
+    /// value of a histogram that is not auto-resizable. Only span groups with at least one overflow are present.
+    pub(crate) overflow_counts: HashMap<SpanGroupPriv, u64>,
+    /// Distinct field values of closed spans of each span group, in the order first observed, only recorded if
+    /// [`LatencyTraceCfg::with_sample_fields`] is set.
+    pub(crate) field_samples: HashMap<SpanGroupPriv, Vec<Props>>,
+    /// Number of events by level emitted within the spans of each span group, only counted if
+    /// [`LatencyTraceCfg::with_event_counting`] is enabled.
+    pub(crate) event_counts: HashMap<SpanGroupPriv, BTreeMap<Level, u64>>,
+    /// Latencies buffered when [`LatencyTraceCfg::with_flush_batch`] is set, which are yet to be recorded in
+    /// `timings`. The latency is `None` for spans that are not recorded (see
+    /// [`LatencyTraceCfg::with_record_only_nth`]).
+    pub(crate) pending: Vec<(SpanGroupPriv, Option<u64>)>,
+    /// Number of spans created minus number of spans closed on this thread. Can be negative for spans
+    /// that are created on one thread and closed on another.
+    pub(crate) open_spans: isize,
+    /// Thread on which the information was collected, set by [`op`] when the thread-local information is
+    /// accumulated. `None` if the information was collected on more than one thread.
+    pub(crate) thread_id: Option<ThreadId>,
 }
 
 impl RawTrace {
@@ -89,6 +297,20 @@ impl RawTrace {
         Self {
             timings: HashMap::new(),
             callsite_infos: HashMap::new(),
+            poll_counts: HashMap::new(),
+            occurrences: HashMap::new(),
+            offsets: HashMap::new(),
+            epoch_offsets: HashMap::new(),
+            active_times: HashMap::new(),
+            suspend_times: HashMap::new(),
+            resize_counts: HashMap::new(),
+            instance_counts: HashMap::new(),
+            overflow_counts: HashMap::new(),
+            field_samples: HashMap::new(),
+            event_counts: HashMap::new(),
+            pending: Vec::new(),
+            open_spans: 0,
+            thread_id: None,
         }
     }
 }
@@ -102,33 +324,172 @@ pub(crate) type AccRawTrace = Vec<RawTrace>;
 //=================
 // SpanTiming
 
+/// Monotonic clock instant used to timestamp spans: [`quanta::Instant`] with the "quanta" feature, which is
+/// cheaper to read than [`std::time::Instant`] on platforms with a stable TSC, and [`std::time::Instant`] otherwise.
+#[cfg(feature = "quanta")]
+type ClockInstant = quanta::Instant;
+#[cfg(not(feature = "quanta"))]
+type ClockInstant = std::time::Instant;
+
 /// Information about a span stored in the registry.
 #[derive(Debug)]
 struct SpanTiming {
-    // callsite_info_priv_path: CallsiteInfoPrivPath,
-    callsite_id_path: CallsiteIdPath,
-    props_path: PropsPath,
-    created_at: Instant,
+    span_group_priv: SpanGroupPriv,
+    created_at: ClockInstant,
+    /// Time of creation of the span's root span, the same as `created_at` for root spans.
+    root_created_at: ClockInstant,
+    /// Start time of the span, in the configured [`TimeUnit`] since the configured epoch, only computed if
+    /// [`LatencyTraceCfg::with_epoch`] is set.
+    epoch_offset: Option<u64>,
+    /// Time of the span's last [`crate::checkpoint`], initially the same as `created_at`.
+    last_checkpoint: ClockInstant,
+    /// Additional property sets of the span, from the configured [`LatencyTraceCfg::with_multi_span_grouper`],
+    /// for the alternative span groups into which the span's latency is also recorded.
+    alt_props: Vec<Arc<Props>>,
+    /// Number of times the span was entered, only updated if [`LatencyTraceCfg::with_record_poll_counts`]
+    /// is enabled.
+    enter_count: u64,
+    /// Time the span was last entered, if it is currently entered, only updated if
+    /// [`LatencyTraceCfg::with_record_active_time`] is enabled.
+    entered_at: Option<ClockInstant>,
+    /// Accumulated time between entering and exiting the span, only updated if
+    /// [`LatencyTraceCfg::with_record_active_time`] is enabled.
+    acc_active_time: Duration,
+    /// Name-value pairs of all the fields of the span at creation, only computed if
+    /// [`LatencyTraceCfg::with_sample_fields`] is set.
+    fields: Option<Arc<Props>>,
 }
 
-pub(crate) fn op(raw_trace: RawTrace, acc: &mut AccRawTrace, tid: ThreadId) {
+pub(crate) fn op(mut raw_trace: RawTrace, acc: &mut AccRawTrace, tid: ThreadId) {
     log::debug!("executing `op` for {:?}", tid);
+    raw_trace.thread_id = Some(tid);
     acc.push(raw_trace);
 }
 
-pub(crate) fn op_r(acc1: RawTrace, acc2: RawTrace) -> RawTrace {
-    let mut timings = acc1.timings;
-    for (k, v) in acc2.timings {
-        let hist = timings.get_mut(&k);
+/// Combines the histograms of `timings2` into `timings1`.
+fn add_timings(
+    mut timings1: HashMap<SpanGroupPriv, Timing>,
+    mut timings2: HashMap<SpanGroupPriv, Timing>,
+) -> HashMap<SpanGroupPriv, Timing> {
+    // Histogram addition is commutative, so the smaller map is added to the larger one.
+    if timings1.len() < timings2.len() {
+        std::mem::swap(&mut timings1, &mut timings2);
+    }
+    for (k, v) in timings2 {
+        let hist = timings1.get_mut(&k);
         match hist {
             Some(hist) => hist
                 .add(v)
                 .expect("should not happen given histogram construction"),
             None => {
-                timings.insert(k, v);
+                timings1.insert(k, v);
+            }
+        }
+    }
+    timings1
+}
+
+/// Combines the offsets of `offsets2` into `offsets1`, keeping the smallest offset of each span group.
+fn add_offsets(
+    mut offsets1: HashMap<SpanGroupPriv, u64>,
+    offsets2: HashMap<SpanGroupPriv, u64>,
+) -> HashMap<SpanGroupPriv, u64> {
+    for (k, v) in offsets2 {
+        let offset = offsets1.entry(k).or_insert(v);
+        *offset = (*offset).min(v);
+    }
+    offsets1
+}
+
+/// Combines the field samples of `samples2` into `samples1`, appending the samples of each span group that are not
+/// already present. The samples are truncated to the configured sample size in post-processing.
+fn add_field_samples(
+    mut samples1: HashMap<SpanGroupPriv, Vec<Props>>,
+    samples2: HashMap<SpanGroupPriv, Vec<Props>>,
+) -> HashMap<SpanGroupPriv, Vec<Props>> {
+    for (k, v) in samples2 {
+        let samples = samples1.entry(k).or_default();
+        for fields in v {
+            if !samples.contains(&fields) {
+                samples.push(fields);
+            }
+        }
+    }
+    samples1
+}
+
+/// Combines the event counts of `counts2` into `counts1`.
+fn add_event_counts(
+    mut counts1: HashMap<SpanGroupPriv, BTreeMap<Level, u64>>,
+    counts2: HashMap<SpanGroupPriv, BTreeMap<Level, u64>>,
+) -> HashMap<SpanGroupPriv, BTreeMap<Level, u64>> {
+    for (k, v) in counts2 {
+        let counts = counts1.entry(k).or_default();
+        for (level, count) in v {
+            *counts.entry(level).or_insert(0) += count;
+        }
+    }
+    counts1
+}
+
+/// Adds `fields` to the field samples of `span_group_priv` if there are fewer than `n` samples and `fields` is not
+/// already present.
+fn sample_fields(
+    field_samples: &mut HashMap<SpanGroupPriv, Vec<Props>>,
+    span_group_priv: &SpanGroupPriv,
+    fields: &Props,
+    n: usize,
+) {
+    match field_samples.get_mut(span_group_priv) {
+        Some(samples) => {
+            if samples.len() < n && !samples.contains(fields) {
+                samples.push(fields.clone());
             }
         }
+        None => {
+            field_samples.insert(span_group_priv.clone(), vec![fields.clone()]);
+        }
     }
+}
+
+/// Increments the instance count of `span_group_priv`, only cloning it when it is first counted.
+fn count_instance(
+    instance_counts: &mut HashMap<SpanGroupPriv, u64>,
+    span_group_priv: &SpanGroupPriv,
+) {
+    match instance_counts.get_mut(span_group_priv) {
+        Some(count) => *count += 1,
+        None => {
+            instance_counts.insert(span_group_priv.clone(), 1);
+        }
+    }
+}
+
+/// Combines the counts of `counts2` into `counts1`, adding the counts of each span group.
+fn add_counts(
+    mut counts1: HashMap<SpanGroupPriv, u64>,
+    counts2: HashMap<SpanGroupPriv, u64>,
+) -> HashMap<SpanGroupPriv, u64> {
+    for (k, v) in counts2 {
+        *counts1.entry(k).or_insert(0) += v;
+    }
+    counts1
+}
+
+pub(crate) fn op_r(acc1: RawTrace, acc2: RawTrace) -> RawTrace {
+    let timings = add_timings(acc1.timings, acc2.timings);
+    let poll_counts = add_timings(acc1.poll_counts, acc2.poll_counts);
+    let offsets = add_offsets(acc1.offsets, acc2.offsets);
+    let epoch_offsets = add_timings(acc1.epoch_offsets, acc2.epoch_offsets);
+    let active_times = add_timings(acc1.active_times, acc2.active_times);
+    let suspend_times = add_timings(acc1.suspend_times, acc2.suspend_times);
+    let resize_counts = add_counts(acc1.resize_counts, acc2.resize_counts);
+    let instance_counts = add_counts(acc1.instance_counts, acc2.instance_counts);
+    let overflow_counts = add_counts(acc1.overflow_counts, acc2.overflow_counts);
+    let field_samples = add_field_samples(acc1.field_samples, acc2.field_samples);
+    let event_counts = add_event_counts(acc1.event_counts, acc2.event_counts);
+    let mut pending = acc1.pending;
+    pending.extend(acc2.pending);
 
     let callsite_infos: HashMap<Identifier, CallsiteInfo> = acc1
         .callsite_infos
@@ -139,6 +500,82 @@ pub(crate) fn op_r(acc1: RawTrace, acc2: RawTrace) -> RawTrace {
     RawTrace {
         timings,
         callsite_infos,
+        poll_counts,
+        // Occurrences are thread-local and only used during collection.
+        occurrences: HashMap::new(),
+        offsets,
+        epoch_offsets,
+        active_times,
+        suspend_times,
+        resize_counts,
+        instance_counts,
+        overflow_counts,
+        field_samples,
+        event_counts,
+        pending,
+        open_spans: acc1.open_spans + acc2.open_spans,
+        thread_id: if acc1.thread_id == acc2.thread_id {
+            acc1.thread_id
+        } else {
+            None
+        },
+    }
+}
+
+//=================
+// TimeUnit
+
+/// Unit of the latencies and other durations recorded by [`LatencyTrace`](crate::LatencyTrace)
+/// (see [`LatencyTraceCfg::with_time_unit`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum TimeUnit {
+    Nanos,
+    #[default]
+    Micros,
+    Millis,
+}
+
+impl TimeUnit {
+    /// Number of nanoseconds in one unit.
+    pub fn nanos_per_unit(&self) -> u64 {
+        match self {
+            TimeUnit::Nanos => 1,
+            TimeUnit::Micros => 1000,
+            TimeUnit::Millis => 1000 * 1000,
+        }
+    }
+
+    /// Converts `duration` to a whole number of units, truncating any fraction.
+    pub fn from_duration(&self, duration: Duration) -> u64 {
+        match self {
+            TimeUnit::Nanos => duration.as_nanos() as u64,
+            TimeUnit::Micros => duration.as_micros() as u64,
+            TimeUnit::Millis => duration.as_millis() as u64,
+        }
+    }
+
+    /// Converts `value` in this unit to a [`Duration`].
+    pub fn to_duration(&self, value: u64) -> Duration {
+        match self {
+            TimeUnit::Nanos => Duration::from_nanos(value),
+            TimeUnit::Micros => Duration::from_micros(value),
+            TimeUnit::Millis => Duration::from_millis(value),
+        }
+    }
+
+    /// Returns the symbol of the unit, e.g., `"µs"`.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            TimeUnit::Nanos => "ns",
+            TimeUnit::Micros => "µs",
+            TimeUnit::Millis => "ms",
+        }
+    }
+}
+
+impl Display for TimeUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.symbol())
     }
 }
 
@@ -147,18 +584,55 @@ pub(crate) fn op_r(acc1: RawTrace, acc2: RawTrace) -> RawTrace {
 
 /// Configuration information for [`LatencyTrace`](crate::LatencyTrace). It is instantiated with its [`LatencyTraceCfg::default`] method
 /// and can be customized with its other methods.
+#[derive(Clone)]
 pub struct LatencyTraceCfg {
     pub(crate) span_grouper: SpanGrouper,
+    pub(crate) multi_span_grouper: Option<MultiSpanGrouper>,
+    pub(crate) hist_low: u64,
     pub(crate) hist_high: u64,
     pub(crate) hist_sigfig: u8,
+    pub(crate) source_root: Option<Arc<Path>>,
+    pub(crate) separate_panicked: bool,
+    pub(crate) record_poll_counts: bool,
+    pub(crate) record_only_nth: Option<u64>,
+    pub(crate) sample_stride: Option<u64>,
+    pub(crate) drop_root: bool,
+    pub(crate) id_bytes: usize,
+    pub(crate) record_offsets: bool,
+    pub(crate) on_report: Option<OnReport>,
+    pub(crate) epoch: Option<SystemTime>,
+    pub(crate) record_active_time: bool,
+    pub(crate) time_unit: TimeUnit,
+    pub(crate) min_level: Level,
+    pub(crate) auto_resize: bool,
+    pub(crate) flush_batch: Option<usize>,
+    pub(crate) sample_fields: Option<usize>,
+    pub(crate) event_counting: bool,
+    pub(crate) max_groups: Option<usize>,
+    pub(crate) path_names: bool,
 }
 
 //=================
 // SpanGrouper
 
+/// Overflow label of the span groups of spans whose property sets exceed [`LatencyTraceCfg::with_max_groups`].
+pub(crate) const MAX_GROUPS_OVERFLOW_LABEL: &str = "other";
+
 /// Internal type of span groupers.
 type SpanGrouper = Arc<dyn Fn(&Attributes) -> Vec<(String, String)> + Send + Sync + 'static>;
 
+/// Internal type of span groupers that return multiple alternative property sets
+/// (see [`LatencyTraceCfg::with_multi_span_grouper`]).
+type MultiSpanGrouper =
+    Arc<dyn Fn(&Attributes) -> Vec<Vec<(String, String)>> + Send + Sync + 'static>;
+
+/// Internal type of report callbacks (see [`LatencyTraceCfg::with_on_report`]).
+pub(crate) type OnReport = Arc<dyn Fn(&Timings) + Send + Sync + 'static>;
+
+/// Maximum number of property sets of a [`MultiSpanGrouper`] that are used, to bound the fan-out of the recording
+/// of a span's latency into multiple span groups.
+pub(crate) const MAX_SPAN_GROUPINGS: usize = 4;
+
 //=================
 // LatencyTrace
 
@@ -179,8 +653,34 @@ where
 {
     pub(crate) control: P::Control,
     span_grouper: SpanGrouper,
+    multi_span_grouper: Option<MultiSpanGrouper>,
+    pub(crate) hist_low: u64,
     pub(crate) hist_high: u64,
     pub(crate) hist_sigfig: u8,
+    source_root: Option<Arc<Path>>,
+    separate_panicked: bool,
+    record_poll_counts: bool,
+    record_only_nth: Option<u64>,
+    sample_stride: Option<u64>,
+    pub(crate) drop_root: bool,
+    pub(crate) id_bytes: usize,
+    record_offsets: bool,
+    pub(crate) on_report: Option<OnReport>,
+    epoch: Option<SystemTime>,
+    record_active_time: bool,
+    pub(crate) time_unit: TimeUnit,
+    min_level: Level,
+    auto_resize: bool,
+    flush_batch: Option<usize>,
+    pub(crate) sample_fields: Option<usize>,
+    event_counting: bool,
+    path_names: bool,
+    /// Held for reading while latencies are recorded and for writing during blocking probes
+    /// (see [`ProbeMode::Blocking`](crate::ProbeMode::Blocking)), once `probe_gate_enabled` is set.
+    probe_gate: Arc<RwLock<()>>,
+    /// Set by the first blocking probe, so that the recording of latencies doesn't acquire `probe_gate` unless
+    /// blocking probes are used.
+    probe_gate_enabled: Arc<AtomicBool>,
 }
 
 impl<P> LatencyTraceG<P>
@@ -189,47 +689,194 @@ where
     P::Control: TlcBase,
 {
     pub(crate) fn new(config: LatencyTraceCfg) -> Self {
+        let span_grouper: SpanGrouper = match config.max_groups {
+            Some(max_groups) => {
+                let inner = config.span_grouper;
+                Arc::new(bounded_cardinality(
+                    move |attrs: &Attributes| inner(attrs),
+                    max_groups,
+                    MAX_GROUPS_OVERFLOW_LABEL,
+                ))
+            }
+            None => config.span_grouper,
+        };
         LatencyTraceG {
             control: P::Control::new(),
-            span_grouper: config.span_grouper,
+            span_grouper,
+            multi_span_grouper: config.multi_span_grouper,
+            hist_low: config.hist_low,
             hist_high: config.hist_high,
             hist_sigfig: config.hist_sigfig,
+            source_root: config.source_root,
+            separate_panicked: config.separate_panicked,
+            record_poll_counts: config.record_poll_counts,
+            record_only_nth: config.record_only_nth,
+            sample_stride: config.sample_stride,
+            drop_root: config.drop_root,
+            id_bytes: config.id_bytes,
+            record_offsets: config.record_offsets,
+            on_report: config.on_report,
+            epoch: config.epoch,
+            record_active_time: config.record_active_time,
+            time_unit: config.time_unit,
+            min_level: config.min_level,
+            auto_resize: config.auto_resize,
+            flush_batch: config.flush_batch,
+            sample_fields: config.sample_fields,
+            event_counting: config.event_counting,
+            path_names: config.path_names,
+            probe_gate: Arc::new(RwLock::new(())),
+            probe_gate_enabled: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Acquires the probe gate for recording, which blocks only during a blocking probe, or returns `None` without
+    /// synchronization if there hasn't been a blocking probe.
+    fn record_gate(&self) -> Option<RwLockReadGuard<'_, ()>> {
+        if !self.probe_gate_enabled.load(Ordering::Acquire) {
+            return None;
+        }
+        // The guarded data is `()`, so a panic while the lock was held can't have left it inconsistent.
+        Some(
+            self.probe_gate
+                .read()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+
+    /// Acquires the probe gate exclusively, blocking the recording of latencies until the guard is dropped.
+    ///
+    /// The first call enables the probe gate, so recordings that are already in progress when it is made are not
+    /// blocked.
+    pub(crate) fn block_recording(&self) -> RwLockWriteGuard<'_, ()> {
+        self.probe_gate_enabled.store(true, Ordering::Release);
+        self.probe_gate
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns `file` relative to the configured source root, or `file` unchanged if there is no configured
+    /// source root or `file` is not under it.
+    fn relative_file(&self, file: &str) -> String {
+        self.source_root
+            .as_ref()
+            .and_then(|root| Path::new(file).strip_prefix(root).ok())
+            .map(|rel| rel.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file.to_owned())
+    }
+
+    /// Constructs an empty latency [`Timing`] according to the configured histogram parameters.
+    pub(crate) fn new_latency_timing(&self) -> Timing {
+        let mut timing = new_timing(
+            self.hist_low,
+            self.hist_high,
+            self.hist_sigfig,
+            self.time_unit,
+        );
+        timing.auto(self.auto_resize);
+        timing
+    }
+
     /// Updates timings for the given span group. Called by [`Layer`] impl.
-    fn update_timings(&self, span_group_priv: &SpanGroupPriv, f: impl FnOnce(&mut Timing)) {
-        self.control.with_data_mut(|raw_trace| {
-            let timing = {
-                if let Some(timing) = raw_trace.timings.get_mut(span_group_priv) {
-                    timing
-                } else {
-                    log::trace!(
-                        "thread-loacal Timing created for {:?} on {:?}",
-                        span_group_priv,
-                        thread::current().id()
-                    );
-                    raw_trace.timings.insert(
-                        span_group_priv.clone(),
-                        new_timing(self.hist_high, self.hist_sigfig),
-                    );
-                    raw_trace
-                        .timings
-                        .get_mut(span_group_priv)
-                        .expect("impossible: span_group_priv key was just inserted")
-                }
-            };
+    fn update_timings(
+        &self,
+        span_group_priv: &SpanGroupPriv,
+        f: impl FnOnce(&mut Timing) -> Result<(), RecordError>,
+    ) {
+        self.control
+            .with_data_mut(|raw_trace| self.update_raw_timings(raw_trace, span_group_priv, f));
+    }
 
-            f(timing);
+    /// Updates the timings of `raw_trace` for the given span group.
+    ///
+    /// `f` fails only if a value exceeds the highest trackable value of a histogram that is not auto-resizable,
+    /// in which case the overflow is counted.
+    fn update_raw_timings(
+        &self,
+        raw_trace: &mut RawTrace,
+        span_group_priv: &SpanGroupPriv,
+        f: impl FnOnce(&mut Timing) -> Result<(), RecordError>,
+    ) {
+        let timing = {
+            if let Some(timing) = raw_trace.timings.get_mut(span_group_priv) {
+                timing
+            } else {
+                log::trace!(
+                    "thread-loacal Timing created for {:?} on {:?}",
+                    span_group_priv,
+                    thread::current().id()
+                );
+                raw_trace
+                    .timings
+                    .insert(span_group_priv.clone(), self.new_latency_timing());
+                raw_trace
+                    .timings
+                    .get_mut(span_group_priv)
+                    .expect("impossible: span_group_priv key was just inserted")
+            }
+        };
 
-            log::trace!(
-                "exiting `update_timings` for {:?} on {:?}",
-                span_group_priv,
-                thread::current().id()
-            );
+        // Auto-resizing reallocates the histogram, which adds to the latencies being measured.
+        let high = timing.high();
+        let res = f(timing);
+        if timing.high() != high {
+            *raw_trace
+                .resize_counts
+                .entry(span_group_priv.clone())
+                .or_insert(0) += 1;
+        }
+        if res.is_err() {
+            *raw_trace
+                .overflow_counts
+                .entry(span_group_priv.clone())
+                .or_insert(0) += 1;
+        }
+
+        log::trace!(
+            "exiting `update_timings` for {:?} on {:?}",
+            span_group_priv,
+            thread::current().id()
+        );
+    }
+
+    /// Records the latency of a closed span of the given span group, or only creates its timing if `latency` is
+    /// `None`. If [`LatencyTraceCfg::with_flush_batch`] is set, the latency is buffered instead, and the buffer is
+    /// flushed when it is full.
+    fn record_latency(&self, span_group_priv: &SpanGroupPriv, latency: Option<u64>) {
+        let Some(flush_batch) = self.flush_batch else {
+            self.update_timings(span_group_priv, |hist| match latency {
+                Some(latency) => hist.record(latency),
+                None => Ok(()),
+            });
+            return;
+        };
+        self.control.with_data_mut(|raw_trace| {
+            raw_trace.pending.push((span_group_priv.clone(), latency));
+            if raw_trace.pending.len() >= flush_batch {
+                self.flush_pending(raw_trace);
+            }
         });
     }
 
+    /// Records the latencies buffered in `raw_trace` (see [`LatencyTraceCfg::with_flush_batch`]).
+    pub(crate) fn flush_pending(&self, raw_trace: &mut RawTrace) {
+        let pending = std::mem::take(&mut raw_trace.pending);
+        for (span_group_priv, latency) in pending.iter() {
+            self.update_raw_timings(raw_trace, span_group_priv, |hist| match latency {
+                Some(latency) => hist.record(*latency),
+                None => Ok(()),
+            });
+        }
+        // Reuses the buffer's allocation.
+        raw_trace.pending = pending;
+        raw_trace.pending.clear();
+    }
+
+    /// Returns `true` if `meta` is the metadata of a span below the configured minimum level.
+    fn is_filtered_out(&self, meta: &Metadata<'_>) -> bool {
+        meta.is_span() && *meta.level() > self.min_level
+    }
+
     /// Updates callsite info for the given callsite [`Identifier`].
     fn update_callsite_infos(
         &self,
@@ -265,6 +912,18 @@ where
     P: TlcParam + 'static,
     P::Control: TlcBase,
 {
+    fn register_callsite(&self, meta: &'static Metadata<'static>) -> Interest {
+        if self.is_filtered_out(meta) {
+            Interest::never()
+        } else {
+            Interest::always()
+        }
+    }
+
+    fn enabled(&self, meta: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        !self.is_filtered_out(meta)
+    }
+
     fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
         let span = ctx
             .span(id)
@@ -272,28 +931,93 @@ where
         log::trace!("`on_new_span` start: name={}, id={:?}", span.name(), id);
         let meta = span.metadata();
         let callsite_id = meta.callsite();
+        // The registry resolves the parent from the span's attributes, so explicitly set parents
+        // (`span!(parent: ...)`) take precedence over the contextual (current) span and explicit roots
+        // (`span!(parent: None, ...)`) have no parent.
         let parent_span = span.parent();
 
-        let props = (self.span_grouper)(attrs);
-        let (callsite_id_path, props_path) = match &parent_span {
-            None => (vec![callsite_id.clone()], vec![Arc::new(props)]),
+        let wrapper_props = match meta.target() {
+            DROP_TARGET => {
+                wrapper_name(attrs, DROP_FIELD).map(|name| vec![(DROP_FIELD.to_owned(), name)])
+            }
+            LOCK_TARGET => {
+                wrapper_name(attrs, LOCK_FIELD).map(|name| vec![(LOCK_FIELD.to_owned(), name)])
+            }
+            _ => None,
+        };
+        let (mut props, mut alt_props) = match (wrapper_props, &self.multi_span_grouper) {
+            (Some(props), _) => (props, Vec::new()),
+            (None, None) => ((self.span_grouper)(attrs), Vec::new()),
+            (None, Some(multi_span_grouper)) => {
+                let mut props_sets = multi_span_grouper(attrs)
+                    .into_iter()
+                    .take(MAX_SPAN_GROUPINGS);
+                let props = props_sets.next().unwrap_or_default();
+                // Duplicate property sets would record the span's latency more than once in the same span group.
+                let mut alt_props: Vec<Arc<Props>> = Vec::new();
+                for alt in props_sets {
+                    if alt != props && alt_props.iter().all(|p| **p != alt) {
+                        alt_props.push(Arc::new(alt));
+                    }
+                }
+                (props, alt_props)
+            }
+        };
+        if self.path_names {
+            let path = span
+                .scope()
+                .from_root()
+                .map(|s| s.name())
+                .collect::<Vec<_>>()
+                .join(PATH_SEPARATOR);
+            let path_prop = (PATH_PROP.to_owned(), path);
+            props.insert(0, path_prop.clone());
+            for alt in &mut alt_props {
+                Arc::make_mut(alt).insert(0, path_prop.clone());
+            }
+        }
+        let (span_group_priv, root_created_at) = match &parent_span {
+            None => (
+                SpanGroupPriv::root(callsite_id.clone(), Arc::new(props)),
+                None,
+            ),
             Some(parent_span) => {
                 let ext = parent_span.extensions();
-                let pst = ext
-                    .get::<SpanTiming>()
-                    .expect("span extensions does not contain SpanTiming record");
-                let mut callsite_id_path = pst.callsite_id_path.clone();
-                callsite_id_path.push(callsite_id.clone());
-                let mut props_path = pst.props_path.clone();
-                props_path.push(Arc::new(props));
-                (callsite_id_path, props_path)
+                let Some(pst) = ext.get::<SpanTiming>() else {
+                    // The parent was created before `self` was attached, so the span group can't be determined.
+                    log::debug!(
+                        "`on_new_span` skipped: parent of name={}, id={:?} has no SpanTiming record",
+                        span.name(),
+                        id
+                    );
+                    return;
+                };
+                let span_group_priv = pst
+                    .span_group_priv
+                    .child(callsite_id.clone(), Arc::new(props));
+                (span_group_priv, Some(pst.root_created_at))
             }
         };
 
+        let created_at = ClockInstant::now();
+        // Spans created before the epoch have an offset of 0.
+        let epoch_offset = self.epoch.map(|epoch| {
+            self.time_unit
+                .from_duration(SystemTime::now().duration_since(epoch).unwrap_or_default())
+        });
         span.extensions_mut().insert(SpanTiming {
-            callsite_id_path,
-            props_path,
-            created_at: Instant::now(),
+            span_group_priv,
+            created_at,
+            root_created_at: root_created_at.unwrap_or(created_at),
+            epoch_offset,
+            last_checkpoint: created_at,
+            alt_props,
+            enter_count: 0,
+            entered_at: None,
+            acc_active_time: Duration::ZERO,
+            fields: self
+                .sample_fields
+                .map(|_| Arc::new(group_by_all_fields(attrs))),
         });
 
         let callsite_info = {
@@ -302,7 +1026,9 @@ where
             move || CallsiteInfo {
                 callsite_id,
                 name: span.name(),
-                file: meta.file().map(|s| s.to_owned()),
+                target: meta.target(),
+                module_path: meta.module_path(),
+                file: meta.file().map(|file| self.relative_file(file)),
                 line: meta.line(),
                 parent: parent_span
                     .iter()
@@ -312,13 +1038,134 @@ where
         };
 
         self.update_callsite_infos(callsite_id, callsite_info);
+        self.control
+            .with_data_mut(|raw_trace| raw_trace.open_spans += 1);
 
         log::trace!("`on_new_span` end: name={}, id={:?}", span.name(), id);
     }
 
-    // No need for fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+    /// Only counts span entries, if [`LatencyTraceCfg::with_record_poll_counts`] is enabled, and tracks the
+    /// start of active time, if [`LatencyTraceCfg::with_record_active_time`] is enabled.
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if !self.record_poll_counts && !self.record_active_time {
+            return;
+        }
+        let span = ctx
+            .span(id)
+            .expect("impossible: there is no span with the given id");
+        let mut ext = span.extensions_mut();
+        let Some(span_timing) = ext.get_mut::<SpanTiming>() else {
+            log::debug!("`on_enter` skipped: id={:?} has no SpanTiming record", id);
+            return;
+        };
+        if self.record_poll_counts {
+            span_timing.enter_count += 1;
+        }
+        if self.record_active_time {
+            // A span that is re-entered while entered remains active until its first exit.
+            span_timing.entered_at.get_or_insert_with(ClockInstant::now);
+        }
+    }
+
+    /// Only accumulates active time, if [`LatencyTraceCfg::with_record_active_time`] is enabled.
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if !self.record_active_time {
+            return;
+        }
+        let span = ctx
+            .span(id)
+            .expect("impossible: there is no span with the given id");
+        let mut ext = span.extensions_mut();
+        let Some(span_timing) = ext.get_mut::<SpanTiming>() else {
+            log::debug!("`on_exit` skipped: id={:?} has no SpanTiming record", id);
+            return;
+        };
+        if let Some(entered_at) = span_timing.entered_at.take() {
+            span_timing.acc_active_time += entered_at.elapsed();
+        }
+    }
+
+    /// Counts events by level within their spans, if [`LatencyTraceCfg::with_event_counting`] is enabled, and
+    /// processes events emitted by [`crate::checkpoint`].
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let meta = event.metadata();
+        if meta.target() != CHECKPOINT_TARGET {
+            if !self.event_counting {
+                return;
+            }
+            let Some(span) = ctx.event_span(event) else {
+                return;
+            };
+            let ext = span.extensions();
+            let Some(span_timing) = ext.get::<SpanTiming>() else {
+                log::debug!(
+                    "`on_event` skipped: span={} has no SpanTiming record",
+                    span.name()
+                );
+                return;
+            };
+            let _gate = self.record_gate();
+            self.control.with_data_mut(|raw_trace| {
+                *raw_trace
+                    .event_counts
+                    .entry(span_timing.span_group_priv.clone())
+                    .or_default()
+                    .entry(*meta.level())
+                    .or_insert(0) += 1;
+            });
+            return;
+        }
+        let (span, name) = match (ctx.event_span(event), checkpoint_name(event)) {
+            (Some(span), Some(name)) => (span, name),
+            _ => return,
+        };
+        log::trace!(
+            "`on_event` checkpoint: span={}, checkpoint={}",
+            span.name(),
+            name
+        );
+
+        let now = ClockInstant::now();
+        let callsite_id = meta.callsite();
+        let (span_group_priv, elapsed) = {
+            let mut ext = span.extensions_mut();
+            let Some(span_timing) = ext.get_mut::<SpanTiming>() else {
+                log::debug!(
+                    "`on_event` checkpoint skipped: span={} has no SpanTiming record",
+                    span.name()
+                );
+                return;
+            };
+            let elapsed = now - span_timing.last_checkpoint;
+            span_timing.last_checkpoint = now;
 
-    // No need for fn on_exit(&self, id: &Id, ctx: Context<'_, S>)
+            let span_group_priv = span_timing.span_group_priv.child(
+                callsite_id.clone(),
+                Arc::new(vec![(CHECKPOINT_FIELD.to_owned(), name)]),
+            );
+            (span_group_priv, elapsed)
+        };
+
+        let callsite_info = {
+            let callsite_id = callsite_id.clone();
+            let parent = span.metadata().callsite();
+            move || CallsiteInfo {
+                callsite_id,
+                name: CHECKPOINT_NAME,
+                target: meta.target(),
+                module_path: meta.module_path(),
+                file: meta.file().map(|file| self.relative_file(file)),
+                line: meta.line(),
+                parent: Some(parent),
+            }
+        };
+        self.update_callsite_infos(callsite_id, callsite_info);
+
+        let _gate = self.record_gate();
+        self.update_timings(&span_group_priv, |hist| {
+            hist.record(self.time_unit.from_duration(elapsed))
+        });
+    }
 
     fn on_close(&self, id: Id, ctx: Context<'_, S>) {
         let span = ctx
@@ -327,18 +1174,158 @@ where
         log::trace!("`on_close` start: name={}, id={:?}", span.name(), id);
 
         let ext = span.extensions();
-        let span_timing = ext
-            .get::<SpanTiming>()
-            .expect("span extensions does not contain SpanTiming record");
+        let Some(span_timing) = ext.get::<SpanTiming>() else {
+            // The span was created before `self` was attached or was skipped by `on_new_span`.
+            log::debug!(
+                "`on_close` skipped: name={}, id={:?} has no SpanTiming record",
+                span.name(),
+                id
+            );
+            return;
+        };
 
-        let span_group_priv = SpanGroupPriv {
-            callsite_id_path: span_timing.callsite_id_path.clone(),
-            props_path: span_timing.props_path.clone(),
+        // Spans that close within the clock resolution have a latency of 0, which is below the lowest discernible
+        // value of the histogram, so it is recorded as 1 instead.
+        let latency = self
+            .time_unit
+            .from_duration(ClockInstant::now() - span_timing.created_at)
+            .max(1);
+
+        let panicked = self.separate_panicked && thread::panicking();
+        let span_group_priv = if panicked {
+            log::trace!(
+                "`on_close` while panicking: name={}, id={:?}",
+                span.name(),
+                id
+            );
+            let props = span_timing.span_group_priv.props();
+            span_timing
+                .span_group_priv
+                .with_props(panicked_props(props))
+        } else {
+            span_timing.span_group_priv.clone()
         };
 
-        self.update_timings(&span_group_priv, |hist| {
-            hist.record((Instant::now() - span_timing.created_at).as_micros() as u64)
-                .expect("should not happen given histogram construction");
+        // Determine whether this span is recorded and count the span group occurrence if
+        // `record_only_nth` or `sample_stride` is configured.
+        let _gate = self.record_gate();
+        let record = self.control.with_data_mut(|raw_trace| {
+            raw_trace.open_spans -= 1;
+            count_instance(&mut raw_trace.instance_counts, &span_group_priv);
+            if let (Some(n), Some(fields)) = (self.sample_fields, &span_timing.fields) {
+                sample_fields(&mut raw_trace.field_samples, &span_group_priv, fields, n);
+            }
+            let record = match (self.record_only_nth, self.sample_stride) {
+                (None, None) => true,
+                (nth, stride) => {
+                    let occurrences = raw_trace
+                        .occurrences
+                        .entry(span_group_priv.clone())
+                        .or_insert(0);
+                    *occurrences += 1;
+                    let is_nth = match nth {
+                        Some(nth) => *occurrences == nth,
+                        None => true,
+                    };
+                    // The first occurrence is always sampled, so rarely hit span groups are recorded.
+                    let is_sampled = match stride {
+                        Some(stride) => (*occurrences - 1) % stride == 0,
+                        None => true,
+                    };
+                    is_nth && is_sampled
+                }
+            };
+            if record && self.record_offsets && !raw_trace.offsets.contains_key(&span_group_priv) {
+                let offset = span_timing.created_at - span_timing.root_created_at;
+                raw_trace.offsets.insert(
+                    span_group_priv.clone(),
+                    self.time_unit.from_duration(offset),
+                );
+            }
+            record
+        });
+
+        // The timing is created even if the span is not recorded, so that the span group is reported.
+        let recorded_latency = if record { Some(latency) } else { None };
+        self.record_latency(&span_group_priv, recorded_latency);
+
+        // Record into the alternative span groups, which share the parent of the span's span group.
+        for alt_props in span_timing.alt_props.iter() {
+            let alt_props = if panicked {
+                panicked_props(alt_props)
+            } else {
+                alt_props.clone()
+            };
+            let alt_span_group_priv = span_group_priv.with_props(alt_props);
+            self.record_latency(&alt_span_group_priv, recorded_latency);
+            self.control.with_data_mut(|raw_trace| {
+                count_instance(&mut raw_trace.instance_counts, &alt_span_group_priv);
+                if let (Some(n), Some(fields)) = (self.sample_fields, &span_timing.fields) {
+                    sample_fields(
+                        &mut raw_trace.field_samples,
+                        &alt_span_group_priv,
+                        fields,
+                        n,
+                    );
+                }
+            });
+        }
+        self.control.with_data_mut(|raw_trace| {
+            if record && self.record_active_time {
+                let active_time = self.time_unit.from_duration(span_timing.acc_active_time);
+                let hist = raw_trace
+                    .active_times
+                    .entry(span_group_priv.clone())
+                    .or_insert_with(|| {
+                        new_timing(
+                            self.hist_low,
+                            self.hist_high,
+                            self.hist_sigfig,
+                            self.time_unit,
+                        )
+                    });
+                hist.record(active_time)
+                    .expect("should not happen given histogram construction");
+                let hist = raw_trace
+                    .suspend_times
+                    .entry(span_group_priv.clone())
+                    .or_insert_with(|| {
+                        new_timing(
+                            self.hist_low,
+                            self.hist_high,
+                            self.hist_sigfig,
+                            self.time_unit,
+                        )
+                    });
+                hist.record(latency.saturating_sub(active_time))
+                    .expect("should not happen given histogram construction");
+            }
+            if let (true, Some(epoch_offset)) = (record, span_timing.epoch_offset) {
+                let hist = raw_trace
+                    .epoch_offsets
+                    .entry(span_group_priv.clone())
+                    .or_insert_with(|| {
+                        new_timing(
+                            self.hist_low,
+                            self.hist_high,
+                            self.hist_sigfig,
+                            self.time_unit,
+                        )
+                    });
+                hist.record(epoch_offset)
+                    .expect("should not happen given histogram construction");
+            }
+            if record && self.record_poll_counts {
+                let hist = raw_trace
+                    .poll_counts
+                    .entry(span_group_priv)
+                    // Poll counts are not durations, so they don't use the configured `hist_low`.
+                    .or_insert_with(|| {
+                        new_timing(1, self.hist_high, self.hist_sigfig, self.time_unit)
+                    });
+                hist.record(span_timing.enter_count)
+                    .expect("should not happen given histogram construction");
+            }
         });
 
         log::trace!(
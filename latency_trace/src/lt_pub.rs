@@ -1,25 +1,44 @@
 //! Publicly exported core [`LatencyTrace`]-related types and methods.
 
-use std::{collections::BTreeMap, sync::Arc, thread};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::{Display, Write},
+    future::Future,
+    path::Path,
+    sync::Arc,
+    thread::{self, ThreadId},
+    time::{Duration, Instant, SystemTime},
+};
 
-use hdrhistogram::Histogram;
+use base64ct::{Base64, Encoding};
+use hdrhistogram::{
+    serialization::{Deserializer, Serializer, V2DeflateSerializer},
+    Histogram,
+};
 use tracing::{
     span::{Attributes, Id},
-    Subscriber,
+    subscriber::Interest,
+    Event, Level, Metadata, Subscriber,
 };
 use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
 
 use crate::{
+    executor::Executor,
     lt_collect_g::LatencyTraceG,
-    summary_stats,
+    summary_stats, summary_stats_with_percentiles,
     tlc_param::{Either, Joined, Probed},
-    SummaryStats, Wrapper,
+    trend::csv_field,
+    SpanGroupDiff, SummaryStats, SummaryStatsMinCount, Wrapper,
 };
 pub use crate::{
-    lt_collect_g::{LatencyTraceCfg, Timing},
-    lt_refine_g::{SpanGroup, Timings, TimingsView},
-    lt_report_g::ActivationError,
-    probed_trace::ProbedTrace,
+    lt_collect_g::{LatencyTraceCfg, RawTrace, TimeUnit, Timing},
+    lt_refine_g::{
+        ActiveTimings, ConsistencyError, EpochOffsets, EventCounts, FieldSamples,
+        HdrDeserializationError, InstanceCounts, Offsets, OverflowCounts, PollCounts, Report,
+        ReportDiagnostics, ResizeCounts, SpanGroup, SuspendTimings, Timings, TimingsView,
+    },
+    lt_report_g::{ActivationError, HistogramCfg},
+    probed_trace::{ProbeMode, ProbedTrace},
 };
 
 //==============
@@ -46,14 +65,187 @@ impl LatencyTraceE {
 //==============
 // pub impl for LatencyTraceCfg
 
+/// Minimum number of hash bytes of span group IDs (see [`LatencyTraceCfg::with_id_bytes`]).
+const MIN_ID_BYTES: usize = 4;
+
+/// Maximum number of hash bytes of span group IDs, the size of a SHA-256 hash
+/// (see [`LatencyTraceCfg::with_id_bytes`]).
+const MAX_ID_BYTES: usize = 32;
+
 impl LatencyTraceCfg {
+    /// Returns a configuration preset suitable for long-running services such as servers. It is the same as
+    /// [`Self::default`] except for the following:
+    /// - `hist_high` of `600,000,000` (10 minutes), as long-running services can have long-lived spans
+    ///   (e.g., streaming requests or background jobs) and histogram auto-resizing at runtime is best avoided
+    ///   while a service is under load.
+    /// - Spans closed while their thread is panicking are recorded in separate span groups
+    ///   (see [`Self::with_separate_panicked`]), as services typically recover from panics in request handlers
+    ///   and the latencies of such failed requests should not pollute those of successful requests.
+    /// - `max_groups` of `1,000` (see [`Self::with_max_groups`]), so that a span grouper that uses high-cardinality
+    ///   span fields can't cause the memory used by a long-running service to grow without bound. With the default
+    ///   span grouper, which returns no properties, the cap adds no locking to span creation.
+    ///
+    /// `hist_sigfig` is kept at 2 to limit the memory used by the histograms of the potentially large number
+    /// of span groups of a service.
+    ///
+    /// For long-running services, latencies should be collected with [`LatencyTrace::measure_latencies_probed`]
+    /// (or its async variants) so that interim latencies can be reported with [`ProbedTrace::probe_latencies`]
+    /// while the service is running.
+    pub fn server_preset() -> Self {
+        LatencyTraceCfg::default()
+            .with_hist_high(600 * 1000 * 1000)
+            .with_separate_panicked(true)
+            .with_max_groups(Some(1000))
+    }
+
+    /// Returns the configured [`TimeUnit`] (see [`Self::with_time_unit`]).
+    pub fn time_unit(&self) -> TimeUnit {
+        self.time_unit
+    }
+
+    /// Returns the configured `hist_low` (see [hdrhistogram::Histogram::low]).
+    pub fn hist_low(&self) -> u64 {
+        self.hist_low
+    }
+
+    /// Returns the configured `hist_high` (see [hdrhistogram::Histogram::high]).
+    pub fn hist_high(&self) -> u64 {
+        self.hist_high
+    }
+
+    /// Returns the configured `hist_sigfig` (see [hdrhistogram::Histogram::sigfig]).
+    pub fn hist_sigfig(&self) -> u8 {
+        self.hist_sigfig
+    }
+
+    /// Returns the configured source root (see [`Self::with_source_root`]).
+    pub fn source_root(&self) -> Option<&Path> {
+        self.source_root.as_deref()
+    }
+
+    /// Returns the configured `separate_panicked` flag (see [`Self::with_separate_panicked`]).
+    pub fn separate_panicked(&self) -> bool {
+        self.separate_panicked
+    }
+
+    /// Returns the configured `record_poll_counts` flag (see [`Self::with_record_poll_counts`]).
+    pub fn record_poll_counts(&self) -> bool {
+        self.record_poll_counts
+    }
+
+    /// Returns the configured `record_only_nth` occurrence, if any (see [`Self::with_record_only_nth`]).
+    pub fn record_only_nth(&self) -> Option<u64> {
+        self.record_only_nth
+    }
+
+    /// Returns the effective sampling rate, if any (see [`Self::with_sampling`]).
+    pub fn sampling(&self) -> Option<f64> {
+        self.sample_stride.map(|stride| 1.0 / stride as f64)
+    }
+
+    /// Returns the configured `drop_root` flag (see [`Self::with_drop_root`]).
+    pub fn drop_root(&self) -> bool {
+        self.drop_root
+    }
+
+    /// Returns the configured `record_offsets` flag (see [`Self::with_record_offsets`]).
+    pub fn record_offsets(&self) -> bool {
+        self.record_offsets
+    }
+
+    /// Returns the configured `record_active_time` flag (see [`Self::with_record_active_time`]).
+    pub fn record_active_time(&self) -> bool {
+        self.record_active_time
+    }
+
+    /// Returns the configured minimum span level (see [`Self::with_min_level`]).
+    pub fn min_level(&self) -> Level {
+        self.min_level
+    }
+
+    /// Returns the configured `auto_resize` flag (see [`Self::with_auto_resize`]).
+    pub fn auto_resize(&self) -> bool {
+        self.auto_resize
+    }
+
+    /// Returns the configured flush batch size, if any (see [`Self::with_flush_batch`]).
+    pub fn flush_batch(&self) -> Option<usize> {
+        self.flush_batch
+    }
+
+    /// Returns the configured number of field samples per span group, if any (see [`Self::with_sample_fields`]).
+    pub fn sample_fields(&self) -> Option<usize> {
+        self.sample_fields
+    }
+
+    /// Returns the configured cap on the number of property sets of the span grouper, if any
+    /// (see [`Self::with_max_groups`]).
+    pub fn max_groups(&self) -> Option<usize> {
+        self.max_groups
+    }
+
+    /// Returns the configured `path_names` flag (see [`Self::with_path_names`]).
+    pub fn path_names(&self) -> bool {
+        self.path_names
+    }
+
+    /// Returns the configured `event_counting` flag (see [`Self::with_event_counting`]).
+    pub fn event_counting(&self) -> bool {
+        self.event_counting
+    }
+
+    /// Returns the configured epoch, if any (see [`Self::with_epoch`]).
+    pub fn epoch(&self) -> Option<SystemTime> {
+        self.epoch
+    }
+
+    /// Returns the configured `id_bytes` (see [`Self::with_id_bytes`]).
+    pub fn id_bytes(&self) -> usize {
+        self.id_bytes
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given [`TimeUnit`] for the recorded
+    /// latencies and other durations, e.g., [`TimeUnit::Nanos`] for very short spans, whose latencies lose
+    /// fidelity in microseconds, or [`TimeUnit::Millis`] for long-running spans.
+    ///
+    /// The configured `hist_high` is converted to the new unit so that it represents the same duration, with a
+    /// minimum of 2 (e.g., the default of 20 seconds is `20,000,000,000` in [`TimeUnit::Nanos`]), and likewise
+    /// `hist_low`, with a minimum of 1, so `hist_low` and `hist_high` should be set after the time unit to be set in
+    /// the new unit.
+    ///
+    /// The recorded values are in the configured unit, which is not part of the reported [`Timings`], so it
+    /// should be taken into account when interpreting them, e.g., with [`SummaryStats::with_unit`].
+    pub fn with_time_unit(&self, time_unit: TimeUnit) -> Self {
+        let convert = |value: u64, min: u128| {
+            let nanos = value as u128 * self.time_unit.nanos_per_unit() as u128;
+            (nanos / time_unit.nanos_per_unit() as u128).clamp(min, u64::MAX as u128) as u64
+        };
+        LatencyTraceCfg {
+            time_unit,
+            hist_low: convert(self.hist_low, 1),
+            hist_high: convert(self.hist_high, 2),
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `hist_low`
+    /// (see [hdrhistogram::Histogram::low]), i.e., the lowest value that is discernible from `0` in the histograms.
+    /// Values are still recorded if they are lower, but at the resolution of `hist_low`, so a higher `hist_low`,
+    /// e.g., with [`TimeUnit::Nanos`], reduces the memory used by the histograms when sub-threshold values are
+    /// just noise. `hist_high` must be at least twice `hist_low`.
+    pub fn with_hist_low(&self, hist_low: u64) -> Self {
+        LatencyTraceCfg {
+            hist_low,
+            ..self.clone()
+        }
+    }
+
     /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `hist_high`
     /// (see [hdrhistogram::Histogram::high]).
     pub fn with_hist_high(&self, hist_high: u64) -> Self {
         LatencyTraceCfg {
-            span_grouper: self.span_grouper.clone(),
             hist_high,
-            hist_sigfig: self.hist_sigfig,
+            ..self.clone()
         }
     }
 
@@ -61,9 +253,8 @@ impl LatencyTraceCfg {
     /// (see [hdrhistogram::Histogram::sigfig]).
     pub fn with_hist_sigfig(&self, hist_sigfig: u8) -> Self {
         LatencyTraceCfg {
-            span_grouper: self.span_grouper.clone(),
-            hist_high: self.hist_high,
             hist_sigfig,
+            ..self.clone()
         }
     }
 
@@ -74,8 +265,310 @@ impl LatencyTraceCfg {
     ) -> Self {
         LatencyTraceCfg {
             span_grouper: Arc::new(span_grouper),
-            hist_high: self.hist_high,
-            hist_sigfig: self.hist_sigfig,
+            multi_span_grouper: None,
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `max_groups` cap on the number of
+    /// distinct property sets returned by the configured span grouper, which bounds the number of span groups
+    /// per callsite and ancestors.
+    ///
+    /// When set, the span grouper is wrapped with [`bounded_cardinality`](crate::bounded_cardinality) when the
+    /// [`LatencyTrace`] is created, so the cap applies to whichever span grouper is configured: spans whose property
+    /// set is not among the first `max_groups` distinct ones are grouped with the single property
+    /// `(`[`OVERFLOW_PROP`](crate::OVERFLOW_PROP)`, "other")`. The cap does not apply to a
+    /// [`Self::with_multi_span_grouper`].
+    pub fn with_max_groups(&self, max_groups: Option<usize>) -> Self {
+        LatencyTraceCfg {
+            max_groups,
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `multi_span_grouper`, which replaces
+    /// the configured span grouper and returns multiple alternative property sets for a span, e.g., one by endpoint
+    /// and another by status. The span's latency is recorded into the span group of each property set, which
+    /// enables multi-dimensional rollups from a single run.
+    ///
+    /// The first property set is the primary one: its span group is the parent of the span groups of child spans
+    /// and, if [`Self::with_record_poll_counts`] is enabled, the one for which poll counts are recorded. The span
+    /// groups of the other property sets are siblings of the primary span group. Duplicate property sets are
+    /// ignored and an empty list of property sets is the same as a single empty property set.
+    ///
+    /// To bound the fan-out, only the first 4 property sets are used and the remaining ones are ignored.
+    pub fn with_multi_span_grouper(
+        &self,
+        multi_span_grouper: impl Fn(&Attributes) -> Vec<Vec<(String, String)>> + Send + Sync + 'static,
+    ) -> Self {
+        LatencyTraceCfg {
+            multi_span_grouper: Some(Arc::new(multi_span_grouper)),
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `source_root`.
+    ///
+    /// The `source_root` prefix is stripped from the file paths of span definitions, so that
+    /// [`SpanGroup::code_line`]s (and therefore [`SpanGroup::id`]s) are relative to `source_root`. This makes
+    /// span group IDs stable across machines whose source trees are located in different directories and makes
+    /// reports more readable. File paths that are not under `source_root` are left unchanged.
+    pub fn with_source_root(&self, source_root: impl AsRef<Path>) -> Self {
+        LatencyTraceCfg {
+            source_root: Some(source_root.as_ref().into()),
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `separate_panicked` flag.
+    ///
+    /// When `separate_panicked` is `true`, spans that are closed while their thread is panicking (i.e., closed
+    /// during unwinding, see [`std::thread::panicking`]) are recorded in a separate span group whose
+    /// [`SpanGroup::props`] are those of the regular span group followed by `("panicked", "true")`.
+    /// This keeps the latencies of panic paths, which can be abnormally short or long, from polluting the
+    /// happy-path latency distribution, e.g., in fault-injection testing.
+    ///
+    /// Child span groups of a panicked span group remain children of the corresponding regular span group, as
+    /// the child spans were created before the panic.
+    pub fn with_separate_panicked(&self, separate_panicked: bool) -> Self {
+        LatencyTraceCfg {
+            separate_panicked,
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `record_poll_counts` flag.
+    ///
+    /// When `record_poll_counts` is `true`, the number of times each span is entered is counted and, when the span
+    /// closes, recorded in a per-span-group distribution, which is available from [`Report::poll_counts`] (see
+    /// [`LatencyTrace::measure_report`]). For async spans, a span is entered each time its
+    /// instrumented future is polled (and, with recent versions of *tracing*, when the future is dropped), so
+    /// this indicates executor churn: a high poll count combined with low latency signals wasteful wakeups.
+    ///
+    /// Counting adds a small overhead to each span entry, so it is disabled by default.
+    pub fn with_record_poll_counts(&self, record_poll_counts: bool) -> Self {
+        LatencyTraceCfg {
+            record_poll_counts,
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but recording only the `n`-th (starting at 1) closed span
+    /// of each span group, on each thread. This is a debugging aid to isolate a specific invocation, e.g., a slow
+    /// later repetition of a request in a loop.
+    ///
+    /// All span groups are still reported, with empty histograms for those with fewer than `n` occurrences.
+    /// Occurrences are counted separately on each thread, so for multi-threaded code the `n`-th occurrence of
+    /// each thread is recorded. Checkpoints (see [`crate::checkpoint`]) are not affected.
+    pub fn with_record_only_nth(&self, n: u64) -> Self {
+        LatencyTraceCfg {
+            record_only_nth: Some(n),
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but recording the latencies of only a fraction `rate`
+    /// (in the range `0.0..=1.0`) of the closed spans of each span group, to reduce the overhead of very frequent
+    /// spans.
+    ///
+    /// Sampling is deterministic: on each thread, the first closed span of each span group and every `k`-th one after
+    /// it are recorded, where `k` is `1 / rate` rounded to the nearest integer, so the effective rate (see
+    /// [`Self::sampling`]) is `1 / k`. Since occurrences are counted per span group, span groups that are rarely hit
+    /// are always recorded. A `rate` of `1.0` or more records all spans.
+    ///
+    /// The histograms only contain the sampled latencies, so their counts are reduced by the sampling rate while
+    /// their percentiles are estimates from the sample, which is not random and can therefore be biased for periodic
+    /// workloads. The counts of all closed spans are available from
+    /// [`Report::instance_counts`]. All span groups are reported and checkpoints (see
+    /// [`crate::checkpoint`]) are not affected.
+    pub fn with_sampling(&self, rate: f64) -> Self {
+        // The float to integer cast saturates, and a NaN rate is cast to 0.
+        let stride = (1.0 / rate).round() as u64;
+        LatencyTraceCfg {
+            sample_stride: if stride <= 1 { None } else { Some(stride) },
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `drop_root` flag.
+    ///
+    /// When `drop_root` is `true`, root span groups (those with [`SpanGroup::depth`] 1) are omitted from reports.
+    /// This declutters reports focused on the internal breakdown of a top-level span that just wraps everything,
+    /// e.g., an instrumented `main` function whose latency is that of the whole run.
+    ///
+    /// The children of the omitted span groups become roots, i.e., they have no [`SpanGroup::parent_id`], and
+    /// the depths of all reported span groups are decremented by 1. [`SpanGroup::id`]s are unchanged, so they
+    /// can still be compared with those of reports that include the root span groups.
+    pub fn with_drop_root(&self, drop_root: bool) -> Self {
+        LatencyTraceCfg {
+            drop_root,
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `record_offsets` flag.
+    ///
+    /// When `record_offsets` is `true`, the start offset of the first closed span of each span group relative to the
+    /// creation of its root span is recorded and reported by [`Report::offsets`]. For a
+    /// trace of a single request, this shows the sequencing of its span groups, e.g., as a waterfall (see
+    /// `Timings::to_waterfall`, available with the **"serde"** feature).
+    pub fn with_record_offsets(&self, record_offsets: bool) -> Self {
+        LatencyTraceCfg {
+            record_offsets,
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `on_report` callback, which is
+    /// invoked with the [`Timings`] of every report, i.e., whenever latencies are reported by a `measure_latencies*`
+    /// method or by [`ProbedTrace::probe_latencies`] and [`ProbedTrace::wait_and_report`], except for the per-thread
+    /// reports of [`LatencyTrace::measure_latencies_per_thread`]. This allows the export or
+    /// logging of reports to be set up once, with the configuration, rather than at every call site.
+    ///
+    /// The callback is invoked on the thread that produces the report, before the report is returned.
+    pub fn with_on_report(&self, on_report: impl Fn(&Timings) + Send + Sync + 'static) -> Self {
+        LatencyTraceCfg {
+            on_report: Some(Arc::new(on_report)),
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `record_active_time` flag.
+    ///
+    /// When `record_active_time` is `true`, the time each span is entered (i.e., the time between entering and
+    /// exiting it, accumulated across entries) is recorded in a per-span-group distribution when the span closes,
+    /// which is reported by [`Report::active_timings`]. The [`Timings`] record total
+    /// times, from span creation to close, so for async spans, which are entered each time their instrumented
+    /// future is polled, comparing the two distinguishes spans that keep the executor busy from those that mostly
+    /// wait while suspended. The difference of the two for each span, its suspended time, is also recorded in a
+    /// per-span-group distribution, which is reported by [`Report::suspend_timings`].
+    ///
+    /// Tracking entries and exits adds a small overhead to each of them, so it is disabled by default.
+    pub fn with_record_active_time(&self, record_active_time: bool) -> Self {
+        LatencyTraceCfg {
+            record_active_time,
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `min_level`.
+    ///
+    /// Spans with a level below `min_level` (e.g., `trace` and `debug` spans if `min_level` is [`Level::INFO`]) are
+    /// disabled at their callsites, so they incur no collection overhead, and their latencies are not recorded. The
+    /// children of a disabled span are recorded as children of its nearest enabled ancestor, so span group paths
+    /// skip the disabled spans. This also applies to the spans of [`DropTimer`](crate::DropTimer) and the traced
+    /// locks (see [`TracedMutex`](crate::TracedMutex)), whose level is `trace`.
+    ///
+    /// When [`LatencyTrace`] is used as a [`Layer`], the disabled spans are also disabled for the other layers of
+    /// the subscriber.
+    pub fn with_min_level(&self, min_level: Level) -> Self {
+        LatencyTraceCfg {
+            min_level,
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `auto_resize` flag.
+    ///
+    /// When `auto_resize` is `false`, the latency histograms cover exactly the range from `hist_low` to `hist_high`
+    /// and are never reallocated while latencies are recorded, so the overhead of recording is deterministic, which
+    /// matters for sensitive benchmarks. Latencies that exceed `hist_high` are then not recorded; instead, they are
+    /// counted per span group, and the counts can be obtained with [`Report::overflow_counts`]. The histograms of poll counts, offsets, and active
+    /// times remain auto-resizable.
+    pub fn with_auto_resize(&self, auto_resize: bool) -> Self {
+        LatencyTraceCfg {
+            auto_resize,
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with latencies recorded in batches of `n`
+    /// (at least 1).
+    ///
+    /// By default, the latency of a span is recorded in the histogram of its span group as soon as the span closes.
+    /// With batching, each thread instead buffers the latencies of its closed spans and records them all when `n`
+    /// latencies are buffered, as well as when the latencies are reported. This replaces the per-close histogram
+    /// update with the buffering of the span group and latency, which can reduce the overhead of workloads with very
+    /// many short spans, at the cost of the memory of the buffers and of occasional flushes that add to the
+    /// latencies of the enclosing spans. The `dev_support` benchmark `bench_flush_batch_divan` compares the two
+    /// modes.
+    ///
+    /// [`Report::resize_counts`] counts the histogram resizes caused by flushes.
+    pub fn with_flush_batch(&self, n: usize) -> Self {
+        LatencyTraceCfg {
+            flush_batch: Some(n.max(1)),
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with up to `n` (at least 1) distinct field value
+    /// samples recorded for each span group.
+    ///
+    /// The samples are the name-value pairs of all the fields of the spans at creation (see
+    /// [`crate::group_by_all_fields`]), whether or not the configured span grouper uses them, e.g., to find out which
+    /// `request_id`s are among the inputs of a span group with a slow p99 without grouping by `request_id`. The first
+    /// `n` distinct samples of each span group are kept, which bounds the memory used. Sampling requires the fields of
+    /// every span to be formatted, which adds to the overhead of span creation. The samples are returned by
+    /// [`Report::field_samples`].
+    pub fn with_sample_fields(&self, n: usize) -> Self {
+        LatencyTraceCfg {
+            sample_fields: Some(n.max(1)),
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `path_names` flag.
+    ///
+    /// When set, a `"path"` property is added, before the properties from the configured span grouper, to the span
+    /// group of each span. Its value is the names of the span's ancestors and the span itself, from the root,
+    /// separated by `/`, e.g., `"root/child/grandchild"`, so that span groups carry a readable path. The span groups
+    /// are the same as without the property, as the path is determined by the span group's callsite and ancestors.
+    pub fn with_path_names(&self, path_names: bool) -> Self {
+        LatencyTraceCfg {
+            path_names,
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `event_counting` flag.
+    ///
+    /// When set, the *tracing* events emitted within the spans of each span group, e.g., `warn!`s, are counted by
+    /// level, which is reported by [`Report::event_counts`]. Events are not timed and
+    /// the events emitted by [`crate::checkpoint`] are not counted.
+    pub fn with_event_counting(&self, event_counting: bool) -> Self {
+        LatencyTraceCfg {
+            event_counting,
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `epoch`.
+    ///
+    /// When an epoch is set, the start time of each span, in the configured [`TimeUnit`] since `epoch`, is recorded in a
+    /// per-span-group distribution, which is reported by [`Report::epoch_offsets`].
+    /// With an epoch shared with other services (e.g., the start of a distributed test run), this allows the
+    /// latencies measured here to be aligned with the spans of those services. Spans created before `epoch` have a
+    /// start time of 0.
+    ///
+    /// Start times are recorded with the configured `hist_sigfig` precision, so a recent epoch should be used to keep
+    /// the recorded values, and thus their absolute errors, small.
+    pub fn with_epoch(&self, epoch: SystemTime) -> Self {
+        LatencyTraceCfg {
+            epoch: Some(epoch),
+            ..self.clone()
+        }
+    }
+
+    /// Creates a new [`LatencyTraceCfg`] the same as `self` but with the given `id_bytes`, the number of bytes of
+    /// the SHA-256 hash used to compute [`SpanGroup::id`]s, which are the base64 encoding of those bytes.
+    /// `id_bytes` is clamped to the range `4..=32`.
+    ///
+    /// More bytes make ID collisions less likely in very large traces, while fewer bytes make IDs shorter.
+    /// Span group IDs computed with different `id_bytes` are not comparable.
+    pub fn with_id_bytes(&self, id_bytes: usize) -> Self {
+        LatencyTraceCfg {
+            id_bytes: id_bytes.clamp(MIN_ID_BYTES, MAX_ID_BYTES),
+            ..self.clone()
         }
     }
 }
@@ -93,13 +586,55 @@ impl LatencyTraceCfg {
 /// (by method [`Self::activated`] or [`Self::activated_default`])
 /// as the global default [`tracing::Subscriber`], of which there can be only one and it can't be changed once
 /// it is set.
+///
+/// Alternatively, an instance constructed with [`Self::new`] can be added as one [`Layer`] among many to a
+/// subscriber built by the caller, e.g., `Registry::default().with(lt.clone()).with(other_layer)`, which need not be
+/// the global default. Latencies are then extracted with [`Self::take_acc_timings`] followed by
+/// [`Self::report_timings`], or with any of the `measure_latencies*` methods.
 #[derive(Clone)]
 pub struct LatencyTrace(pub(crate) LatencyTraceG<Probed>);
 
 impl LatencyTrace {
     /// Constructs `Self` with the given configuration. Can be used to construct an instance for use as a [`Layer`].
-    pub fn new(config: LatencyTraceCfg) -> Self {
-        Self(LatencyTraceG::new(config))
+    ///
+    /// # Errors
+    /// - [`ActivationError::HistogramConfigError`] if the `config`'s histogram parameters are invalid (see
+    ///   [`Self::activated`]).
+    pub fn new(config: LatencyTraceCfg) -> Result<Self, ActivationError> {
+        config.validate_hist_config()?;
+        Ok(Self(LatencyTraceG::new(config)))
+    }
+
+    /// Returns a [`LatencyTraceBuilder`] with the default configuration, for the fluent configuration and activation
+    /// of `Self`, e.g., `LatencyTrace::builder().hist_high(60_000_000).measure_latencies(f)`.
+    pub fn builder() -> LatencyTraceBuilder {
+        LatencyTraceBuilder::default()
+    }
+
+    /// Executes the instrumented function `f` with a new instance of `Self`, configured with `config`, and returns the
+    /// latencies observed during its execution. Unlike the `activated*` methods, this doesn't set the global default
+    /// [`tracing::Subscriber`], so that unrelated code regions can be measured with different configurations, e.g.,
+    /// different span groupers, in the same process, even if another instance of `Self` is active.
+    ///
+    /// `f` is executed to completion on a new thread, not on the current thread, with the new instance as the thread's
+    /// default [`tracing::Subscriber`]. This is because the thread-local latency information of a thread is bound to
+    /// the first instance of `Self` that records latencies on it, so the current thread may already be bound to
+    /// another instance. Caveats:
+    /// - Threads spawned by `f` don't inherit the subscriber. Their spans are only recorded if they set the subscriber
+    ///   of `f`'s thread, obtained with [`tracing::dispatcher::get_default`], as their default, e.g., with
+    ///   [`tracing::dispatcher::with_default`], and if no other instance of `Self` has recorded latencies on them. So,
+    ///   pooled threads, e.g., of an async runtime created before the call, should not be used.
+    /// - Spans that outlive `f` on other threads are not recorded.
+    /// - Instrumented async code must be driven to completion within `f`, e.g., by a current-thread runtime created in `f`.
+    ///
+    /// # Errors
+    /// - [`ActivationError::HistogramConfigError`] if the `config`'s histogram parameters are invalid (see
+    ///   [`Self::activated`]).
+    pub fn with_scoped(
+        config: LatencyTraceCfg,
+        f: impl FnOnce() + Send,
+    ) -> Result<Timings, ActivationError> {
+        LatencyTraceG::<Probed>::with_scoped(config, f)
     }
 
     /// Returns the active instance of `Self` if it exists. An active instance is an instance that is registered as
@@ -114,13 +649,17 @@ impl LatencyTrace {
     /// be changed once it is set.
     ///
     /// If a [`LatencyTrace`] has been previously activated in the same process, the `config` passed to this
-    /// function will be ignored and the current active [`LatencyTrace`] will be returned.
+    /// function will be ignored and the current active [`LatencyTrace`] will be returned, provided that its
+    /// time unit and histogram settings are the same as those of `config`.
     ///
     /// # Errors
-    /// - [`ActivationError::HistogramConfigError`] if the `config`'s `hist_high` and `hist_sigfig` would cause
-    ///   [`hdrhistogram::Histogram::new_with_bounds`]`(1, hist_high, hist_sigfig)` to fail.
+    /// - [`ActivationError::HistogramConfigError`] if the `config`'s `hist_low`, `hist_high`, and `hist_sigfig` would
+    ///   cause [`hdrhistogram::Histogram::new_with_bounds`]`(hist_low, hist_high, hist_sigfig)` to fail, e.g., if
+    ///   `hist_high` is less than twice `hist_low` or `hist_sigfig` is greater than 5.
     /// - [`ActivationError::TracingSubscriberInitError`] if a global [`tracing::Subscriber`] is already set and its
     ///   type is not the same as `Self`.
+    /// - [`ActivationError::ConfigMismatch`] if an instance is already active with a different `time_unit`,
+    ///   `hist_low`, `hist_high`, or `hist_sigfig`.
     pub fn activated(config: LatencyTraceCfg) -> Result<Self, ActivationError> {
         Ok(Self(LatencyTraceG::activated(config)?))
     }
@@ -131,7 +670,7 @@ impl LatencyTrace {
     /// be changed once it is set.
     ///
     /// If a [`LatencyTrace`] has been previously activated in the same process, the default configuration
-    /// will be ignored and the current active [`LatencyTrace`] will be returned.
+    /// will be ignored and the current active [`LatencyTrace`] will be returned, whatever its configuration.
     ///
     /// # Errors
     /// - [`ActivationError::TracingSubscriberInitError`] if a global [`tracing::Subscriber`] is already set and its
@@ -145,6 +684,191 @@ impl LatencyTrace {
         self.0.measure_latencies(f)
     }
 
+    /// Executes the instrumented function `f` and, after `f` completes, returns its result and the observed
+    /// latencies, e.g., to measure a real function without passing its result out through captured variables.
+    pub fn measure_latencies_r<T>(&self, f: impl FnOnce() -> T) -> (T, Timings) {
+        self.0.measure_latencies_r(f)
+    }
+
+    /// Executes the instrumented function `f` with a [`thread::Scope`] in which `f` can spawn worker threads and,
+    /// after `f` and all the threads spawned in the scope complete, returns the observed latencies.
+    ///
+    /// Spans closed on threads spawned with [`thread::spawn`] are only reported if the threads have completed by the time
+    /// latencies are extracted, which requires the threads to be joined by `f`. With this method, the scope waits
+    /// for all its threads, whether or not `f` joins them, so their latencies are always included in the report.
+    pub fn measure_latencies_scoped<'env, F>(&self, f: F) -> Timings
+    where
+        F: for<'scope> FnOnce(&'scope thread::Scope<'scope, 'env>),
+    {
+        self.0.measure_latencies(|| thread::scope(f))
+    }
+
+    /// Repeatedly executes the instrumented function `f` until `duration` has elapsed and then returns the
+    /// observed latencies, e.g., to run a workload for 30 seconds without manual loop and timer code.
+    /// `f` is executed at least once and the last execution may end after `duration`.
+    pub fn measure_latencies_for(&self, duration: Duration, f: impl Fn()) -> Timings {
+        self.0.measure_latencies_for(duration, f)
+    }
+
+    /// Repeatedly executes the instrumented function `f` until the value at `quantile` of the span group with ID
+    /// `group_id` is stable or `max_duration` has elapsed, and then returns the observed latencies. This automates
+    /// running a benchmark workload long enough for its results to be stable.
+    ///
+    /// After each execution of `f`, the partial latencies are probed and the value at `quantile` is considered stable
+    /// if its relative change since the previous probe is less than `rel_tolerance` (e.g., `0.01` for 1%). As each
+    /// probe processes the latencies collected so far, `f` should be a batch of work (e.g., many requests) rather
+    /// than a single short operation. `f` is executed at least twice unless `max_duration` elapses first, and the
+    /// last execution may end after `max_duration`. The probes don't invoke the [`LatencyTraceCfg::with_on_report`]
+    /// callback, which is only invoked with the returned latencies.
+    pub fn run_until_stable(
+        &self,
+        group_id: &str,
+        quantile: f64,
+        rel_tolerance: f64,
+        max_duration: Duration,
+        f: impl Fn(),
+    ) -> Timings {
+        let start = Instant::now();
+        let mut prev_value: Option<u64> = None;
+        loop {
+            f();
+            if start.elapsed() >= max_duration {
+                break;
+            }
+            let timings = self.0.build_report(self.0.control.probe_tls()).timings;
+            let value = timings
+                .iter()
+                .find(|(sg, timing)| sg.id() == group_id && !timing.is_empty())
+                .map(|(_, timing)| timing.value_at_quantile(quantile));
+            if let (Some(prev), Some(value)) = (prev_value, value) {
+                let rel_change = match prev {
+                    0 if value == 0 => 0.0,
+                    0 => f64::INFINITY,
+                    _ => (value as f64 - prev as f64).abs() / prev as f64,
+                };
+                if rel_change < rel_tolerance {
+                    break;
+                }
+            }
+            prev_value = value;
+        }
+        let acc = self.0.take_acc_timings();
+        self.0.report_timings(acc)
+    }
+
+    /// Executes the instrumented async function `f`, running on `executor`; after `f` completes,
+    /// returns the observed latencies.
+    pub fn measure_latencies_with<F>(
+        &self,
+        executor: &impl Executor,
+        f: impl FnOnce() -> F,
+    ) -> Timings
+    where
+        F: Future<Output = ()>,
+    {
+        self.0.measure_latencies_with(executor, f)
+    }
+
+    /// Executes the instrumented async function `f`, running on `executor` in a separate thread; returns a
+    /// [`ProbedTrace`] that allows partial latencies to be reported before `f` completes.
+    pub fn measure_latencies_probed_with<F>(
+        self,
+        executor: impl Executor + Send + 'static,
+        f: impl FnOnce() -> F + Send + 'static,
+    ) -> Result<ProbedTrace, ActivationError>
+    where
+        F: Future<Output = ()>,
+    {
+        self.measure_latencies_probed(move || executor.block_on(f()))
+    }
+
+    /// Executes the instrumented function `f` and, after `f` completes, returns the [`Report`] of the measurement,
+    /// which carries the observed latencies together with the other per-span-group data recorded in the same run,
+    /// e.g., the [`ActiveTimings`] recorded if [`LatencyTraceCfg::with_record_active_time`] is enabled.
+    pub fn measure_report(&self, f: impl FnOnce()) -> Report {
+        self.0.measure_report(f)
+    }
+
+    /// Executes the instrumented function `f` and, after `f` completes, returns the observed latencies of each
+    /// thread on which spans were closed, e.g., to diagnose an imbalance of work across worker threads.
+    pub fn measure_latencies_per_thread(&self, f: impl FnOnce()) -> HashMap<ThreadId, Timings> {
+        self.0.measure_latencies_per_thread(f)
+    }
+
+    /// Extracts the latency information collected so far from all threads, leaving `self` with no collected
+    /// information. The result is post-processed into [`Timings`] by [`Self::report_timings`].
+    ///
+    /// Together with [`Self::report_timings`], this supports the extraction of latencies when `self` is used as a
+    /// [`Layer`] of a subscriber that is not managed by `self`, without wrapping the measured code in a
+    /// `measure_latencies*` method. Spans closed on threads that are still running are included, while
+    /// spans that are still open are not.
+    pub fn take_acc_timings(&self) -> Vec<RawTrace> {
+        self.0.take_acc_timings()
+    }
+
+    /// Post-processes the latency information extracted by [`Self::take_acc_timings`] into [`Timings`].
+    pub fn report_timings(&self, acc: Vec<RawTrace>) -> Timings {
+        self.0.report_timings(acc)
+    }
+
+    /// Post-processes the latency information extracted by [`Self::take_acc_timings`] into [`Timings`] for each
+    /// thread on which spans were closed, e.g., to diagnose an imbalance of work across worker threads.
+    /// Latency information restored with [`Self::restore`] is not attributed to any thread and is not included.
+    pub fn report_timings_per_thread(&self, acc: Vec<RawTrace>) -> HashMap<ThreadId, Timings> {
+        self.0.report_timings_per_thread(acc)
+    }
+
+    /// Returns a snapshot of the latency information collected so far, without changing it. The snapshot can later
+    /// be passed to [`Self::restore`] to continue accumulation from the snapshot, e.g., to compare two workloads
+    /// that start from a common warm-up.
+    ///
+    /// The snapshot is not attributed to any thread, even if all of its latencies were recorded on one thread, so
+    /// it is not included in [`Self::report_timings_per_thread`] once restored.
+    ///
+    /// Should be called when no instrumented code is running on other threads, otherwise the snapshot may
+    /// contain only part of the latencies recorded concurrently.
+    pub fn snapshot(&self) -> RawTrace {
+        let acc = self.0.control.probe_tls();
+        RawTrace {
+            thread_id: None,
+            ..LatencyTraceG::<Probed>::reduce_acc_to_raw_trace(acc)
+        }
+    }
+
+    /// Replaces the latency information collected so far with `snapshot`, which was previously obtained with
+    /// [`Self::snapshot`]. Subsequently recorded latencies are accumulated on top of `snapshot`.
+    ///
+    /// Should be called when no instrumented code is running on other threads, otherwise latencies recorded
+    /// concurrently may be lost or recorded on top of `snapshot`.
+    pub fn restore(&self, snapshot: RawTrace) {
+        self.0.control.take_tls();
+        self.0.control.take_acc(vec![snapshot]);
+    }
+
+    /// Discards the latency information collected so far, so that subsequently recorded latencies start fresh,
+    /// e.g., to measure successive phases of a benchmark separately, as the subscriber can't be re-activated.
+    ///
+    /// The recording of latencies is blocked while the information is discarded, so it is safe to call this while
+    /// instrumented code is running on other threads. Spans that are open during the reset are recorded when they
+    /// close, with their full latencies, including the time before the reset. Information about the callsites of
+    /// those spans is kept so that they can still be reported. As with [`ProbeMode::Blocking`], recordings already in
+    /// progress the first time recording is blocked are not blocked, so they may be kept or discarded.
+    pub fn reset(&self) {
+        let _gate = self.0.block_recording();
+        self.0.control.take_tls();
+        let acc = self.0.control.take_acc(Vec::new());
+        let RawTrace {
+            callsite_infos,
+            open_spans,
+            ..
+        } = LatencyTraceG::<Probed>::reduce_acc_to_raw_trace(acc);
+        self.0.control.take_acc(vec![RawTrace {
+            callsite_infos,
+            open_spans,
+            ..RawTrace::new()
+        }]);
+    }
+
     /// Executes the instrumented function `f`, returning a [`ProbedTrace`] that allows partial latencies to be
     /// reported before `f` completes.
     pub fn measure_latencies_probed(
@@ -161,7 +885,7 @@ impl LatencyTrace {
 impl Default for LatencyTrace {
     /// Constructs `Self` with default configuration. Can be used to construct an instance for use as a [`Layer`].
     fn default() -> Self {
-        Self::new(LatencyTraceCfg::default())
+        Self(LatencyTraceG::new(LatencyTraceCfg::default()))
     }
 }
 impl<S> Layer<S> for LatencyTrace
@@ -169,13 +893,29 @@ where
     S: Subscriber,
     S: for<'lookup> LookupSpan<'lookup>,
 {
+    fn register_callsite(&self, meta: &'static Metadata<'static>) -> Interest {
+        Layer::<S>::register_callsite(&self.0, meta)
+    }
+
+    fn enabled(&self, meta: &Metadata<'_>, ctx: Context<'_, S>) -> bool {
+        self.0.enabled(meta, ctx)
+    }
+
     fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
         self.0.on_new_span(attrs, id, ctx);
     }
 
-    // No need for fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        self.0.on_enter(id, ctx);
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        self.0.on_exit(id, ctx);
+    }
 
-    // No need for fn on_exit(&self, id: &Id, ctx: Context<'_, S>)
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        self.0.on_event(event, ctx);
+    }
 
     fn on_close(&self, id: Id, ctx: Context<'_, S>) {
         self.0.on_close(id, ctx);
@@ -183,40 +923,464 @@ where
 }
 
 //==============
-// pub impl for SpanGroup
+// pub impl for LatencyTraceBuilder
 
-impl SpanGroup {
-    /// Returns the span group's name.
-    pub fn name(&self) -> &'static str {
-        self.name
-    }
+/// Fluent builder of the [`LatencyTraceCfg`] of a [`LatencyTrace`], which it activates, obtained with
+/// [`LatencyTrace::builder`]. The most common settings have their own methods and the others can be set with
+/// [`Self::cfg`].
+#[derive(Clone, Default)]
+pub struct LatencyTraceBuilder {
+    cfg: LatencyTraceCfg,
+}
 
-    /// Returns the span group's ID.
-    pub fn id(&self) -> &str {
-        &self.id
+impl LatencyTraceBuilder {
+    /// Sets the time unit (see [`LatencyTraceCfg::with_time_unit`]).
+    pub fn time_unit(self, time_unit: TimeUnit) -> Self {
+        self.cfg(|cfg| cfg.with_time_unit(time_unit))
     }
 
-    /// Returns the span group's file name and code line.
-    pub fn code_line(&self) -> &str {
-        &self.code_line
+    /// Sets `hist_low` (see [`LatencyTraceCfg::with_hist_low`]).
+    pub fn hist_low(self, hist_low: u64) -> Self {
+        self.cfg(|cfg| cfg.with_hist_low(hist_low))
     }
 
-    /// Returns the span group's properties list.
-    ///
-    /// This list can be empty as is the case with the default span grouper.
-    pub fn props(&self) -> &[(String, String)] {
-        &self.props
+    /// Sets `hist_high` (see [`LatencyTraceCfg::with_hist_high`]).
+    pub fn hist_high(self, hist_high: u64) -> Self {
+        self.cfg(|cfg| cfg.with_hist_high(hist_high))
     }
 
-    /// Returns the ID of the span group's parent.
-    pub fn parent_id(&self) -> Option<&str> {
-        self.parent_id.iter().map(|x| x.as_ref()).next()
+    /// Sets `hist_sigfig` (see [`LatencyTraceCfg::with_hist_sigfig`]).
+    pub fn sigfig(self, hist_sigfig: u8) -> Self {
+        self.cfg(|cfg| cfg.with_hist_sigfig(hist_sigfig))
     }
 
-    /// Returns the number of ancestor span groups this span group has.
+    /// Sets the span grouper (see [`LatencyTraceCfg::with_span_grouper`]).
+    pub fn span_grouper(
+        self,
+        span_grouper: impl Fn(&Attributes) -> Vec<(String, String)> + Send + Sync + 'static,
+    ) -> Self {
+        self.cfg(|cfg| cfg.with_span_grouper(span_grouper))
+    }
+
+    /// Sets the minimum level of the spans that are recorded (see [`LatencyTraceCfg::with_min_level`]).
+    pub fn min_level(self, min_level: Level) -> Self {
+        self.cfg(|cfg| cfg.with_min_level(min_level))
+    }
+
+    /// Transforms the configuration with `f`, e.g., `builder.cfg(|cfg| cfg.with_drop_root(true))`, for the
+    /// settings that have no method of their own.
+    pub fn cfg(self, f: impl FnOnce(&LatencyTraceCfg) -> LatencyTraceCfg) -> Self {
+        Self { cfg: f(&self.cfg) }
+    }
+
+    /// Returns the configuration built so far.
+    pub fn build_cfg(self) -> LatencyTraceCfg {
+        self.cfg
+    }
+
+    /// Activates a [`LatencyTrace`] with the configuration built so far (see [`LatencyTrace::activated`]).
+    ///
+    /// # Errors
+    /// Same as [`LatencyTrace::activated`].
+    pub fn activated(self) -> Result<LatencyTrace, ActivationError> {
+        LatencyTrace::activated(self.cfg)
+    }
+
+    /// Activates a [`LatencyTrace`] with the configuration built so far and executes the instrumented function `f`
+    /// with it (see [`LatencyTrace::measure_latencies`]).
+    ///
+    /// # Errors
+    /// Same as [`LatencyTrace::activated`].
+    pub fn measure_latencies(self, f: impl FnOnce()) -> Result<Timings, ActivationError> {
+        Ok(self.activated()?.measure_latencies(f))
+    }
+}
+
+//==============
+// pub impl for SpanGroup
+
+impl SpanGroup {
+    /// Returns the span group's name.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the span group's ID.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the span group's file name and code line.
+    pub fn code_line(&self) -> &str {
+        &self.code_line
+    }
+
+    /// Returns the span group's properties list.
+    ///
+    /// This list can be empty as is the case with the default span grouper.
+    pub fn props(&self) -> &[(String, String)] {
+        &self.props
+    }
+
+    /// Returns the ID of the span group's parent.
+    pub fn parent_id(&self) -> Option<&str> {
+        self.parent_id.iter().map(|x| x.as_ref()).next()
+    }
+
+    /// Returns the number of ancestor span groups this span group has.
     pub fn depth(&self) -> usize {
         self.depth
     }
+
+    /// Returns the [`tracing`] target of the span definition, e.g., for grouping by crate or subsystem.
+    /// For [`crate::checkpoint`] span groups, this is the target of the checkpoint event.
+    pub fn target(&self) -> &'static str {
+        self.target
+    }
+
+    /// Returns the module path of the span definition, if available.
+    pub fn module_path(&self) -> Option<&'static str> {
+        self.module_path
+    }
+}
+
+//==============
+// pub impl for Report
+
+impl Report {
+    /// Observed latencies of the span groups.
+    pub fn timings(&self) -> &Timings {
+        &self.timings
+    }
+
+    /// Consumes `self` and returns its observed latencies.
+    pub fn into_timings(self) -> Timings {
+        self.timings
+    }
+
+    /// Distributions of the number of span entries (async polls) of the span groups, recorded if
+    /// [`LatencyTraceCfg::with_record_poll_counts`] is enabled (empty otherwise).
+    pub fn poll_counts(&self) -> &PollCounts {
+        &self.poll_counts
+    }
+
+    /// Start offsets of the span groups relative to the creation of their root spans, recorded if
+    /// [`LatencyTraceCfg::with_record_offsets`] is enabled (empty otherwise).
+    pub fn offsets(&self) -> &Offsets {
+        &self.offsets
+    }
+
+    /// Distributions of the start times of the spans of the span groups since the configured epoch, recorded if
+    /// [`LatencyTraceCfg::with_epoch`] is set (empty otherwise).
+    pub fn epoch_offsets(&self) -> &EpochOffsets {
+        &self.epoch_offsets
+    }
+
+    /// Number of closed spans of each span group, regardless of [`LatencyTraceCfg::with_record_only_nth`] and
+    /// [`LatencyTraceCfg::with_sampling`].
+    pub fn instance_counts(&self) -> &InstanceCounts {
+        &self.instance_counts
+    }
+
+    /// Number of latencies of each span group that were not recorded because they exceeded
+    /// [`LatencyTraceCfg::hist_high`] when [`LatencyTraceCfg::with_auto_resize`] is `false` (empty otherwise).
+    pub fn overflow_counts(&self) -> &OverflowCounts {
+        &self.overflow_counts
+    }
+
+    /// Active times of the span groups, recorded if [`LatencyTraceCfg::with_record_active_time`] is enabled
+    /// (empty otherwise).
+    pub fn active_timings(&self) -> &ActiveTimings {
+        &self.active_timings
+    }
+
+    /// Ratio of the mean active time to the mean latency of each span group, i.e., `mean(active)/mean(total)`.
+    /// A ratio near `1.0` indicates spans that keep the thread busy, e.g., CPU-bound work, while a ratio near `0.0`
+    /// indicates spans that mostly wait while suspended. Span groups with a mean latency of `0`, e.g., those with no
+    /// recorded latencies, have a ratio of `0.0`.
+    ///
+    /// Empty unless [`LatencyTraceCfg::with_record_active_time`] is enabled.
+    pub fn active_ratio(&self) -> BTreeMap<SpanGroup, f64> {
+        self.active_timings
+            .iter()
+            .filter_map(|(sg, active)| {
+                let total = self.timings.get(sg)?.mean();
+                let ratio = if total == 0.0 {
+                    0.0
+                } else {
+                    active.mean() / total
+                };
+                Some((sg.clone(), ratio))
+            })
+            .collect()
+    }
+
+    /// Suspended times of the span groups, recorded together with the [`Self::active_timings`] (empty otherwise).
+    pub fn suspend_timings(&self) -> &SuspendTimings {
+        &self.suspend_timings
+    }
+
+    /// Distinct field values sampled from the spans of each span group, recorded if
+    /// [`LatencyTraceCfg::with_sample_fields`] is set (empty otherwise).
+    pub fn field_samples(&self) -> &FieldSamples {
+        &self.field_samples
+    }
+
+    /// Number of events, by level, emitted within the spans of each span group, counted if
+    /// [`LatencyTraceCfg::with_event_counting`] is enabled (empty otherwise).
+    pub fn event_counts(&self) -> &EventCounts {
+        &self.event_counts
+    }
+
+    /// Number of times the latency histograms of the span groups were auto-resized, which helps tune
+    /// [`LatencyTraceCfg::with_hist_high`].
+    pub fn resize_counts(&self) -> &ResizeCounts {
+        &self.resize_counts
+    }
+
+    /// [`ReportDiagnostics`] about the captured thread-local data, which help detect latencies missing from the
+    /// report, e.g., because spans on threads that were not joined had not yet closed.
+    pub fn diagnostics(&self) -> &ReportDiagnostics {
+        &self.diagnostics
+    }
+
+    /// Wall-clock duration of the measurement, from the start of the instrumented function until the latencies
+    /// were extracted.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the number of closed spans of `sg` per second over the [`Self::duration`] of the measurement, e.g.,
+    /// the number of requests per second handled by a server, or `None` if `sg` is not in `self`. Returns `0.0` if
+    /// the duration is zero.
+    ///
+    /// All closed spans are counted, including those whose latencies were not recorded because of
+    /// [`LatencyTraceCfg::with_record_only_nth`] or [`LatencyTraceCfg::with_sampling`].
+    pub fn throughput(&self, sg: &SpanGroup) -> Option<f64> {
+        let count = *self.instance_counts.get(sg)?;
+        let secs = self.duration.as_secs_f64();
+        if secs == 0.0 {
+            return Some(0.0);
+        }
+        Some(count as f64 / secs)
+    }
+}
+
+//==============
+// pub impl for ReportDiagnostics
+
+impl ReportDiagnostics {
+    /// Number of threads whose thread-local data was captured in the report.
+    pub fn threads_captured(&self) -> usize {
+        self.threads_captured
+    }
+
+    /// Number of captured threads that still had open spans when the report was produced.
+    /// Spans that are created on one thread and closed on another (e.g., with async runtimes) may cause this
+    /// count to be inaccurate.
+    pub fn threads_with_open_spans(&self) -> usize {
+        self.threads_with_open_spans
+    }
+
+    /// Number of spans that were created but not closed when the report was produced. The latencies of these
+    /// spans are not included in the report.
+    pub fn open_spans(&self) -> usize {
+        self.open_spans
+    }
+
+    /// Returns `true` if all spans created before the report was produced were closed, so no latencies are missing.
+    pub fn is_complete(&self) -> bool {
+        self.open_spans == 0
+    }
+}
+
+//==============
+// pub impl for Timing
+
+/// Number of log-scale bins per doubling of latency values used by [`Timing::is_bimodal`].
+const BIMODAL_BINS_PER_OCTAVE: f64 = 2.0;
+
+/// Minimum fraction of the total count that each mode's bin must hold for [`Timing::is_bimodal`].
+const BIMODAL_MIN_MODE_FRACTION: f64 = 0.1;
+
+/// Maximum ratio of the dip between two modes to the smaller mode for [`Timing::is_bimodal`].
+const BIMODAL_MAX_DIP_RATIO: f64 = 0.25;
+
+/// Minimum ratio of the maximum recorded value to the highest trackable value of a histogram for
+/// [`Timings::saturated_groups`].
+const SATURATION_MIN_RATIO: f64 = 0.5;
+
+impl Timing {
+    /// Constructs a [`Timing`] from a histogram of values in `unit`.
+    pub fn new(hist: Histogram<u64>, unit: TimeUnit) -> Self {
+        Self { hist, unit }
+    }
+
+    /// Returns the [`TimeUnit`] of the recorded values, i.e., the configured [`LatencyTraceCfg::time_unit`] of the
+    /// [`LatencyTrace`] that recorded them.
+    pub fn unit(&self) -> TimeUnit {
+        self.unit
+    }
+
+    /// Returns the histogram of the recorded values.
+    pub fn value(&self) -> &Histogram<u64> {
+        &self.hist
+    }
+
+    /// Consumes `self` and returns the histogram of the recorded values.
+    pub fn into_histogram(self) -> Histogram<u64> {
+        self.hist
+    }
+
+    /// Heuristically determines whether the recorded latencies have a bimodal distribution, e.g., cache hits and
+    /// cache misses recorded in the same span group. A bimodal span group is often a hint that the span grouper
+    /// should be refined to separate the two populations.
+    ///
+    /// The recorded values are binned on a logarithmic scale and the distribution is considered bimodal if there are
+    /// two bins, each holding at least 10% of the recorded values, separated by a dip whose count is at most 25% of
+    /// the smaller of the two bins.
+    ///
+    /// This is a simple heuristic, not a rigorous statistical test, so it can yield both false positives and
+    /// false negatives, especially for histograms with few recorded values.
+    pub fn is_bimodal(&self) -> bool {
+        let total = self.len();
+        if total == 0 {
+            return false;
+        }
+
+        let bin_of = |value: u64| ((value.max(1) as f64).log2() * BIMODAL_BINS_PER_OCTAVE) as usize;
+        let mut bins = vec![0_u64; bin_of(self.max()) + 1];
+        for v in self.iter_recorded() {
+            bins[bin_of(v.value_iterated_to())] += v.count_at_value();
+        }
+
+        let min_mode = (total as f64 * BIMODAL_MIN_MODE_FRACTION).ceil() as u64;
+        let modes: Vec<usize> = (0..bins.len()).filter(|&i| bins[i] >= min_mode).collect();
+
+        modes.iter().enumerate().any(|(k, &i)| {
+            modes[k + 1..].iter().any(|&j| {
+                let smaller_mode = bins[i].min(bins[j]);
+                let dip = bins[i..=j]
+                    .iter()
+                    .min()
+                    .expect("range is non-empty because i < j");
+                (*dip as f64) <= smaller_mode as f64 * BIMODAL_MAX_DIP_RATIO
+            })
+        })
+    }
+
+    /// Estimates the value at quantile `q` (clamped to `[0, 1]`) by linear interpolation within the histogram
+    /// bucket that contains the quantile, assuming the values recorded in the bucket are uniformly distributed
+    /// across the bucket's range of equivalent values. Returns `0.0` if no values were recorded.
+    ///
+    /// [`Histogram::value_at_quantile`] returns the highest value equivalent to the bucket that contains the
+    /// quantile, which causes stair-stepping when comparing quantiles at a granularity finer than the bucket
+    /// widths. This method yields a smoother estimate, but it is still only an estimate: the true value can be
+    /// anywhere in the bucket.
+    pub fn value_at_quantile_interpolated(&self, q: f64) -> f64 {
+        let total = self.len();
+        if total == 0 {
+            return 0.0;
+        }
+
+        let rank = q.clamp(0.0, 1.0) * total as f64;
+        let mut count_below = 0_u64;
+        for v in self.iter_recorded() {
+            let count = v.count_at_value();
+            if (count_below + count) as f64 >= rank {
+                let value = v.value_iterated_to();
+                let low = self.lowest_equivalent(value) as f64;
+                let high = self.highest_equivalent(value) as f64;
+                let fraction = ((rank - count_below as f64) / count as f64).max(0.0);
+                return low + fraction * (high - low);
+            }
+            count_below += count;
+        }
+
+        self.highest_equivalent(self.max()) as f64
+    }
+}
+
+/// Returns a copy of `timing` with all recorded values shifted by `offset`, clamped to the histogram's range.
+fn shift_timing(timing: &Timing, offset: i64) -> Timing {
+    let mut hist = Histogram::new_from(timing.value());
+    let (low, high) = (hist.low() as i64, hist.high() as i64);
+    for v in timing.iter_recorded() {
+        let value = (v.value_iterated_to() as i64 + offset).clamp(low, high);
+        hist.record_n(value as u64, v.count_at_value())
+            .expect("value is clamped to the histogram's range");
+    }
+    Timing::new(hist, timing.unit())
+}
+
+/// Number of significant digits of the latencies in [`Timings::to_stable_text`].
+const STABLE_TEXT_SIGFIG: i32 = 2;
+
+/// Rounds `value` to `sigfig` significant digits.
+fn round_to_sigfig(value: f64, sigfig: i32) -> u64 {
+    if value < 1.0 {
+        return value.round() as u64;
+    }
+    let magnitude = 10_f64.powi(value.log10().floor() as i32 + 1 - sigfig);
+    ((value / magnitude).round() * magnitude) as u64
+}
+
+/// [`Display`] rendering of [`Timings`] returned by [`Timings::display_summary`].
+struct TimingsSummary<'a> {
+    timings: &'a Timings,
+}
+
+impl Display for TimingsSummary<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // All the timings of a measurement have the same unit.
+        let u = self
+            .timings
+            .values()
+            .next()
+            .map(Timing::unit)
+            .unwrap_or_default();
+        let header = [
+            "name".to_owned(),
+            "count".to_owned(),
+            format!("mean({u})"),
+            format!("p50({u})"),
+            format!("p95({u})"),
+            format!("p99({u})"),
+            format!("max({u})"),
+        ];
+        let rows: Vec<[String; 7]> = self
+            .timings
+            .iter()
+            .map(|(sg, timing)| {
+                [
+                    sg.name().to_owned(),
+                    timing.len().to_string(),
+                    format!("{:.1}", timing.mean()),
+                    timing.value_at_percentile(50.0).to_string(),
+                    timing.value_at_percentile(95.0).to_string(),
+                    timing.value_at_percentile(99.0).to_string(),
+                    timing.max().to_string(),
+                ]
+            })
+            .collect();
+
+        let mut widths = header.clone().map(|h| h.chars().count());
+        for row in rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        for row in std::iter::once(&header).chain(rows.iter()) {
+            // The name is left-aligned and the numbers are right-aligned.
+            write!(f, "{:<w$}", row[0], w = widths[0])?;
+            for (cell, width) in row.iter().zip(widths.iter()).skip(1) {
+                write!(f, "  {:>w$}", cell, w = *width)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
 }
 
 //==============
@@ -229,19 +1393,19 @@ impl<K> TimingsView<K> {
     where
         G: Ord,
     {
-        let mut res: BTreeMap<G, Histogram<u64>> = BTreeMap::new();
+        let mut res: BTreeMap<G, Timing> = BTreeMap::new();
         for (k, v) in self.iter() {
             // Construct aggregation map.
             let g = f(k);
             let hist = match res.get_mut(&g) {
                 Some(hist) => hist,
                 None => {
-                    res.insert(g, Histogram::new_from(v));
+                    res.insert(g, Timing::new(Histogram::new_from(v.value()), v.unit()));
                     res.get_mut(&f(k))
                         .expect("key `g == f(k)` was just inserted in `res`")
                 }
             };
-            hist.add(v)
+            hist.add(v.value())
                 .expect("should not happen given histogram construction");
         }
         res.into()
@@ -267,13 +1431,38 @@ impl<K> TimingsView<K> {
         }
     }
 
-    /// Produces a map whose values are the [`SummaryStats`] of `self`'s histogram values.
+    /// Produces a map whose values are the [`SummaryStats`] of `self`'s histogram values, in their [`Timing::unit`].
     pub fn summary_stats(&self) -> Wrapper<BTreeMap<K, SummaryStats>>
     where
         K: Ord + Clone,
     {
         self.map_values(summary_stats)
     }
+
+    /// Produces a map whose values are the [`SummaryStats`] of `self`'s histogram values, in their [`Timing::unit`],
+    /// with the given `percentiles` (see [`summary_stats_with_percentiles`]).
+    pub fn summary_stats_with_percentiles(
+        &self,
+        percentiles: &[f64],
+    ) -> Wrapper<BTreeMap<K, SummaryStats>>
+    where
+        K: Ord + Clone,
+    {
+        self.map_values(|timing: &Timing| summary_stats_with_percentiles(timing, percentiles))
+    }
+
+    /// Produces a map whose values are the [`SummaryStatsMinCount`] of `self`'s histogram values, i.e., the
+    /// [`SummaryStats`] are omitted for histograms with fewer than `min_count` samples, leaving only their count and
+    /// mean. This prevents over-interpretation of the quantiles of sparse data.
+    pub fn summary_stats_min_count(
+        &self,
+        min_count: u64,
+    ) -> Wrapper<BTreeMap<K, SummaryStatsMinCount>>
+    where
+        K: Ord + Clone,
+    {
+        self.map_values(|timing: &Timing| SummaryStatsMinCount::new(timing, min_count))
+    }
 }
 
 //==============
@@ -307,6 +1496,601 @@ impl Timings {
         is_consistent
     }
 
+    /// Returns the *aggregates* (see [`Self::aggregator_is_consistent`]) of the aggregation function `f` whose span
+    /// groups have different callsites, keyed by *aggregate key*. The result is empty if and only if `f` is consistent.
+    pub fn aggregation_conflicts<G>(
+        &self,
+        f: impl Fn(&SpanGroup) -> G,
+    ) -> BTreeMap<G, Vec<SpanGroup>>
+    where
+        G: Ord,
+    {
+        let mut aggregates: BTreeMap<G, Vec<SpanGroup>> = BTreeMap::new();
+        for k in self.keys() {
+            aggregates.entry(f(k)).or_default().push(k.clone());
+        }
+        aggregates.retain(|_, sgs| sgs.iter().any(|sg| sg.code_line() != sgs[0].code_line()));
+        aggregates
+    }
+
+    /// Aggregates the timings of the span groups with the same [`SpanGroup::name`] (see [`TimingsView::aggregate`]).
+    ///
+    /// Spans with the same name at different callsites are aggregated together, so this aggregation may be
+    /// inconsistent; `self.aggregator_is_consistent(|sg| sg.name())` tells whether it is
+    /// (see [`Self::aggregator_is_consistent`]).
+    pub fn aggregate_by_name(&self) -> TimingsView<&'static str> {
+        self.aggregate(|sg| sg.name())
+    }
+
+    /// Aggregates the timings of the span groups with the same [`SpanGroup::code_line`], i.e., the same callsite
+    /// (see [`TimingsView::aggregate`]). This aggregation is always consistent (see
+    /// [`Self::aggregator_is_consistent`]).
+    pub fn aggregate_by_code_line(&self) -> TimingsView<Arc<str>> {
+        self.aggregate(|sg| sg.code_line.clone())
+    }
+
+    /// Aggregates the timings of the span groups with the same [`SpanGroup::depth`] (see
+    /// [`TimingsView::aggregate`]), e.g., to see how much time is spent across all the span groups at each nesting
+    /// level. The sum of each aggregate's recorded values is the total latency at that depth.
+    ///
+    /// Different callsites generally have the same depth, so this aggregation is generally inconsistent;
+    /// `self.aggregator_is_consistent(|sg| sg.depth())` tells whether it is (see [`Self::aggregator_is_consistent`]).
+    pub fn aggregate_by_depth(&self) -> TimingsView<usize> {
+        self.aggregate(|sg| sg.depth())
+    }
+
+    /// Returns an iterator over the [`SpanGroup`]s in `self`, in key order, without their timings.
+    pub fn span_groups(&self) -> impl Iterator<Item = &SpanGroup> {
+        self.keys()
+    }
+
+    /// Returns the number of [`SpanGroup`]s in `self`.
+    pub fn group_count(&self) -> usize {
+        self.len()
+    }
+
+    /// Retains only the span groups for which `pred` returns `true`, e.g., to drop uninteresting span groups before
+    /// printing a report.
+    ///
+    /// Only the span groups themselves are removed, so the children of a removed span group remain with a
+    /// [`SpanGroup::parent_id`] that is not in `self` (which [`Self::validate`] reports as
+    /// [`ConsistencyError::OrphanParent`] and the tree-based methods, e.g., [`Self::span_group_to_parent`], treat as
+    /// roots). Use [`Self::retain_subtrees`] to also remove their descendants.
+    pub fn retain_groups(&mut self, pred: impl Fn(&SpanGroup) -> bool) {
+        self.retain(|sg, _| pred(sg));
+    }
+
+    /// Retains only the span groups for which `pred` returns `true` and whose ancestors are all retained, i.e.,
+    /// removing a span group removes its whole subtree, so `self` remains consistent. Span groups whose parent was
+    /// already missing from `self` are retained if `pred` returns `true` for them.
+    pub fn retain_subtrees(&mut self, pred: impl Fn(&SpanGroup) -> bool) {
+        let mut by_depth: Vec<&SpanGroup> = self.keys().collect();
+        by_depth.sort_by_key(|sg| sg.depth());
+
+        // Parents have lower depths than their children, so they are visited first.
+        let mut removed: HashSet<&str> = HashSet::new();
+        for sg in by_depth {
+            let parent_removed = sg
+                .parent_id()
+                .is_some_and(|parent_id| removed.contains(parent_id));
+            if parent_removed || !pred(sg) {
+                removed.insert(sg.id());
+            }
+        }
+
+        let removed: HashSet<String> = removed.into_iter().map(str::to_owned).collect();
+        self.retain(|sg, _| !removed.contains(sg.id()));
+    }
+
+    /// Returns the span groups whose [`Timing`] is heuristically bimodal according to [`Timing::is_bimodal`].
+    ///
+    /// Such span groups likely mix distinct populations of spans (e.g., cache hits and misses) and may benefit from
+    /// a finer-grained span grouper.
+    pub fn bimodal_groups(&self) -> Vec<&SpanGroup> {
+        self.iter()
+            .filter(|(_, timing)| timing.is_bimodal())
+            .map(|(sg, _)| sg)
+            .collect()
+    }
+
+    /// Returns the span groups whose maximum recorded latency is at least half of the highest trackable value of
+    /// their histogram ([`Histogram::high`]), which starts as the [`LatencyTraceCfg::hist_high`] with which `self`
+    /// was collected. Such span groups indicate that `hist_high` is too low for them.
+    ///
+    /// Histograms are auto-resized when a recorded value exceeds their highest trackable value, so values are not
+    /// clamped. However, the reallocation of the histogram adds to the latencies being measured, inflating the tail
+    /// latencies of the span groups whose histograms were resized (see [`Report::resize_counts`]). A resized
+    /// histogram's highest trackable value becomes the top of the range that covers the recorded value, which is less
+    /// than twice that value, so the returned span groups include all of those, as well as span groups whose
+    /// latencies are within a factor of two of causing resizes.
+    pub fn saturated_groups(&self) -> Vec<&SpanGroup> {
+        self.iter()
+            .filter(|(_, timing)| {
+                !timing.is_empty()
+                    && timing.max() as f64 >= SATURATION_MIN_RATIO * timing.high() as f64
+            })
+            .map(|(sg, _)| sg)
+            .collect()
+    }
+
+    /// Returns the fraction of the instrumentation callsites in `self` that recorded latencies, i.e., the number of
+    /// distinct [`SpanGroup::code_line`]s with at least one non-empty [`Timing`] divided by the number of distinct
+    /// [`SpanGroup::code_line`]s. Returns `0.0` if `self` is empty.
+    ///
+    /// This helps gauge the coverage of the instrumentation by a test or workload. Note that the *tracing* framework
+    /// doesn't provide the set of all callsites in the code, only those that were reached, so callsites are
+    /// only known to `self` if spans were created for them. Callsites without recorded latencies arise, e.g., from
+    /// parent spans that were still open when latencies were probed with [`ProbedTrace::probe_latencies`] or from
+    /// [`LatencyTraceCfg::with_record_only_nth`].
+    pub fn coverage(&self) -> f64 {
+        let mut callsites: BTreeMap<&str, bool> = BTreeMap::new();
+        for (sg, timing) in self.iter() {
+            *callsites.entry(sg.code_line()).or_insert(false) |= !timing.is_empty();
+        }
+        if callsites.is_empty() {
+            return 0.0;
+        }
+        let recorded = callsites.values().filter(|recorded| **recorded).count();
+        recorded as f64 / callsites.len() as f64
+    }
+
+    /// Returns the callsites (identified by [`SpanGroup::code_line`]) whose span groups appear under more than one
+    /// parent span group, mapped from the callsite's span name to the callsite's span groups. Callsites with the same
+    /// span name are combined under the same key.
+    ///
+    /// As span groups are defined by callsite paths, a callsite reached through different parent paths yields
+    /// separate span groups with different parents, which splits the callsite's counts. This is a diagnostic to help
+    /// interpret such splits.
+    pub fn multi_parent_callsites(&self) -> BTreeMap<&'static str, Vec<SpanGroup>> {
+        let mut callsites: BTreeMap<(&'static str, &str), Vec<&SpanGroup>> = BTreeMap::new();
+        for sg in self.keys() {
+            callsites
+                .entry((sg.name(), sg.code_line()))
+                .or_default()
+                .push(sg);
+        }
+
+        let mut res: BTreeMap<&'static str, Vec<SpanGroup>> = BTreeMap::new();
+        for ((name, _), sgs) in callsites {
+            let mut parent_ids: Vec<Option<&str>> = sgs.iter().map(|sg| sg.parent_id()).collect();
+            parent_ids.sort();
+            parent_ids.dedup();
+            if parent_ids.len() > 1 {
+                res.entry(name)
+                    .or_default()
+                    .extend(sgs.into_iter().cloned());
+            }
+        }
+        res
+    }
+
+    /// Checks that the span groups in `self` are consistent with each other, returning all the
+    /// [`ConsistencyError`]s found otherwise. This is a debugging aid to detect post-processing bugs or data
+    /// corrupted by manipulation of the map, e.g., merging of [`Timings`] from different configurations.
+    ///
+    /// The checks are:
+    /// - [`SpanGroup::id`]s are unique.
+    /// - The [`SpanGroup::parent_id`] of each non-root span group is the ID of a span group in `self`.
+    /// - The [`SpanGroup::depth`] of each span group is 1 for root span groups and 1 more than its parent's depth
+    ///   otherwise. As a span group's callsite path is that of its parent extended by its callsite, this ensures
+    ///   that the depths are consistent with the callsite paths.
+    pub fn validate(&self) -> Result<(), Vec<ConsistencyError>> {
+        let mut errors = Vec::new();
+
+        let mut by_id: BTreeMap<&str, &SpanGroup> = BTreeMap::new();
+        for sg in self.keys() {
+            if by_id.insert(sg.id(), sg).is_some() {
+                errors.push(ConsistencyError::DuplicateId {
+                    id: sg.id().to_owned(),
+                });
+            }
+        }
+
+        for sg in self.keys() {
+            let expected = match sg.parent_id() {
+                None => 1,
+                Some(parent_id) => match by_id.get(parent_id) {
+                    Some(parent) => parent.depth() + 1,
+                    None => {
+                        errors.push(ConsistencyError::OrphanParent {
+                            id: sg.id().to_owned(),
+                            parent_id: parent_id.to_owned(),
+                        });
+                        continue;
+                    }
+                },
+            };
+            if sg.depth() != expected {
+                errors.push(ConsistencyError::WrongDepth {
+                    id: sg.id().to_owned(),
+                    depth: sg.depth(),
+                    expected,
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Splits `self` into the span trees of individual requests, mapped from the request ID. The span groups of a
+    /// request are those that have an `id_field` property (see [`SpanGroup::props`]), the request span groups, and
+    /// their descendants. Span groups that are not in the subtree of a request span group are omitted, and a
+    /// descendant that has its own `id_field` property belongs to the request with that ID. Request IDs are the
+    /// property values as recorded by the span grouper, e.g., quoted for string fields grouped with
+    /// [`group_by_given_fields`](crate::group_by_given_fields).
+    ///
+    /// This requires a span grouper that groups request spans by `id_field`, e.g.,
+    /// `group_by_given_fields(&["request_id"])`. Note that this yields a separate set of span groups, each with its
+    /// own histogram, for each request, so it is only suitable for a limited number of requests, e.g., when
+    /// tracing selected requests end-to-end.
+    pub fn per_request(&self, id_field: &str) -> BTreeMap<String, Timings> {
+        let by_id: BTreeMap<&str, &SpanGroup> = self.keys().map(|sg| (sg.id(), sg)).collect();
+        let request_id = |sg: &SpanGroup| {
+            sg.props()
+                .iter()
+                .find(|(name, _)| name == id_field)
+                .map(|(_, value)| value.clone())
+        };
+
+        let mut res: BTreeMap<String, Timings> = BTreeMap::new();
+        for (sg, timing) in self.iter() {
+            let mut curr = Some(sg);
+            while let Some(ancestor) = curr {
+                if let Some(id) = request_id(ancestor) {
+                    res.entry(id)
+                        .or_insert_with(|| BTreeMap::new().into())
+                        .insert(sg.clone(), timing.clone());
+                    break;
+                }
+                curr = ancestor
+                    .parent_id()
+                    .and_then(|parent_id| by_id.get(parent_id).copied());
+            }
+        }
+        res
+    }
+
+    /// Returns the maximum [`SpanGroup::depth`] of the span groups in `self`, or `0` if `self` is empty.
+    ///
+    /// Unexpectedly deep nesting may indicate instrumentation in a recursive code path.
+    pub fn max_depth(&self) -> usize {
+        self.keys().map(|sg| sg.depth()).max().unwrap_or(0)
+    }
+
+    /// Returns the span groups whose [`SpanGroup::depth`] is [`Self::max_depth`].
+    pub fn deepest_groups(&self) -> Vec<&SpanGroup> {
+        let max_depth = self.max_depth();
+        self.keys().filter(|sg| sg.depth() == max_depth).collect()
+    }
+
+    /// Returns an iterator over the `(quantile, value)` pairs of the latency distribution of `sg`, e.g., to plot its
+    /// CDF, or `None` if `sg` is not in `self`. The pairs are obtained with [`Histogram::iter_quantiles`], whose
+    /// `ticks_per_half_distance` argument determines how many pairs are reported for each halving of the distance to
+    /// the 100th percentile, so that the tail of the distribution has finer resolution.
+    pub fn quantile_curve(
+        &self,
+        sg: &SpanGroup,
+        ticks_per_half_distance: u32,
+    ) -> Option<impl Iterator<Item = (f64, u64)> + '_> {
+        let timing = self.get(sg)?;
+        let curve = timing
+            .iter_quantiles(ticks_per_half_distance)
+            .map(|v| (v.quantile_iterated_to(), v.value_iterated_to()));
+        Some(curve)
+    }
+
+    /// Renders the [`SummaryStats`] of `self` as CSV for spreadsheet analysis, with header
+    /// `name,id,parent_id,depth,code_line,props,count,min,max,mean,median,p95,p99` and one row per span group, in the
+    /// order of `self`. The props are rendered in a single column as `k1=v1;k2=v2`, so rows have the same columns
+    /// regardless of the span grouper, and the `parent_id` of root span groups is empty.
+    pub fn to_csv_summary(&self) -> String {
+        let mut csv = String::from(
+            "name,id,parent_id,depth,code_line,props,count,min,max,mean,median,p95,p99\n",
+        );
+        for (sg, timing) in self.iter() {
+            let stats = summary_stats(timing);
+            let props = sg
+                .props()
+                .iter()
+                .map(|(k, v)| format!("{k}={v}"))
+                .collect::<Vec<_>>()
+                .join(";");
+            writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{:.1},{},{},{}",
+                csv_field(sg.name()),
+                csv_field(sg.id()),
+                csv_field(sg.parent_id().unwrap_or("")),
+                sg.depth(),
+                csv_field(sg.code_line()),
+                csv_field(&props),
+                stats.count,
+                stats.min,
+                stats.max,
+                stats.mean,
+                stats.median,
+                stats.p95,
+                stats.p99,
+            )
+            .expect("writing to a String can't fail");
+        }
+        csv
+    }
+
+    /// Returns a [`Display`] rendering of `self` as a table with aligned columns for the name, count, mean, median
+    /// (p50), 95th and 99th percentiles, and maximum of each span group, in the order of `self`, e.g.:
+    ///
+    /// ```text
+    /// name  count  mean(µs)  p50(µs)  p95(µs)  p99(µs)  max(µs)
+    /// f         1   21452.0    21455    21455    21455    21455
+    /// g        10    1050.3     1050     1102     1102     1102
+    /// ```
+    ///
+    /// The columns are labeled with the [`Timing::unit`] of the latencies, i.e., the configured
+    /// [`LatencyTraceCfg::time_unit`].
+    pub fn display_summary(&self) -> impl Display + '_ {
+        TimingsSummary { timings: self }
+    }
+
+    /// Serializes the histogram of each span group in the standard HdrHistogram compressed V2 encoding, in base64, as
+    /// produced by `encodeIntoCompressedByteBuffer` followed by base64 encoding in the Java implementation, keyed by
+    /// [`SpanGroup::id`]. The serialized histograms can be loaded by HdrHistogram tooling in other languages and
+    /// restored with [`Self::from_hdr_serialized`].
+    pub fn to_hdr_serialized(&self) -> BTreeMap<String, String> {
+        let mut serializer = V2DeflateSerializer::new();
+        self.iter()
+            .map(|(sg, timing)| {
+                let mut bytes = Vec::new();
+                serializer
+                    .serialize(timing, &mut bytes)
+                    .expect("serialization of a valid histogram to a Vec can't fail");
+                (sg.id().to_owned(), Base64::encode_string(&bytes))
+            })
+            .collect()
+    }
+
+    /// Reconstructs a [`Timings`] from `span_groups` and the histograms serialized by [`Self::to_hdr_serialized`]
+    /// (or by other HdrHistogram tooling), keyed by [`SpanGroup::id`]. The span groups are typically those of the
+    /// serialized [`Timings`] (see [`Self::span_groups`]). Serialized histograms whose keys are not the id of any of
+    /// `span_groups` are ignored. `unit` is the [`TimeUnit`] of the serialized histograms' values, which is not
+    /// included in the serialization.
+    ///
+    /// # Errors
+    /// - [`HdrDeserializationError::MissingHistogram`] if there is no serialized histogram for a span group.
+    /// - [`HdrDeserializationError::InvalidBase64`] or [`HdrDeserializationError::InvalidHistogram`] if the serialized
+    ///   histogram of a span group can't be decoded.
+    pub fn from_hdr_serialized(
+        span_groups: impl IntoIterator<Item = SpanGroup>,
+        serialized: &BTreeMap<String, String>,
+        unit: TimeUnit,
+    ) -> Result<Timings, HdrDeserializationError> {
+        let mut deserializer = Deserializer::new();
+        let mut timings = BTreeMap::new();
+        for sg in span_groups {
+            let id = sg.id().to_owned();
+            let encoded = serialized
+                .get(&id)
+                .ok_or_else(|| HdrDeserializationError::MissingHistogram { id: id.clone() })?;
+            let bytes = Base64::decode_vec(encoded)
+                .map_err(|_| HdrDeserializationError::InvalidBase64 { id: id.clone() })?;
+            let mut hist: Histogram<u64> = deserializer
+                .deserialize(&mut bytes.as_slice())
+                .map_err(|err| HdrDeserializationError::InvalidHistogram {
+                    id,
+                    message: format!("{err:?}"),
+                })?;
+            // Same as the histograms constructed during collection.
+            hist.auto(true);
+            timings.insert(sg, Timing::new(hist, unit));
+        }
+        Ok(timings.into())
+    }
+
+    /// Compares the [`SummaryStats`] of the span groups of `self` with those of `baseline`, e.g., to fail a CI job when
+    /// the p99 of a span group regresses beyond a threshold. Span groups are matched by [`SpanGroup::id`], so the
+    /// comparison doesn't depend on the order of the span groups. The result contains every span group of `self` and
+    /// `baseline`, the latter only if it is [`SpanGroupDiff::Removed`], and both should be produced with the same
+    /// configuration.
+    pub fn diff(&self, baseline: &Timings) -> BTreeMap<SpanGroup, SpanGroupDiff> {
+        let baseline_by_id: BTreeMap<&str, (&SpanGroup, &Timing)> = baseline
+            .iter()
+            .map(|(sg, timing)| (sg.id(), (sg, timing)))
+            .collect();
+        let ids: HashSet<&str> = self.keys().map(|sg| sg.id()).collect();
+
+        let mut diff: BTreeMap<SpanGroup, SpanGroupDiff> = self
+            .iter()
+            .map(|(sg, timing)| {
+                let stats = summary_stats(timing);
+                let sg_diff = match baseline_by_id.get(sg.id()) {
+                    Some((_, baseline_timing)) => SpanGroupDiff::Changed(Box::new(
+                        stats.diff(&summary_stats(baseline_timing)),
+                    )),
+                    None => SpanGroupDiff::Added(stats),
+                };
+                (sg.clone(), sg_diff)
+            })
+            .collect();
+        for (id, (sg, timing)) in baseline_by_id {
+            if !ids.contains(id) {
+                diff.insert(sg.clone(), SpanGroupDiff::Removed(summary_stats(timing)));
+            }
+        }
+        diff
+    }
+
+    /// Merges the given [`Timings`], e.g., from the shards of a load test run in separate processes, into a single
+    /// [`Timings`] in which the histograms of the span groups with the same [`SpanGroup::id`] are combined.
+    ///
+    /// Span groups are matched by their ids, which are stable hashes of the span groups' names, code lines, props,
+    /// and parent ids, rather than by full struct equality, so span groups with the same id are identical by
+    /// construction. Span groups whose ids differ only when truncated to fewer bytes (see
+    /// [`LatencyTraceCfg::with_id_bytes`]) are not matched, so all the [`Timings`] should be produced with the same
+    /// configuration.
+    ///
+    /// # Panics
+    /// - If two span groups with the same id differ, which can only happen due to an id hash collision.
+    pub fn merge_many(iter: impl IntoIterator<Item = Timings>) -> Timings {
+        let mut merged: BTreeMap<String, (SpanGroup, Timing)> = BTreeMap::new();
+        for timings in iter {
+            for (sg, timing) in timings.0 {
+                match merged.get_mut(sg.id()) {
+                    Some((merged_sg, merged_timing)) => {
+                        assert_eq!(
+                            *merged_sg, sg,
+                            "span groups with the same id must be identical"
+                        );
+                        merged_timing
+                            .add(timing.value())
+                            .expect("should not happen given histogram construction");
+                    }
+                    None => {
+                        merged.insert(sg.id().to_owned(), (sg, timing));
+                    }
+                }
+            }
+        }
+        merged.into_values().collect::<BTreeMap<_, _>>().into()
+    }
+
+    /// Returns a copy of `self` with the recorded values of all span groups shifted by the same constant, chosen so
+    /// that the median of the span group with ID `anchor_id` matches the median of the span group with the same ID in
+    /// `reference`. This enables the relative comparison of runs measured in heterogeneous environments (e.g., on
+    /// faster and slower machines), as regressions in other span groups are not obscured by the environment offset.
+    ///
+    /// Returns `None` if `self` or `reference` don't have a non-empty [`Timing`] for the anchor span group.
+    ///
+    /// This relies on strong assumptions that should be validated for the use case at hand:
+    /// - the difference between the environments is a constant additive offset that affects all span groups equally,
+    ///   whereas real environment differences are often proportional and differ across span groups (e.g., CPU-bound
+    ///   vs. IO-bound code);
+    /// - the anchor span group itself did not regress between the runs.
+    ///
+    /// Shifted values are clamped to the range supported by the histograms, so the distribution tails can be
+    /// distorted by large shifts.
+    pub fn align_to_median(&self, reference: &Timings, anchor_id: &str) -> Option<Timings> {
+        let anchor_median = |timings: &Timings| {
+            timings
+                .iter()
+                .find(|(sg, timing)| sg.id() == anchor_id && !timing.is_empty())
+                .map(|(_, timing)| timing.value_at_quantile(0.5) as i64)
+        };
+        let offset = anchor_median(reference)? - anchor_median(self)?;
+        Some(self.map_values(|timing| shift_timing(timing, offset)))
+    }
+
+    /// Returns an approximation of the latencies of the span group with ID `group_id` excluding the time spent in
+    /// its child span groups named `child_name`, e.g., "my time excluding the database" for a child named `"db"`.
+    /// Returns `None` if there is no span group with ID `group_id`.
+    ///
+    /// The approximation subtracts the mean contribution of the named children per span of the span group, i.e.,
+    /// the total latency recorded by the named child span groups divided by the number of spans in the span group,
+    /// from each recorded value. The mean of the result is therefore accurate (unless values are clamped at the low
+    /// end of the histogram range), but the shape of the distribution is that of the span group including the
+    /// children, as the actual time spent in the children by each individual span is not known.
+    pub fn time_excluding_child(&self, group_id: &str, child_name: &str) -> Option<Timing> {
+        let (_, timing) = self.iter().find(|(sg, _)| sg.id() == group_id)?;
+        if timing.is_empty() {
+            return Some(timing.clone());
+        }
+
+        let child_total: f64 = self
+            .iter()
+            .filter(|(sg, _)| sg.parent_id() == Some(group_id) && sg.name() == child_name)
+            .map(|(_, child_timing)| child_timing.mean() * child_timing.len() as f64)
+            .sum();
+        let contribution = child_total / timing.len() as f64;
+        Some(shift_timing(timing, -(contribution.round() as i64)))
+    }
+
+    /// Returns the value at percentile `p` (in the range `0.0..=100.0`) of each span group, see
+    /// [`Histogram::value_at_percentile`].
+    pub fn percentile(&self, p: f64) -> BTreeMap<SpanGroup, u64> {
+        self.iter()
+            .map(|(sg, timing)| (sg.clone(), timing.value_at_percentile(p)))
+            .collect()
+    }
+
+    /// Returns the values at each of the percentiles `ps` (in the range `0.0..=100.0`) of each span group, in the
+    /// order of `ps`.
+    pub fn percentiles(&self, ps: &[f64]) -> BTreeMap<SpanGroup, Vec<u64>> {
+        self.iter()
+            .map(|(sg, timing)| {
+                let values = ps.iter().map(|p| timing.value_at_percentile(*p)).collect();
+                (sg.clone(), values)
+            })
+            .collect()
+    }
+
+    /// Returns the approximate mean self time, i.e., exclusive latency, of each span group: its mean latency minus the
+    /// mean time spent in its child span groups per span of the span group. The time spent in a child span group per
+    /// parent span is the total latency recorded by the child divided by the number of spans of the parent, so children
+    /// that run several times per parent span are accounted for.
+    ///
+    /// This is an approximation based on aggregate means, as histograms don't record which child spans belong to which
+    /// parent span. Children that run concurrently with each other (e.g., joined futures or spawned threads) overlap in
+    /// time, so their total can exceed the parent's latency; the self time is then clamped at `0.0`. Span groups with
+    /// no recorded latencies have a self time of `0.0`.
+    pub fn self_time(&self) -> BTreeMap<SpanGroup, f64> {
+        let mut child_totals: BTreeMap<&str, f64> = BTreeMap::new();
+        for (sg, timing) in self.iter() {
+            if let Some(parent_id) = sg.parent_id() {
+                *child_totals.entry(parent_id).or_default() += timing.mean() * timing.len() as f64;
+            }
+        }
+
+        self.iter()
+            .map(|(sg, timing)| {
+                let self_time = if timing.is_empty() {
+                    0.0
+                } else {
+                    let child_total = child_totals.get(sg.id()).copied().unwrap_or_default();
+                    (timing.mean() - child_total / timing.len() as f64).max(0.0)
+                };
+                (sg.clone(), self_time)
+            })
+            .collect()
+    }
+
+    /// Renders `self` as compact text intended for golden files checked into version control, with one line per span
+    /// group, sorted by [`SpanGroup::id`]. Each line has the span group ID, the count, the mean, median, 90th and
+    /// 99th percentiles, and maximum latency, and the span group name and props, e.g.:
+    ///
+    /// ```text
+    /// yGxBNzxsJsI= count=      10 mean=    1200 p50=    1200 p90=    1300 p99=    1300 max=    1300 f[("foo", "1")]
+    /// ```
+    ///
+    /// Latencies are rounded to 2 significant digits and formatted with a fixed width, so trivial run-to-run jitter
+    /// doesn't change the lines and diffs highlight real changes.
+    pub fn to_stable_text(&self) -> String {
+        let mut span_groups: Vec<(&SpanGroup, &Timing)> = self.iter().collect();
+        span_groups.sort_by(|(sg1, _), (sg2, _)| sg1.id().cmp(sg2.id()));
+
+        let mut text = String::new();
+        for (sg, timing) in span_groups {
+            let r = |value: f64| round_to_sigfig(value, STABLE_TEXT_SIGFIG);
+            writeln!(
+                text,
+                "{} count={:>8} mean={:>8} p50={:>8} p90={:>8} p99={:>8} max={:>8} {}{:?}",
+                sg.id(),
+                timing.len(),
+                r(timing.mean()),
+                r(timing.value_at_quantile(0.5) as f64),
+                r(timing.value_at_quantile(0.9) as f64),
+                r(timing.value_at_quantile(0.99) as f64),
+                r(timing.max() as f64),
+                sg.name(),
+                sg.props(),
+            )
+            .expect("writing to a String can't fail");
+        }
+        text
+    }
+
     /// Returns a map from span group ID to [`SpanGroup`].
     fn id_to_span_group(&self) -> BTreeMap<String, SpanGroup> {
         self.keys()
@@ -314,19 +2098,132 @@ impl Timings {
             .collect()
     }
 
-    /// Returns a map that associates each [`SpanGroup`] to its parent.
+    /// Returns a map that associates each [`SpanGroup`] to its parent. Span groups whose parent is not in `self`,
+    /// e.g., after [`Self::retain_groups`], are mapped to `None`, like roots.
     pub fn span_group_to_parent(&self) -> BTreeMap<SpanGroup, Option<SpanGroup>> {
         let id_to_sg = self.id_to_span_group();
         self.keys()
             .map(|sg| {
-                let parent = sg.parent_id().map(|pid| {
-                    id_to_sg
-                        .get(pid)
-                        .expect("`id_to_sg` must have key `pid` by construction")
-                        .clone()
-                });
+                let parent = sg.parent_id().and_then(|pid| id_to_sg.get(pid)).cloned();
                 (sg.clone(), parent)
             })
             .collect()
     }
+
+    /// Returns the ancestors of `sg` in `self`, from its parent up to the root of its tree. The result is empty if
+    /// `sg` is a root. If an ancestor is not in `self`, e.g., after [`Self::retain_groups`], the result stops before
+    /// it, consistently with [`Self::iter_topological`], which treats the span groups whose parent is not in `self`
+    /// as roots.
+    pub fn ancestors(&self, sg: &SpanGroup) -> Vec<SpanGroup> {
+        let id_to_sg = self.id_to_span_group();
+        let mut ancestors = Vec::new();
+        let mut parent_id = sg.parent_id();
+        while let Some(parent) = parent_id.and_then(|pid| id_to_sg.get(pid)) {
+            ancestors.push(parent.clone());
+            parent_id = parent.parent_id();
+        }
+        ancestors
+    }
+
+    /// Returns the entries of `self` in the depth-first order of the forest of span groups (see
+    /// [`Self::span_group_to_parent`]), so that each span group comes after its parent and before its siblings'
+    /// subtrees, e.g., for rendering. Roots and siblings are ordered by [`SpanGroup::id`]. Span groups whose parent is
+    /// not in `self` are treated as roots.
+    pub fn iter_topological(&self) -> impl Iterator<Item = (&SpanGroup, &Timing)> {
+        let ids: HashSet<&str> = self.keys().map(|sg| sg.id()).collect();
+        let mut children: BTreeMap<Option<&str>, Vec<(&SpanGroup, &Timing)>> = BTreeMap::new();
+        for (sg, timing) in self.iter() {
+            let parent_id = sg.parent_id().filter(|pid| ids.contains(pid));
+            children.entry(parent_id).or_default().push((sg, timing));
+        }
+        // Siblings are reversed so that they are popped from the stack in order of id.
+        for siblings in children.values_mut() {
+            siblings.sort_by(|(sg1, _), (sg2, _)| sg2.id().cmp(sg1.id()));
+        }
+
+        let mut ordered = Vec::with_capacity(self.len());
+        let mut stack = children.remove(&None).unwrap_or_default();
+        while let Some((sg, timing)) = stack.pop() {
+            ordered.push((sg, timing));
+            if let Some(sg_children) = children.remove(&Some(sg.id())) {
+                stack.extend(sg_children);
+            }
+        }
+        ordered.into_iter()
+    }
+
+    /// Returns the span groups in `self` whose parent is `sg`, in the order of `self`.
+    pub fn children(&self, sg: &SpanGroup) -> Vec<SpanGroup> {
+        self.keys()
+            .filter(|k| k.parent_id() == Some(sg.id()))
+            .cloned()
+            .collect()
+    }
+
+    /// Renders `self` as an indented tree, using box-drawing characters, in which each span group is a child of its
+    /// parent and is labeled by `f`, e.g., with `|sg, timing| format!("{} mean={:.0}", sg.name(), timing.mean())`:
+    ///
+    /// ```text
+    /// root mean=2150
+    /// ├── a mean=1020
+    /// │   └── aa mean=510
+    /// └── b mean=1100
+    /// ```
+    ///
+    /// The roots are the span groups whose [`SpanGroup::parent_id`] is `None` or whose parent is not in `self`, e.g.,
+    /// after [`Self::retain_groups`], and siblings are in the order of `self`.
+    pub fn format_tree(&self, f: impl Fn(&SpanGroup, &Timing) -> String) -> String {
+        let id_to_sg = self.id_to_span_group();
+        let mut children: BTreeMap<&SpanGroup, Vec<&SpanGroup>> = BTreeMap::new();
+        let mut roots: Vec<&SpanGroup> = Vec::new();
+        for sg in self.keys() {
+            match sg.parent_id().and_then(|pid| id_to_sg.get(pid)) {
+                Some(parent) => children.entry(parent).or_default().push(sg),
+                None => roots.push(sg),
+            }
+        }
+
+        let mut tree = String::new();
+        for root in roots {
+            self.format_subtree(root, &children, "", "", &f, &mut tree);
+        }
+        tree
+    }
+
+    /// Appends the lines of the subtree of `sg` to `tree`, prefixing the line of `sg` with `prefix` and the lines of
+    /// its descendants with `child_prefix`.
+    fn format_subtree(
+        &self,
+        sg: &SpanGroup,
+        children: &BTreeMap<&SpanGroup, Vec<&SpanGroup>>,
+        prefix: &str,
+        child_prefix: &str,
+        f: &impl Fn(&SpanGroup, &Timing) -> String,
+        tree: &mut String,
+    ) {
+        let timing = self
+            .get(sg)
+            .expect("span group must be in `self` by construction");
+        writeln!(tree, "{prefix}{}", f(sg, timing)).expect("writing to a String can't fail");
+
+        let Some(sg_children) = children.get(sg) else {
+            return;
+        };
+        let last = sg_children.len() - 1;
+        for (i, child) in sg_children.iter().enumerate() {
+            let (branch, continuation) = if i == last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            self.format_subtree(
+                child,
+                children,
+                &format!("{child_prefix}{branch}"),
+                &format!("{child_prefix}{continuation}"),
+                f,
+                tree,
+            );
+        }
+    }
 }
@@ -0,0 +1,33 @@
+//! Publicly exported `async-std`-related methods of [`LatencyTrace`].
+//! Present only when the **"async-std"** feature flag is enabled.
+
+use crate::{
+    executor::AsyncStdExecutor, lt_refine_g::Timings, lt_report_g::ActivationError,
+    probed_trace::ProbedTrace, LatencyTrace,
+};
+use std::future::Future;
+
+impl LatencyTrace {
+    /// Executes the instrumented async function `f`, running on the `async-std` runtime; after `f` completes,
+    /// returns the observed latencies.
+    /// Present only when the **"async-std"** feature flag is enabled.
+    pub fn measure_latencies_async_std<F>(&self, f: impl FnOnce() -> F) -> Timings
+    where
+        F: Future<Output = ()> + Send,
+    {
+        self.0.measure_latencies_async_std(f)
+    }
+
+    /// Executes the instrumented async function `f`, running on the `async-std` runtime; returns a [`ProbedTrace`]
+    /// that allows partial latencies to be reported before `f` completes.
+    /// Present only when the **"async-std"** feature flag is enabled.
+    pub fn measure_latencies_probed_async_std<F>(
+        self,
+        f: impl FnOnce() -> F + Send + 'static,
+    ) -> Result<ProbedTrace, ActivationError>
+    where
+        F: Future<Output = ()> + Send,
+    {
+        self.measure_latencies_probed_with(AsyncStdExecutor, f)
+    }
+}
@@ -0,0 +1,427 @@
+//! Publicly exported `serde`-related methods of [`LatencyTraceCfg`] and [`Timings`], and `Serialize` implementation
+//! of [`SpanGroup`].
+//! Present only when the **"serde"** feature flag is enabled.
+
+use crate::{
+    default_span_grouper, group_by_all_fields, module_level_grouper,
+    span_groupers::group_by_given_owned_fields, LatencyTraceCfg, Offsets, SpanGroup, TimeUnit,
+    Timing, Timings,
+};
+use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{Debug, Display},
+    io::Read,
+};
+use tracing::Level;
+
+/// Error returned by [`LatencyTraceCfg::from_reader`].
+#[derive(Debug)]
+pub enum ConfigLoadError {
+    /// The input could not be read or is not a valid configuration.
+    ParseError(serde_json::Error),
+}
+
+impl Display for ConfigLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl Error for ConfigLoadError {}
+
+impl From<serde_json::Error> for ConfigLoadError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::ParseError(value)
+    }
+}
+
+/// Configuration presets that can be selected in a configuration file.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PresetSpec {
+    Default,
+    Server,
+}
+
+/// Built-in span groupers that can be selected in a configuration file.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+enum SpanGrouperSpec {
+    Default,
+    AllFields,
+    GivenFields { fields: Vec<String> },
+    ModuleLevel { depth: usize },
+}
+
+/// Time units that can be selected in a configuration file.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TimeUnitSpec {
+    Nanos,
+    Micros,
+    Millis,
+}
+
+/// Span levels that can be selected in a configuration file.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum LevelSpec {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// Serialized form of [`LatencyTraceCfg`]. Settings that are absent are taken from the preset.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LatencyTraceCfgSpec {
+    preset: Option<PresetSpec>,
+    time_unit: Option<TimeUnitSpec>,
+    hist_low: Option<u64>,
+    hist_high: Option<u64>,
+    hist_sigfig: Option<u8>,
+    auto_resize: Option<bool>,
+    source_root: Option<String>,
+    separate_panicked: Option<bool>,
+    record_poll_counts: Option<bool>,
+    record_only_nth: Option<u64>,
+    drop_root: Option<bool>,
+    id_bytes: Option<usize>,
+    record_offsets: Option<bool>,
+    record_active_time: Option<bool>,
+    min_level: Option<LevelSpec>,
+    /// `None` if absent and `Some(None)` if `null`, which removes the cap of the preset.
+    #[serde(default, deserialize_with = "deserialize_present")]
+    max_groups: Option<Option<usize>>,
+    span_grouper: Option<SpanGrouperSpec>,
+}
+
+/// Deserializes a setting that is present, possibly as `null`, so that it can be distinguished from an absent one.
+fn deserialize_present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+impl LatencyTraceCfg {
+    /// Loads a [`LatencyTraceCfg`] from a JSON configuration, e.g., a configuration file, allowing measurement
+    /// settings to be tuned without recompilation.
+    /// Present only when the **"serde"** feature flag is enabled.
+    ///
+    /// All settings are optional. Settings that are absent are taken from the selected `preset`
+    /// (`"default"` for [`Self::default`] or `"server"` for [`Self::server_preset`]), which is itself `"default"`
+    /// if absent. Example:
+    ///
+    /// ```json
+    /// {
+    ///   "preset": "server",
+    ///   "time_unit": "micros",
+    ///   "hist_high": 60000000,
+    ///   "hist_sigfig": 3,
+    ///   "source_root": "/home/me/my_project",
+    ///   "separate_panicked": false,
+    ///   "record_poll_counts": true,
+    ///   "max_groups": 500,
+    ///   "span_grouper": { "kind": "given_fields", "fields": ["endpoint", "status"] }
+    /// }
+    /// ```
+    ///
+    /// Span groupers are closures and can't be deserialized, so only the built-in span groupers can be selected
+    /// by `kind`: `"default"` for [`default_span_grouper`], `"all_fields"` for [`group_by_all_fields`],
+    /// `"given_fields"` for [`group_by_given_fields`](crate::group_by_given_fields) with the given `fields`, and
+    /// `"module_level"` for [`module_level_grouper`] with the given `depth`.
+    /// A custom span grouper can be set on the loaded configuration with [`Self::with_span_grouper`].
+    ///
+    /// The `time_unit` (`"nanos"`, `"micros"`, or `"millis"`, see [`Self::with_time_unit`]) is applied before
+    /// `hist_low` and `hist_high`, so they are in the configured unit.
+    ///
+    /// `max_groups` (see [`Self::with_max_groups`]) can be `null` to remove the cap of the `"server"` preset.
+    ///
+    /// # Errors
+    /// - [`ConfigLoadError::ParseError`] if the input can't be read or is not a valid configuration, e.g., it has
+    ///   unknown settings, presets, or span grouper kinds.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, ConfigLoadError> {
+        let spec: LatencyTraceCfgSpec = serde_json::from_reader(reader)?;
+
+        let mut cfg = match spec.preset {
+            None | Some(PresetSpec::Default) => LatencyTraceCfg::default(),
+            Some(PresetSpec::Server) => LatencyTraceCfg::server_preset(),
+        };
+        if let Some(time_unit) = spec.time_unit {
+            cfg = cfg.with_time_unit(match time_unit {
+                TimeUnitSpec::Nanos => TimeUnit::Nanos,
+                TimeUnitSpec::Micros => TimeUnit::Micros,
+                TimeUnitSpec::Millis => TimeUnit::Millis,
+            });
+        }
+        if let Some(hist_low) = spec.hist_low {
+            cfg = cfg.with_hist_low(hist_low);
+        }
+        if let Some(hist_high) = spec.hist_high {
+            cfg = cfg.with_hist_high(hist_high);
+        }
+        if let Some(hist_sigfig) = spec.hist_sigfig {
+            cfg = cfg.with_hist_sigfig(hist_sigfig);
+        }
+        if let Some(auto_resize) = spec.auto_resize {
+            cfg = cfg.with_auto_resize(auto_resize);
+        }
+        if let Some(source_root) = spec.source_root {
+            cfg = cfg.with_source_root(source_root);
+        }
+        if let Some(separate_panicked) = spec.separate_panicked {
+            cfg = cfg.with_separate_panicked(separate_panicked);
+        }
+        if let Some(record_poll_counts) = spec.record_poll_counts {
+            cfg = cfg.with_record_poll_counts(record_poll_counts);
+        }
+        if let Some(n) = spec.record_only_nth {
+            cfg = cfg.with_record_only_nth(n);
+        }
+        if let Some(drop_root) = spec.drop_root {
+            cfg = cfg.with_drop_root(drop_root);
+        }
+        if let Some(id_bytes) = spec.id_bytes {
+            cfg = cfg.with_id_bytes(id_bytes);
+        }
+        if let Some(record_offsets) = spec.record_offsets {
+            cfg = cfg.with_record_offsets(record_offsets);
+        }
+        if let Some(record_active_time) = spec.record_active_time {
+            cfg = cfg.with_record_active_time(record_active_time);
+        }
+        if let Some(max_groups) = spec.max_groups {
+            cfg = cfg.with_max_groups(max_groups);
+        }
+        if let Some(min_level) = spec.min_level {
+            cfg = cfg.with_min_level(match min_level {
+                LevelSpec::Trace => Level::TRACE,
+                LevelSpec::Debug => Level::DEBUG,
+                LevelSpec::Info => Level::INFO,
+                LevelSpec::Warn => Level::WARN,
+                LevelSpec::Error => Level::ERROR,
+            });
+        }
+        cfg = match spec.span_grouper {
+            None => cfg,
+            Some(SpanGrouperSpec::Default) => cfg.with_span_grouper(default_span_grouper),
+            Some(SpanGrouperSpec::AllFields) => cfg.with_span_grouper(group_by_all_fields),
+            Some(SpanGrouperSpec::GivenFields { fields }) => {
+                cfg.with_span_grouper(group_by_given_owned_fields(fields))
+            }
+            Some(SpanGrouperSpec::ModuleLevel { depth }) => {
+                cfg.with_span_grouper(module_level_grouper(depth))
+            }
+        };
+
+        Ok(cfg)
+    }
+}
+
+/// Percentiles included in the output of [`Timings::to_json`].
+pub const DEFAULT_JSON_PERCENTILES: [f64; 6] = [25.0, 50.0, 75.0, 90.0, 95.0, 99.0];
+
+/// Version of the structure of the JSON produced by [`Timings::to_json`], emitted in its `schema_version` field.
+/// It is incremented on breaking changes to the structure, e.g., removed or renamed fields.
+pub const JSON_SCHEMA_VERSION: u64 = 1;
+
+/// JSON Schema of the output of [`Timings::to_json`], for version [`JSON_SCHEMA_VERSION`].
+const JSON_SCHEMA: &str = r#"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "title": "latency_trace timings",
+  "type": "object",
+  "required": ["schema_version", "timings"],
+  "additionalProperties": false,
+  "properties": {
+    "schema_version": { "const": 1 },
+    "timings": {
+      "type": "array",
+      "items": {
+        "type": "object",
+        "required": ["span_group", "timing"],
+        "additionalProperties": false,
+        "properties": {
+          "span_group": {
+            "type": "object",
+            "required": ["name", "id", "code_line", "props", "parent_id", "depth"],
+            "additionalProperties": false,
+            "properties": {
+              "name": { "type": "string" },
+              "id": { "type": "string" },
+              "code_line": { "type": "string" },
+              "props": {
+                "type": "array",
+                "items": {
+                  "type": "array",
+                  "prefixItems": [{ "type": "string" }, { "type": "string" }],
+                  "minItems": 2,
+                  "maxItems": 2
+                }
+              },
+              "parent_id": { "type": ["string", "null"] },
+              "depth": { "type": "integer", "minimum": 1 }
+            }
+          },
+          "timing": {
+            "type": "object",
+            "required": ["count", "min", "max", "mean", "stdev", "percentiles"],
+            "additionalProperties": false,
+            "properties": {
+              "count": { "type": "integer", "minimum": 0 },
+              "min": { "type": "integer", "minimum": 0 },
+              "max": { "type": "integer", "minimum": 0 },
+              "mean": { "type": "number" },
+              "stdev": { "type": "number" },
+              "percentiles": {
+                "type": "object",
+                "propertyNames": { "pattern": "^p[0-9]+(\\.[0-9]+)?$" },
+                "additionalProperties": { "type": "integer", "minimum": 0 }
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+/// Returns a JSON Schema string describing the output of [`Timings::to_json`], e.g., to validate it in downstream
+/// tooling.
+/// Present only when the **"serde"** feature flag is enabled.
+pub fn json_schema() -> &'static str {
+    JSON_SCHEMA
+}
+
+impl Serialize for SpanGroup {
+    /// Serializes the span group's `name`, `id`, `code_line`, `props` (as `[name, value]` pairs), `parent_id`, and
+    /// `depth`.
+    /// Present only when the **"serde"** feature flag is enabled.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("SpanGroup", 6)?;
+        state.serialize_field("name", self.name())?;
+        state.serialize_field("id", self.id())?;
+        state.serialize_field("code_line", self.code_line())?;
+        state.serialize_field("props", self.props())?;
+        state.serialize_field("parent_id", &self.parent_id())?;
+        state.serialize_field("depth", &self.depth())?;
+        state.end()
+    }
+}
+
+/// Serializable summary of a [`Timing`], used by [`Timings::to_json`].
+#[derive(Serialize)]
+struct TimingSummary {
+    count: u64,
+    min: u64,
+    max: u64,
+    mean: f64,
+    stdev: f64,
+    percentiles: BTreeMap<String, u64>,
+}
+
+impl TimingSummary {
+    fn new(timing: &Timing, percentiles: &[f64]) -> Self {
+        Self {
+            count: timing.len(),
+            min: timing.min(),
+            max: timing.max(),
+            mean: timing.mean(),
+            stdev: timing.stdev(),
+            percentiles: percentiles
+                .iter()
+                .map(|p| (format!("p{p}"), timing.value_at_percentile(*p)))
+                .collect(),
+        }
+    }
+}
+
+/// Entry of the JSON produced by [`Timings::to_json`].
+#[derive(Serialize)]
+struct TimingsEntry<'a> {
+    span_group: &'a SpanGroup,
+    timing: TimingSummary,
+}
+
+/// JSON produced by [`Timings::to_json`].
+#[derive(Serialize)]
+struct TimingsJson<'a> {
+    schema_version: u64,
+    timings: Vec<TimingsEntry<'a>>,
+}
+
+/// Entry of the waterfall produced by [`Timings::to_waterfall`].
+#[derive(Serialize)]
+struct WaterfallEntry<'a> {
+    id: &'a str,
+    parent_id: Option<&'a str>,
+    name: &'static str,
+    props: &'a [(String, String)],
+    depth: usize,
+    start_offset: u64,
+    median: u64,
+}
+
+impl Timings {
+    /// Returns a JSON object with an entry for each span group in `self`, e.g., to archive the results of a run and
+    /// compare them across runs. Same as [`Self::to_json_with_percentiles`] with [`DEFAULT_JSON_PERCENTILES`].
+    /// Present only when the **"serde"** feature flag is enabled.
+    pub fn to_json(&self) -> String {
+        self.to_json_with_percentiles(&DEFAULT_JSON_PERCENTILES)
+    }
+
+    /// Returns a JSON object with the [`JSON_SCHEMA_VERSION`] in its `schema_version` field and, in its `timings`
+    /// array, an entry for each span group in `self`, in the same order as `self` (see [`json_schema`]). Each entry has
+    /// the serialized `span_group` (see [`SpanGroup`]'s `Serialize` implementation) and a `timing` summary with the
+    /// `count`, `min`, `max`, `mean`, `stdev`, and the given `percentiles` (keyed as `"p50"`, `"p99.9"`, etc.) of the
+    /// span group's latencies. The span group tree (see [`Self::span_group_to_parent`]) can be reconstructed from the
+    /// `id` and `parent_id` fields.
+    /// Present only when the **"serde"** feature flag is enabled.
+    pub fn to_json_with_percentiles(&self, percentiles: &[f64]) -> String {
+        let json = TimingsJson {
+            schema_version: JSON_SCHEMA_VERSION,
+            timings: self
+                .iter()
+                .map(|(span_group, timing)| TimingsEntry {
+                    span_group,
+                    timing: TimingSummary::new(timing, percentiles),
+                })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&json).expect("serialization of timings can't fail")
+    }
+
+    /// Returns a JSON waterfall of the span groups in `self` that have an offset in `offsets`, ordered by start
+    /// offset, which shows the sequencing of the span groups of a single request trace. Each entry has the span
+    /// group's `id`, `parent_id`, `name`, `props`, `depth`, `start_offset` (from `offsets`), and `median`
+    /// (the median latency), with the offset and latency in the configured [`TimeUnit`].
+    /// Present only when the **"serde"** feature flag is enabled.
+    ///
+    /// The `offsets` are obtained with [`Report::offsets`](crate::Report::offsets) when
+    /// [`LatencyTraceCfg::with_record_offsets`] is enabled.
+    pub fn to_waterfall(&self, offsets: &Offsets) -> String {
+        let mut entries: Vec<WaterfallEntry> = self
+            .iter()
+            .filter_map(|(sg, timing)| {
+                let start_offset = *offsets.get(sg)?;
+                Some(WaterfallEntry {
+                    id: sg.id(),
+                    parent_id: sg.parent_id(),
+                    name: sg.name(),
+                    props: sg.props(),
+                    depth: sg.depth(),
+                    start_offset,
+                    median: timing.value_at_quantile(0.5),
+                })
+            })
+            .collect();
+        entries.sort_by_key(|e| (e.start_offset, e.depth));
+        serde_json::to_string_pretty(&entries)
+            .expect("serialization of waterfall entries can't fail")
+    }
+}
@@ -2,7 +2,8 @@
 //! Present only when the **"tokio"** feature flag is enabled.
 
 use crate::{
-    lt_refine_g::Timings, lt_report_g::ActivationError, probed_trace::ProbedTrace, LatencyTrace,
+    executor::TokioExecutor, lt_refine_g::Timings, lt_report_g::ActivationError,
+    probed_trace::ProbedTrace, LatencyTrace,
 };
 use std::future::Future;
 
@@ -17,6 +18,17 @@ impl LatencyTrace {
         self.0.measure_latencies_tokio(f)
     }
 
+    /// Executes the instrumented async function `f`, running on the `tokio` runtime; after `f` completes,
+    /// returns the output of its future and the observed latencies.
+    /// Present only when the **"tokio"** feature flag is enabled.
+    pub fn measure_latencies_tokio_r<T, F>(&self, f: impl FnOnce() -> F) -> (T, Timings)
+    where
+        F: Future<Output = T> + Send,
+        T: Send,
+    {
+        self.0.measure_latencies_tokio_r(f)
+    }
+
     /// Executes the instrumented async function `f`, running on the `tokio` runtime; returns a [`ProbedTrace`]
     /// that allows partial latencies to be reported before `f` completes.
     /// Present only when the **"tokio"** feature flag is enabled.
@@ -27,12 +39,6 @@ impl LatencyTrace {
     where
         F: Future<Output = ()> + Send,
     {
-        self.measure_latencies_probed(|| {
-            tokio::runtime::Builder::new_multi_thread()
-                .enable_all()
-                .build()
-                .expect("Tokio runtime error")
-                .block_on(f());
-        })
+        self.measure_latencies_probed_with(TokioExecutor, f)
     }
 }
@@ -3,8 +3,7 @@
 
 use crate::{
     lt_collect_g::{
-        new_timing, op_r, AccRawTrace, CallsiteInfo, LatencyTraceG, Props, RawTrace, SpanGroupPriv,
-        Timing,
+        op_r, AccRawTrace, CallsiteInfo, LatencyTraceG, Props, RawTrace, SpanGroupPriv, Timing,
     },
     tlc_param::{TlcBase, TlcParam},
     Wrapper,
@@ -13,10 +12,14 @@ use base64ct::{Base64, Encoding};
 use sha2::{Digest, Sha256};
 use std::{
     collections::{BTreeMap, HashMap},
-    fmt::Debug,
+    error::Error,
+    fmt::{Debug, Display},
     hash::Hash,
     sync::Arc,
+    thread::ThreadId,
+    time::Duration,
 };
+use tracing::{callsite::Identifier, Level};
 
 pub type CallsiteInfoPath = Vec<Arc<CallsiteInfo>>;
 
@@ -42,6 +45,8 @@ pub type CallsiteInfoPath = Vec<Arc<CallsiteInfo>>;
 ///   in case debug information is not available, the corresponding [`tracing::callsite::Identifier`].
 /// - a [`parent_id`](Self::parent_id) that is the `id` field of the parent span group, if any.
 /// - its [`depth`](Self::depth), i.e., the number of ancestor span groups this span group has
+/// - the [`target`](Self::target) and [`module_path`](Self::module_path) of the span definition, which are not
+///   included in the computation of the `id`
 #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Clone)]
 pub struct SpanGroup {
     pub(crate) name: &'static str,
@@ -50,6 +55,8 @@ pub struct SpanGroup {
     pub(crate) props: Arc<Props>,
     pub(crate) parent_id: Option<Arc<str>>,
     pub(crate) depth: usize,
+    pub(crate) target: &'static str,
+    pub(crate) module_path: Option<&'static str>,
 }
 
 /// Intermediate form of [`SpanGroup`] used in post-processing when transforming from [`SpanGroupPriv`]
@@ -62,11 +69,8 @@ struct SpanGroupTemp {
 
 impl SpanGroupTemp {
     fn parent(&self) -> Option<Self> {
-        let parent_sgp = match self.span_group_priv.parent() {
-            None => return None,
-            Some(sgp) => sgp,
-        };
-        let len = self.span_group_priv.callsite_id_path.len();
+        let parent_sgp = self.span_group_priv.parent()?;
+        let len = self.span_group_priv.depth();
         let callsite_info_priv_path = self.callsite_info_priv_path[0..len - 1].to_vec();
         Some(SpanGroupTemp {
             span_group_priv: parent_sgp,
@@ -84,6 +88,150 @@ pub type TimingsView<K> = Wrapper<BTreeMap<K, Timing>>;
 /// Mapping of [`SpanGroup`]s to the [`Timing`] information recorded for them; inherits all [`BTreeMap`] methods.
 pub type Timings = TimingsView<SpanGroup>;
 
+/// Mapping of [SpanGroup]s to the distribution of the number of times their spans were entered, i.e., polled in
+/// the case of async spans. Recorded only if
+/// [`LatencyTraceCfg::with_record_poll_counts`](crate::LatencyTraceCfg::with_record_poll_counts) is enabled.
+pub type PollCounts = TimingsView<SpanGroup>;
+
+/// Mapping of [SpanGroup]s to the start offset, in the configured [`TimeUnit`](crate::TimeUnit), of their first closed span relative to the creation
+/// of its root span. Recorded only if
+/// [`LatencyTraceCfg::with_record_offsets`](crate::LatencyTraceCfg::with_record_offsets) is enabled.
+pub type Offsets = Wrapper<BTreeMap<SpanGroup, u64>>;
+
+/// Mapping of [SpanGroup]s to the distribution of the start times of their spans, in the configured
+/// [`TimeUnit`](crate::TimeUnit) since a configured epoch. Recorded only if [`LatencyTraceCfg::with_epoch`](crate::LatencyTraceCfg::with_epoch) is set.
+pub type EpochOffsets = TimingsView<SpanGroup>;
+
+/// Mapping of [SpanGroup]s to the distribution of the active times of their spans, i.e., the total time spent
+/// between entering and exiting the spans, in the configured [`TimeUnit`](crate::TimeUnit). For async spans, this is the time spent polling the
+/// instrumented future, excluding the time it is suspended. Recorded only if
+/// [`LatencyTraceCfg::with_record_active_time`](crate::LatencyTraceCfg::with_record_active_time) is enabled.
+pub type ActiveTimings = TimingsView<SpanGroup>;
+
+/// Mapping of [SpanGroup]s to the distribution of the suspended times of their spans, i.e., the latency of each span
+/// minus its active time (see [`ActiveTimings`]), in the configured [`TimeUnit`](crate::TimeUnit). For async spans, this
+/// is the time the instrumented future spends waiting to be polled again, while for synchronous spans, which are
+/// entered for their whole lifetime, it is close to 0, so it directly measures how much of the latency of async spans
+/// is spent suspended. Recorded only if
+/// [`LatencyTraceCfg::with_record_active_time`](crate::LatencyTraceCfg::with_record_active_time) is enabled.
+pub type SuspendTimings = TimingsView<SpanGroup>;
+
+/// Mapping of [SpanGroup]s to the number of times their latency histograms were auto-resized during collection
+/// because a recorded latency exceeded the histogram's highest trackable value, summed across threads. Only span
+/// groups with at least one resize are present.
+///
+/// Resizes reallocate the histograms, which adds to the latencies being measured, so a nonzero count suggests that
+/// [`LatencyTraceCfg::with_hist_high`](crate::LatencyTraceCfg::with_hist_high) should be increased.
+pub type ResizeCounts = Wrapper<BTreeMap<SpanGroup, u64>>;
+
+/// Mapping of [SpanGroup]s to the number of their spans that closed, whether or not their latencies were recorded
+/// (see [`LatencyTraceCfg::with_record_only_nth`](crate::LatencyTraceCfg::with_record_only_nth) and
+/// [`LatencyTraceCfg::with_sampling`](crate::LatencyTraceCfg::with_sampling)). All the span groups
+/// of the corresponding [`Timings`] are present, with a count of `0` for span groups none of whose spans closed, e.g.,
+/// parents of probed span groups that are still open.
+pub type InstanceCounts = Wrapper<BTreeMap<SpanGroup, u64>>;
+
+/// Mapping of [SpanGroup]s to the number of their latencies that were not recorded because they exceeded
+/// [`LatencyTraceCfg::hist_high`](crate::LatencyTraceCfg::hist_high) while auto-resizing was disabled with
+/// [`LatencyTraceCfg::with_auto_resize`](crate::LatencyTraceCfg::with_auto_resize). Only span groups with at least
+/// one overflow are present.
+pub type OverflowCounts = Wrapper<BTreeMap<SpanGroup, u64>>;
+
+/// Mapping of [SpanGroup]s to samples of the distinct values of the fields of their spans, as name-value pairs of all
+/// the fields of each span at creation, in the order first observed and at most
+/// [`LatencyTraceCfg::with_sample_fields`](crate::LatencyTraceCfg::with_sample_fields) per span group. Recorded only if
+/// that setting is set, for closed spans regardless of the configured span grouper. When spans of a span group close
+/// on multiple threads, which of their distinct values are sampled is unspecified.
+pub type FieldSamples = Wrapper<BTreeMap<SpanGroup, Vec<Vec<(String, String)>>>>;
+
+/// Mapping of [SpanGroup]s to the number of *tracing* events, by level, emitted within their spans. Recorded only if
+/// [`LatencyTraceCfg::with_event_counting`](crate::LatencyTraceCfg::with_event_counting) is enabled. Only span groups
+/// with at least one event are present.
+pub type EventCounts = Wrapper<BTreeMap<SpanGroup, BTreeMap<Level, u64>>>;
+
+/// Per-span-group information generated in post-processing of a single measurement, returned by
+/// [`LatencyTrace::measure_report`](crate::LatencyTrace::measure_report). All of its maps are keyed by the same
+/// [`SpanGroup`]s, so the latencies and the other per-span-group data it carries are consistent with each other.
+pub struct Report {
+    pub(crate) timings: Timings,
+    pub(crate) poll_counts: PollCounts,
+    pub(crate) offsets: Offsets,
+    pub(crate) epoch_offsets: EpochOffsets,
+    pub(crate) active_timings: ActiveTimings,
+    pub(crate) suspend_timings: SuspendTimings,
+    pub(crate) resize_counts: ResizeCounts,
+    pub(crate) instance_counts: InstanceCounts,
+    pub(crate) overflow_counts: OverflowCounts,
+    pub(crate) field_samples: FieldSamples,
+    pub(crate) event_counts: EventCounts,
+    pub(crate) diagnostics: ReportDiagnostics,
+    pub(crate) duration: Duration,
+}
+
+//==============
+// ReportDiagnostics
+
+/// Diagnostic information about the thread-local data captured for a latency report, returned by
+/// [`Report::diagnostics`](crate::Report::diagnostics).
+///
+/// Latencies are recorded when spans close, so spans that are still open when the report is produced (e.g.,
+/// spans of worker threads that were not joined) are missing from the report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportDiagnostics {
+    pub(crate) threads_captured: usize,
+    pub(crate) threads_with_open_spans: usize,
+    pub(crate) open_spans: usize,
+}
+
+//==============
+// ConsistencyError
+
+/// Inconsistency among the [`SpanGroup`]s of a [`Timings`], returned by
+/// [`Timings::validate`](crate::Timings::validate).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConsistencyError {
+    /// More than one span group has the `id`.
+    DuplicateId { id: String },
+    /// The parent of the span group with `id` is not in the [`Timings`].
+    OrphanParent { id: String, parent_id: String },
+    /// The `depth` of the span group with `id` is not 1 more than its parent's depth, or 1 for a root span group.
+    WrongDepth {
+        id: String,
+        depth: usize,
+        expected: usize,
+    },
+}
+
+impl Display for ConsistencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl Error for ConsistencyError {}
+
+//==============
+// HdrDeserializationError
+
+/// Error returned by [`Timings::from_hdr_serialized`](crate::Timings::from_hdr_serialized).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HdrDeserializationError {
+    /// There is no serialized histogram for the span group with `id`.
+    MissingHistogram { id: String },
+    /// The serialized histogram of the span group with `id` is not valid base64.
+    InvalidBase64 { id: String },
+    /// The serialized histogram of the span group with `id` is not a valid V2 encoded histogram.
+    InvalidHistogram { id: String, message: String },
+}
+
+impl Display for HdrDeserializationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl Error for HdrDeserializationError {}
+
 /// Intermediate form of latency information collected for span groups, used during post-processing while
 /// transforming [`SpanGroupPriv`] to [`SpanGroup`].
 type TimingsTemp = HashMap<SpanGroupTemp, Timing>;
@@ -91,6 +239,46 @@ type TimingsTemp = HashMap<SpanGroupTemp, Timing>;
 //=================
 // Post-processing
 
+/// Part of post-processing.
+/// Removes the root span groups, making their children the new roots
+/// (see [`LatencyTraceCfg::with_drop_root`](crate::LatencyTraceCfg::with_drop_root)).
+/// The depths of the remaining span groups are decremented but their IDs are unchanged.
+fn drop_root_groups<V>(
+    timings: Wrapper<BTreeMap<SpanGroup, V>>,
+) -> Wrapper<BTreeMap<SpanGroup, V>> {
+    timings
+        .0
+        .into_iter()
+        .filter(|(sg, _)| sg.depth > 1)
+        .map(|(mut sg, timing)| {
+            sg.depth -= 1;
+            if sg.depth == 1 {
+                sg.parent_id = None;
+            }
+            (sg, timing)
+        })
+        .collect::<BTreeMap<SpanGroup, V>>()
+        .into()
+}
+
+/// Part of post-processing.
+/// Changes the keys of `temp` from [`SpanGroupTemp`] to [`SpanGroup`], for values recorded together with timings.
+fn key_by_span_group<V>(
+    temp: HashMap<SpanGroupTemp, V>,
+    sgt_to_sg: &HashMap<SpanGroupTemp, SpanGroup>,
+) -> Wrapper<BTreeMap<SpanGroup, V>> {
+    temp.into_iter()
+        .map(|(sgt, v)| {
+            let sg = sgt_to_sg
+                .get(&sgt)
+                .expect("impossible: sgt key not found in sgt_to_sg")
+                .clone();
+            (sg, v)
+        })
+        .collect::<BTreeMap<SpanGroup, V>>()
+        .into()
+}
+
 impl<P> LatencyTraceG<P>
 where
     P: TlcParam,
@@ -98,31 +286,30 @@ where
 {
     /// Part of post-processing.
     /// Reduces acc to TimingsPriv.
-    fn reduce_acc_to_raw_trace(acc: AccRawTrace) -> RawTrace {
+    pub(crate) fn reduce_acc_to_raw_trace(acc: AccRawTrace) -> RawTrace {
         log::trace!("entering `reduce_acc_to_timings_priv`");
-        acc.into_iter().fold(RawTrace::new(), op_r)
+        // Starting from the first element, rather than from an empty `RawTrace`, avoids copying its maps.
+        acc.into_iter().reduce(op_r).unwrap_or_else(RawTrace::new)
     }
 
     /// Part of post-processing.
     /// Moves callsite info in [`RawTrace`] values into the keys in [TimingsTemp].
-    fn move_callsite_info_to_key(raw_trace: RawTrace) -> TimingsTemp {
+    fn move_callsite_info_to_key<V>(
+        timings: HashMap<SpanGroupPriv, V>,
+        callsite_infos: &HashMap<Identifier, Arc<CallsiteInfo>>,
+    ) -> HashMap<SpanGroupTemp, V> {
         log::trace!("entering `move_callsite_info_to_key`");
-        let RawTrace {
-            timings,
-            callsite_infos,
-        } = raw_trace;
         timings
             .into_iter()
             .map(|(span_group_priv, hist)| {
                 let callsite_info_priv_path: CallsiteInfoPath = span_group_priv
-                    .callsite_id_path
+                    .callsite_id_path()
                     .iter()
                     .map(|id| {
                         callsite_infos
                             .get(id)
                             .expect("`callsite_infos` must have key `id` by construction")
                             .clone()
-                            .into()
                     })
                     .collect();
                 let sgt = SpanGroupTemp {
@@ -143,15 +330,23 @@ where
     ///   when using [super::ProbedTrace].
     /// - Generates the span group IDs, which are inherently recursive as a span group's ID is a hash that
     ///   depends on its parent's ID.
-    fn grow_sgt_to_sg(sgt: &SpanGroupTemp, sgt_to_sg: &mut HashMap<SpanGroupTemp, SpanGroup>) {
+    fn grow_sgt_to_sg(
+        &self,
+        sgt: &SpanGroupTemp,
+        sgt_to_sg: &mut HashMap<SpanGroupTemp, SpanGroup>,
+    ) {
         log::trace!("entering `grow_sgt_to_sg`");
+        // Ancestors are grown before their descendants, so `sgt` may already be present.
+        if sgt_to_sg.contains_key(sgt) {
+            return;
+        }
         let parent_sgt = sgt.parent();
         let parent_id: Option<Arc<str>> = parent_sgt
             .iter()
             .map(|parent_sgp| match sgt_to_sg.get(parent_sgp) {
                 Some(sg) => sg.id.clone(),
                 None => {
-                    Self::grow_sgt_to_sg(parent_sgp, sgt_to_sg);
+                    self.grow_sgt_to_sg(parent_sgp, sgt_to_sg);
                     sgt_to_sg
                         .get(parent_sgp)
                         .expect("key `parent_sgp` must exist in `sgt_to_sg` by construction")
@@ -173,12 +368,7 @@ where
             .map(|(file, line)| format!("{}:{}", file, line))
             .unwrap_or_else(|| format!("{:?}", callsite_info.callsite_id));
 
-        let props = sgt
-            .span_group_priv
-            .props_path
-            .last()
-            .expect("sgt.span_group_priv.props_path can't be empty by construction")
-            .clone();
+        let props = sgt.span_group_priv.props().clone();
 
         let mut hasher = Sha256::new();
         if let Some(parent_id) = parent_id.clone() {
@@ -194,7 +384,7 @@ where
             hasher.update(v);
         }
         let hash = hasher.finalize();
-        let id = Base64::encode_string(&hash[0..8]);
+        let id = Base64::encode_string(&hash[0..self.id_bytes]);
 
         let sg = SpanGroup {
             name: callsite_info.name,
@@ -203,6 +393,8 @@ where
             props,
             parent_id,
             depth: sgt.callsite_info_priv_path.len(),
+            target: callsite_info.target,
+            module_path: callsite_info.module_path,
         };
         sgt_to_sg.insert(sgt.clone(), sg);
     }
@@ -230,28 +422,181 @@ where
 
         // Add entries with empty histograms for span groups that are not already keys in `timings`.
         for sg in sgt_to_sg.into_values() {
-            timings.insert(sg, new_timing(self.hist_high, self.hist_sigfig));
+            timings.insert(sg, self.new_latency_timing());
         }
 
         timings
     }
 
+    /// Computes the [`ReportDiagnostics`] for the accumulated thread-local data.
+    pub(crate) fn report_diagnostics(acc: &AccRawTrace) -> ReportDiagnostics {
+        let open_spans: isize = acc.iter().map(|raw_trace| raw_trace.open_spans).sum();
+        ReportDiagnostics {
+            threads_captured: acc.len(),
+            threads_with_open_spans: acc
+                .iter()
+                .filter(|raw_trace| raw_trace.open_spans > 0)
+                .count(),
+            open_spans: open_spans.max(0) as usize,
+        }
+    }
+
     /// Post-processing orchestration of the above functions.
     /// Generates the publicly accessible [`Timings`] in post-processing after all thread-local
     /// data has been accumulated.
     pub(crate) fn report_timings(&self, acc: AccRawTrace) -> Timings {
-        log::trace!("entering `report_timings`");
+        self.report(acc).timings
+    }
+
+    /// Same as [`Self::report_timings`] but generates separate [`Timings`] for each thread. The `on_report`
+    /// callback is not invoked for these partial reports.
+    /// Thread-local information is reported with the callsite information of all threads, as a span group's
+    /// path may include callsites first seen on other threads.
+    pub(crate) fn report_timings_per_thread(&self, acc: AccRawTrace) -> HashMap<ThreadId, Timings> {
+        let callsite_infos: HashMap<Identifier, CallsiteInfo> = acc
+            .iter()
+            .flat_map(|raw_trace| raw_trace.callsite_infos.clone())
+            .collect();
+        let mut acc_per_thread: HashMap<ThreadId, AccRawTrace> = HashMap::new();
+        for raw_trace in acc {
+            if let Some(thread_id) = raw_trace.thread_id {
+                acc_per_thread.entry(thread_id).or_default().push(raw_trace);
+            }
+        }
+        acc_per_thread
+            .into_iter()
+            .map(|(thread_id, mut acc)| {
+                acc.push(RawTrace {
+                    callsite_infos: callsite_infos.clone(),
+                    ..RawTrace::new()
+                });
+                (thread_id, self.build_report(acc).timings)
+            })
+            .collect()
+    }
+
+    /// Same as [`Self::report_timings`] but also generates the other publicly accessible per-span-group
+    /// information.
+    pub(crate) fn report(&self, acc: AccRawTrace) -> Report {
+        let report = self.build_report(acc);
+        if let Some(on_report) = &self.on_report {
+            on_report(&report.timings);
+        }
+        report
+    }
+
+    /// Same as [`Self::report`] but without invoking the `on_report` callback.
+    pub(crate) fn build_report(&self, acc: AccRawTrace) -> Report {
+        log::trace!("entering `build_report`");
+        let diagnostics = Self::report_diagnostics(&acc);
+
         // Reduce acc to RawTrace
-        let raw_trace: RawTrace = Self::reduce_acc_to_raw_trace(acc);
+        let RawTrace {
+            timings,
+            callsite_infos,
+            poll_counts,
+            offsets,
+            epoch_offsets,
+            active_times,
+            suspend_times,
+            resize_counts,
+            instance_counts,
+            overflow_counts,
+            mut field_samples,
+            event_counts,
+            ..
+        } = {
+            let mut raw_trace = Self::reduce_acc_to_raw_trace(acc);
+            self.flush_pending(&mut raw_trace);
+            raw_trace
+        };
+
+        // Share callsite infos across all the paths that contain them.
+        let callsite_infos: HashMap<Identifier, Arc<CallsiteInfo>> = callsite_infos
+            .into_iter()
+            .map(|(id, info)| (id, Arc::new(info)))
+            .collect();
 
         // Transform RawTrace into TimingsTemp and sgt_to_sg.
-        let timings_temp = Self::move_callsite_info_to_key(raw_trace);
-        let mut sgt_to_sg: HashMap<SpanGroupTemp, SpanGroup> = HashMap::new();
-        for sgt in timings_temp.keys() {
-            Self::grow_sgt_to_sg(sgt, &mut sgt_to_sg);
+        let timings_temp = Self::move_callsite_info_to_key(timings, &callsite_infos);
+        let poll_counts_temp = Self::move_callsite_info_to_key(poll_counts, &callsite_infos);
+        let offsets_temp = Self::move_callsite_info_to_key(offsets, &callsite_infos);
+        let epoch_offsets_temp = Self::move_callsite_info_to_key(epoch_offsets, &callsite_infos);
+        let active_times_temp = Self::move_callsite_info_to_key(active_times, &callsite_infos);
+        let suspend_times_temp = Self::move_callsite_info_to_key(suspend_times, &callsite_infos);
+        let resize_counts_temp = Self::move_callsite_info_to_key(resize_counts, &callsite_infos);
+        let instance_counts_temp =
+            Self::move_callsite_info_to_key(instance_counts, &callsite_infos);
+        let overflow_counts_temp =
+            Self::move_callsite_info_to_key(overflow_counts, &callsite_infos);
+        // Samples from multiple threads are combined without regard to the sample size.
+        if let Some(n) = self.sample_fields {
+            for samples in field_samples.values_mut() {
+                samples.truncate(n);
+            }
+        }
+        let field_samples_temp = Self::move_callsite_info_to_key(field_samples, &callsite_infos);
+        let event_counts_temp = Self::move_callsite_info_to_key(event_counts, &callsite_infos);
+        let mut sgt_to_sg: HashMap<SpanGroupTemp, SpanGroup> =
+            HashMap::with_capacity(timings_temp.len());
+        // Events may be counted in spans that are still open, whose span groups have no timings yet.
+        for sgt in timings_temp.keys().chain(event_counts_temp.keys()) {
+            self.grow_sgt_to_sg(sgt, &mut sgt_to_sg);
+        }
+
+        // Poll counts and offsets are recorded together with timings, so their keys are in `sgt_to_sg`.
+        let poll_counts: PollCounts = key_by_span_group(poll_counts_temp, &sgt_to_sg);
+        let offsets: Offsets = key_by_span_group(offsets_temp, &sgt_to_sg);
+        let epoch_offsets: EpochOffsets = key_by_span_group(epoch_offsets_temp, &sgt_to_sg);
+        let active_timings: ActiveTimings = key_by_span_group(active_times_temp, &sgt_to_sg);
+        let suspend_timings: SuspendTimings = key_by_span_group(suspend_times_temp, &sgt_to_sg);
+        let resize_counts: ResizeCounts = key_by_span_group(resize_counts_temp, &sgt_to_sg);
+        let mut instance_counts: InstanceCounts =
+            key_by_span_group(instance_counts_temp, &sgt_to_sg);
+        let overflow_counts: OverflowCounts = key_by_span_group(overflow_counts_temp, &sgt_to_sg);
+        let field_samples: FieldSamples = key_by_span_group(field_samples_temp, &sgt_to_sg);
+        let event_counts: EventCounts = key_by_span_group(event_counts_temp, &sgt_to_sg);
+        for sg in sgt_to_sg.values() {
+            if !instance_counts.contains_key(sg) {
+                instance_counts.insert(sg.clone(), 0);
+            }
         }
 
         // Transform TimingsTemp and sgt_to_sg into Timings.
-        self.timings_from_timings_temp_and_spt_to_sg(timings_temp, sgt_to_sg)
+        let timings = self.timings_from_timings_temp_and_spt_to_sg(timings_temp, sgt_to_sg);
+
+        if self.drop_root {
+            Report {
+                timings: drop_root_groups(timings),
+                poll_counts: drop_root_groups(poll_counts),
+                offsets: drop_root_groups(offsets),
+                epoch_offsets: drop_root_groups(epoch_offsets),
+                active_timings: drop_root_groups(active_timings),
+                suspend_timings: drop_root_groups(suspend_timings),
+                resize_counts: drop_root_groups(resize_counts),
+                instance_counts: drop_root_groups(instance_counts),
+                overflow_counts: drop_root_groups(overflow_counts),
+                field_samples: drop_root_groups(field_samples),
+                event_counts: drop_root_groups(event_counts),
+                diagnostics,
+                duration: Duration::ZERO,
+            }
+        } else {
+            Report {
+                timings,
+                poll_counts,
+                offsets,
+                epoch_offsets,
+                active_timings,
+                suspend_timings,
+                resize_counts,
+                instance_counts,
+                overflow_counts,
+                field_samples,
+                event_counts,
+                diagnostics,
+                duration: Duration::ZERO,
+            }
+        }
     }
 }
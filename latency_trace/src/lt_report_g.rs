@@ -1,12 +1,16 @@
 //! [`LatencyTraceG`] activation and measurment methods, other supporting types and/or impls.
 
-use hdrhistogram::CreationError;
+use hdrhistogram::{CreationError, Histogram};
 use std::{
+    collections::HashMap,
     error::Error,
     fmt::{Debug, Display},
+    future::Future,
     sync::Arc,
+    thread::{self, ThreadId},
+    time::{Duration, Instant},
 };
-use tracing::Dispatch;
+use tracing::{Dispatch, Level};
 use tracing_subscriber::{
     layer::{Layered, SubscriberExt},
     util::{SubscriberInitExt, TryInitError},
@@ -15,32 +19,76 @@ use tracing_subscriber::{
 
 use crate::{
     default_span_grouper,
-    lt_collect_g::{LatencyTraceCfg, LatencyTraceG, Timing},
-    lt_refine_g::Timings,
+    executor::Executor,
+    lt_collect_g::{LatencyTraceCfg, LatencyTraceG, TimeUnit},
+    lt_refine_g::{Report, Timings},
     tlc_param::{TlcBase, TlcDirect, TlcParam},
 };
 
 //==============
 // Errors
 
+/// Settings of a [`LatencyTraceCfg`] that determine the recorded histograms, reported by
+/// [`ActivationError::ConfigMismatch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistogramCfg {
+    pub time_unit: TimeUnit,
+    pub hist_low: u64,
+    pub hist_high: u64,
+    pub hist_sigfig: u8,
+}
+
+impl Display for HistogramCfg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "time_unit={}, hist_low={}, hist_high={}, hist_sigfig={}",
+            self.time_unit, self.hist_low, self.hist_high, self.hist_sigfig
+        )
+    }
+}
+
 /// Error returned by [`LatencyTrace`](crate::LatencyTrace) activation methods.
 #[derive(Debug)]
 pub enum ActivationError {
-    HistogramConfigError,
+    /// The histogram configuration is invalid, as explained by `message`, which names the offending parameters.
+    HistogramConfigError {
+        message: String,
+    },
     TracingSubscriberInitError,
+    /// An instance is already active with histogram settings that differ from the `requested` ones, so the
+    /// latencies it records would not be as requested.
+    ConfigMismatch {
+        existing: HistogramCfg,
+        requested: HistogramCfg,
+    },
 }
 
 impl Display for ActivationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Debug::fmt(self, f)
+        match self {
+            Self::HistogramConfigError { message } => {
+                write!(f, "invalid histogram configuration: {message}")
+            }
+            Self::TracingSubscriberInitError => Debug::fmt(self, f),
+            Self::ConfigMismatch {
+                existing,
+                requested,
+            } => write!(
+                f,
+                "already activated with histogram configuration ({existing}), requested ({requested})"
+            ),
+        }
     }
 }
 
 impl Error for ActivationError {}
 
 impl From<CreationError> for ActivationError {
-    fn from(_: CreationError) -> Self {
-        Self::HistogramConfigError
+    fn from(e: CreationError) -> Self {
+        Self::HistogramConfigError {
+            message: e.to_string(),
+        }
     }
 }
 
@@ -54,11 +102,37 @@ impl From<TryInitError> for ActivationError {
 // impl for LatencyTraceCfg
 
 impl LatencyTraceCfg {
+    /// Returns the settings of `self` that determine the recorded histograms.
+    fn histogram_cfg(&self) -> HistogramCfg {
+        HistogramCfg {
+            time_unit: self.time_unit,
+            hist_low: self.hist_low,
+            hist_high: self.hist_high,
+            hist_sigfig: self.hist_sigfig,
+        }
+    }
+
     /// Validates that the configuration settings yield histograms that avoid all potential [hdrhistogram::Histogram] errors
-    /// as our histograms are `u64`, have a `hist_low` of `1`, and are auto-resizable.
-    fn validate_hist_high_sigfig(&self) -> Result<(), CreationError> {
-        let _ = Timing::new_with_bounds(1, self.hist_high, self.hist_sigfig)?;
-        Ok(())
+    /// as our histograms are `u64` and auto-resizable.
+    ///
+    /// The constraints of [`hdrhistogram::Histogram::new_with_bounds`] are checked explicitly first, so that the error
+    /// names the offending parameters.
+    pub(crate) fn validate_hist_config(&self) -> Result<(), ActivationError> {
+        let message = if self.hist_low < 1 {
+            format!("hist_low ({}) must be at least 1", self.hist_low)
+        } else if self.hist_high < 2 * self.hist_low {
+            format!(
+                "hist_high ({}) must be at least twice hist_low ({})",
+                self.hist_high, self.hist_low
+            )
+        } else if self.hist_sigfig > 5 {
+            format!("hist_sigfig ({}) must be at most 5", self.hist_sigfig)
+        } else {
+            let _ =
+                Histogram::<u64>::new_with_bounds(self.hist_low, self.hist_high, self.hist_sigfig)?;
+            return Ok(());
+        };
+        Err(ActivationError::HistogramConfigError { message })
     }
 }
 
@@ -68,18 +142,72 @@ impl Default for LatencyTraceCfg {
     ///   callsite information, which distills the *tracing* framework's Callsite concept
     ///   (see [Metadata and Callsite](https://docs.rs/tracing-core/0.1.31/tracing_core/)). This default can be
     ///   modified by using the [`Self::with_span_grouper`] method.
+    /// - Latencies are recorded in microseconds. This default can be modified by using the [`Self::with_time_unit`]
+    ///   method.
+    /// - `hist_low` of `1`. This default can be modified by using the [`Self::with_hist_low`] method.
     /// - `hist_high` of `20,000,000` (20 seconds). This default can be modified by using the [`Self::with_hist_high`] method.
     /// - `hist_sigfig` of 2. This default can be modified by using the [`Self::with_hist_sigfig`] method.
+    /// - No source root, i.e., file paths in [`SpanGroup::code_line`](crate::SpanGroup::code_line)s are reported as
+    ///   provided by the *tracing* framework. This default can be modified by using the [`Self::with_source_root`] method.
+    /// - Spans closed while their thread is panicking are grouped with the other spans. This default can be modified
+    ///   by using the [`Self::with_separate_panicked`] method.
+    /// - Poll counts are not recorded. This default can be modified by using the [`Self::with_record_poll_counts`] method.
+    /// - All span occurrences are recorded. This default can be modified by using the [`Self::with_record_only_nth`]
+    ///   and [`Self::with_sampling`] methods.
+    /// - Root span groups are reported. This default can be modified by using the [`Self::with_drop_root`] method.
+    /// - [`SpanGroup::id`](crate::SpanGroup::id)s are computed from 8 bytes of a hash. This default can be modified
+    ///   by using the [`Self::with_id_bytes`] method.
+    /// - Span start offsets are not recorded. This default can be modified by using the [`Self::with_record_offsets`]
+    ///   method.
+    /// - There is no report callback. This default can be modified by using the [`Self::with_on_report`] method.
+    /// - There is no epoch, i.e., span start times are not recorded. This default can be modified by using the
+    ///   [`Self::with_epoch`] method.
+    /// - Active times are not recorded. This default can be modified by using the [`Self::with_record_active_time`]
+    ///   method.
+    /// - Spans of all levels are recorded, i.e., the minimum level is `trace`. This default can be modified by using
+    ///   the [`Self::with_min_level`] method.
+    /// - Latency histograms are auto-resizable. This default can be modified by using the [`Self::with_auto_resize`]
+    ///   method.
+    /// - Latencies are recorded as each span closes, without batching. This default can be modified by using the
+    ///   [`Self::with_flush_batch`] method.
+    /// - Span field values are not sampled. This default can be modified by using the [`Self::with_sample_fields`]
+    ///   method.
+    /// - Events are not counted. This default can be modified by using the [`Self::with_event_counting`] method.
+    /// - Span names are not added to the span group properties. This default can be modified by using the
+    ///   [`Self::with_path_names`] method.
+    /// - The number of property sets of the span grouper is not capped. This default can be modified by using the
+    ///   [`Self::with_max_groups`] method.
     ///
     /// See [hdrhistogram::Histogram::high] and [hdrhistogram::Histogram::sigfig] for an explanation of these histogram configuration parameters.
     ///
-    /// Note that the histograms used here are auto-resizable, which means [`hdrhistogram::Histogram::high`] is
+    /// Note that by default the histograms used here are auto-resizable, which means [`hdrhistogram::Histogram::high`] is
     /// automatically adjusted as needed (although resizing requires memory reallocation at runtime).
     fn default() -> Self {
         LatencyTraceCfg {
             span_grouper: Arc::new(default_span_grouper),
+            multi_span_grouper: None,
+            hist_low: 1,
             hist_high: 20 * 1000 * 1000,
             hist_sigfig: 2,
+            source_root: None,
+            separate_panicked: false,
+            record_poll_counts: false,
+            record_only_nth: None,
+            sample_stride: None,
+            drop_root: false,
+            id_bytes: 8,
+            record_offsets: false,
+            on_report: None,
+            epoch: None,
+            record_active_time: false,
+            time_unit: TimeUnit::Micros,
+            min_level: Level::TRACE,
+            auto_resize: true,
+            flush_batch: None,
+            sample_fields: None,
+            event_counting: false,
+            max_groups: None,
+            path_names: false,
         }
     }
 }
@@ -106,15 +234,33 @@ where
     /// be changed once it is set.
     ///
     /// If a [`LatencyTrace`] has been previously activated in the same process, the `config` passed to this
-    /// function will be ignored and the current active [`LatencyTrace`] will be returned.
+    /// function will be ignored and the current active [`LatencyTrace`] will be returned, provided that its
+    /// time unit and histogram settings are the same as those of `config`.
     ///
     /// # Errors
-    /// - [`ActivationError::HistogramConfigError`] if the `config`'s `hist_high` and `hist_sigfig` would cause
-    ///   [`hdrhistogram::Histogram::new_with_bounds`]`(1, hist_high, hist_sigfig)` to fail.
+    /// - [`ActivationError::HistogramConfigError`] if the `config`'s `hist_low`, `hist_high`, and `hist_sigfig` would
+    ///   cause [`hdrhistogram::Histogram::new_with_bounds`]`(hist_low, hist_high, hist_sigfig)` to fail, e.g., if
+    ///   `hist_high` is less than twice `hist_low` or `hist_sigfig` is greater than 5.
     /// - [`ActivationError::TracingSubscriberInitError`] if a global [`tracing::Subscriber`] is already set and its
     ///   type is not the same as `Self`.
+    /// - [`ActivationError::ConfigMismatch`] if an instance is already active with a different `time_unit`,
+    ///   `hist_low`, `hist_high`, or `hist_sigfig`.
     pub fn activated(config: LatencyTraceCfg) -> Result<Self, ActivationError> {
-        config.validate_hist_high_sigfig()?;
+        let requested = config.histogram_cfg();
+        let lt = Self::activated_lenient(config)?;
+        let existing = lt.histogram_cfg();
+        if existing != requested {
+            return Err(ActivationError::ConfigMismatch {
+                existing,
+                requested,
+            });
+        }
+        Ok(lt)
+    }
+
+    /// Same as [`Self::activated`] but returns the active instance regardless of its configuration.
+    fn activated_lenient(config: LatencyTraceCfg) -> Result<Self, ActivationError> {
+        config.validate_hist_config()?;
         let default_dispatch_exists =
             tracing::dispatcher::get_default(|disp| disp.is::<Layered<Self, Registry>>());
         let lt = if !default_dispatch_exists {
@@ -128,18 +274,59 @@ where
         Ok(lt)
     }
 
+    /// Returns the settings of `self` that determine the recorded histograms.
+    fn histogram_cfg(&self) -> HistogramCfg {
+        HistogramCfg {
+            time_unit: self.time_unit,
+            hist_low: self.hist_low,
+            hist_high: self.hist_high,
+            hist_sigfig: self.hist_sigfig,
+        }
+    }
+
     /// Returns the active instance of `Self` if it exists or activates a new instance with the default configuration otherwise.
     /// Activation entails setting the global default [`tracing::Subscriber`], of which there can be only one and it can't
     /// be changed once it is set.
     ///
     /// If a [`LatencyTrace`] has been previously activated in the same process, the default configuration
-    /// will be ignored and the current active [`LatencyTrace`] will be returned.
+    /// will be ignored and the current active [`LatencyTrace`] will be returned, whatever its configuration.
     ///
     /// # Errors
     /// - [`ActivationError::TracingSubscriberInitError`] if a global [`tracing::Subscriber`] is already set and its
     ///   type is not the same as `Self`.
     pub fn activated_default() -> Result<Self, ActivationError> {
-        Self::activated(LatencyTraceCfg::default())
+        Self::activated_lenient(LatencyTraceCfg::default())
+    }
+}
+
+impl<P> LatencyTraceG<P>
+where
+    P: TlcParam + Clone + 'static,
+    P::Control: TlcDirect + Clone,
+    Layered<LatencyTraceG<P>, Registry>: Into<Dispatch>,
+{
+    /// Executes the instrumented function `f` with a new instance of `Self`, configured with `config`, as the
+    /// default [`tracing::Subscriber`] of the thread on which `f` is executed, and returns the latencies observed
+    /// during its execution, without setting the global default [`tracing::Subscriber`].
+    ///
+    /// `f` is executed on a new thread because the thread-local latency information of a thread is bound to the
+    /// first instance that records latencies on it.
+    ///
+    /// # Errors
+    /// - [`ActivationError::HistogramConfigError`] if the `config`'s histogram parameters are invalid (see
+    ///   [`Self::activated`]).
+    pub fn with_scoped(
+        config: LatencyTraceCfg,
+        f: impl FnOnce() + Send,
+    ) -> Result<Timings, ActivationError> {
+        config.validate_hist_config()?;
+        let lt = LatencyTraceG::new(config);
+        let dispatch: Dispatch = Registry::default().with(lt.clone()).into();
+        thread::scope(|s| {
+            s.spawn(|| tracing::dispatcher::with_default(&dispatch, f));
+        });
+        let acc = lt.take_acc_timings();
+        Ok(lt.report_timings(acc))
     }
 }
 
@@ -154,4 +341,60 @@ where
         let acc = self.take_acc_timings();
         self.report_timings(acc)
     }
+
+    /// Executes the instrumented function `f` and, after `f` completes, returns its result and the observed
+    /// latencies.
+    pub fn measure_latencies_r<T>(&self, f: impl FnOnce() -> T) -> (T, Timings) {
+        let res = f();
+        let acc = self.take_acc_timings();
+        (res, self.report_timings(acc))
+    }
+
+    /// Executes the instrumented async function `f`, running on `executor`; after `f` completes,
+    /// returns the observed latencies.
+    pub fn measure_latencies_with<F>(
+        &self,
+        executor: &impl Executor,
+        f: impl FnOnce() -> F,
+    ) -> Timings
+    where
+        F: Future<Output = ()>,
+    {
+        self.measure_latencies(move || executor.block_on(f()))
+    }
+
+    /// Repeatedly executes the instrumented function `f` until `duration` has elapsed and then returns the
+    /// observed latencies. `f` is executed at least once and the last execution may end after `duration`.
+    pub fn measure_latencies_for(&self, duration: Duration, f: impl Fn()) -> Timings {
+        self.measure_latencies(|| {
+            let start = Instant::now();
+            loop {
+                f();
+                if start.elapsed() >= duration {
+                    break;
+                }
+            }
+        })
+    }
+
+    /// Same as [`Self::measure_latencies`] but returns the full [`Report`] of the measurement, including its
+    /// wall-clock duration, from the start of `f` until the latencies are extracted.
+    pub fn measure_report(&self, f: impl FnOnce()) -> Report {
+        let start = Instant::now();
+        f();
+        let acc = self.take_acc_timings();
+        let duration = start.elapsed();
+        Report {
+            duration,
+            ..self.report(acc)
+        }
+    }
+
+    /// Executes the instrumented function `f` and, after `f` completes, returns the observed latencies of
+    /// each thread.
+    pub fn measure_latencies_per_thread(&self, f: impl FnOnce()) -> HashMap<ThreadId, Timings> {
+        f();
+        let acc = self.take_acc_timings();
+        self.report_timings_per_thread(acc)
+    }
 }
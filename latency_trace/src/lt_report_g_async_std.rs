@@ -0,0 +1,26 @@
+//! `async-std`-related latency reporting methods of [`crate::lt_collect_g::LatencyTraceG`].
+//! Present only when the **"async-std"** feature flag is enabled.
+
+use crate::{
+    executor::AsyncStdExecutor,
+    lt_collect_g::LatencyTraceG,
+    lt_refine_g::Timings,
+    tlc_param::{TlcDirect, TlcParam},
+};
+use std::future::Future;
+
+impl<P> LatencyTraceG<P>
+where
+    P: TlcParam,
+    P::Control: TlcDirect,
+{
+    /// Executes the instrumented async function `f`, running on the `async-std` runtime; after `f` completes,
+    /// returns the observed latencies.
+    /// Present only when the **"async-std"** feature flag is enabled.
+    pub fn measure_latencies_async_std<F>(&self, f: impl FnOnce() -> F) -> Timings
+    where
+        F: Future<Output = ()> + Send,
+    {
+        self.measure_latencies_with(&AsyncStdExecutor, f)
+    }
+}
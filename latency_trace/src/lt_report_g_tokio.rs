@@ -2,6 +2,7 @@
 //! Present only when the **"tokio"** feature flag is enabled.
 
 use crate::{
+    executor::TokioExecutor,
     lt_collect_g::LatencyTraceG,
     lt_refine_g::Timings,
     tlc_param::{TlcDirect, TlcParam},
@@ -20,12 +21,25 @@ where
     where
         F: Future<Output = ()> + Send,
     {
-        self.measure_latencies(move || {
-            tokio::runtime::Builder::new_multi_thread()
-                .enable_all()
-                .build()
-                .expect("Tokio runtime error")
-                .block_on(f());
-        })
+        self.measure_latencies_with(&TokioExecutor, f)
+    }
+
+    /// Executes the instrumented async function `f`, running on the `tokio` runtime; after `f` completes,
+    /// returns the output of its future and the observed latencies.
+    /// Present only when the **"tokio"** feature flag is enabled.
+    pub fn measure_latencies_tokio_r<T, F>(&self, f: impl FnOnce() -> F) -> (T, Timings)
+    where
+        F: Future<Output = T> + Send,
+        T: Send,
+    {
+        let mut res = None;
+        let timings = self.measure_latencies_tokio(|| {
+            let fut = f();
+            async {
+                res = Some(fut.await);
+            }
+        });
+        let res = res.expect("`res` set when the future completes");
+        (res, timings)
     }
 }
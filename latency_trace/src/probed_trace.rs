@@ -1,17 +1,51 @@
 //! Provides the ability to obtain interim timing information before the target function terminates.
 
-use crate::{LatencyTrace, Timings};
+use crate::{LatencyTrace, Timing, Timings};
+use hdrhistogram::Histogram;
 use std::{
-    sync::{Arc, Mutex},
-    thread::JoinHandle,
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
 };
 
+/// Maximum interval at which the reporting thread started by [`ProbedTrace::report_every`] checks whether the
+/// function being measured has completed.
+const COMPLETION_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Determines how interim latencies are obtained by [`ProbedTrace::probe_latencies_with_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProbeMode {
+    /// Reads the current thread-local data of each thread in turn, without stopping the threads of the function
+    /// being measured. Spans that close while the probe is in progress may be reflected for some threads but not for
+    /// others, so the interim latencies may be slightly inconsistent across threads. Those spans are not lost and are
+    /// included in subsequent probes and in the final report.
+    #[default]
+    NonBlocking,
+
+    /// Blocks the recording of latencies on all threads while the thread-local data is read, so the interim
+    /// latencies are a consistent snapshot across threads. Threads whose spans close during the probe wait for it to
+    /// complete, which adds to the latencies of their enclosing spans, so blocking probes are appropriate when exact
+    /// counts are needed but can noticeably distort the results if they are frequent or there are many span groups.
+    /// Once a blocking probe has been made, every recording incurs a small synchronization cost, which is otherwise
+    /// avoided. Recordings already in progress when the first blocking probe is made are not blocked by it.
+    Blocking,
+}
+
 /// Represents an ongoing collection of latency information with the ability to report on partial latencies
 /// before the instrumented function completes.
 #[derive(Clone)]
 pub struct ProbedTrace {
     lt: LatencyTrace,
     join_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+    reporter_handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    /// Set once [`Self::wait_and_report`] has joined the thread of the function being measured.
+    joined: Arc<AtomicBool>,
+    /// Latencies returned by the last call to [`Self::report_delta`], if any.
+    last_probe: Arc<Mutex<Option<Timings>>>,
 }
 
 impl ProbedTrace {
@@ -19,6 +53,9 @@ impl ProbedTrace {
         Self {
             lt,
             join_handle: Mutex::new(None).into(),
+            reporter_handles: Mutex::new(Vec::new()).into(),
+            joined: AtomicBool::new(false).into(),
+            last_probe: Mutex::new(None).into(),
         }
     }
 
@@ -30,28 +67,151 @@ impl ProbedTrace {
         *jh = Some(join_handle);
     }
 
-    /// Returns partial latencies collected when the call is made.
+    /// Returns `true` if the function being measured has completed.
+    pub(crate) fn is_finished(&self) -> bool {
+        let lock = self
+            .join_handle
+            .lock()
+            .expect("ProbedTrace join_handle Mutex poisoned");
+        match lock.as_ref() {
+            Some(jh) => jh.is_finished(),
+            // The handle is taken by `wait_and_report` before the function being measured completes.
+            None => self.joined.load(Ordering::Acquire),
+        }
+    }
+
+    /// Returns partial latencies collected when the call is made. Same as [`Self::probe_latencies_with_mode`]
+    /// with [`ProbeMode::NonBlocking`].
     pub fn probe_latencies(&self) -> Timings {
-        let acc = self.lt.0.control.probe_tls();
+        self.probe_latencies_with_mode(ProbeMode::NonBlocking)
+    }
+
+    /// Returns partial latencies collected when the call is made, using the given [`ProbeMode`].
+    pub fn probe_latencies_with_mode(&self, mode: ProbeMode) -> Timings {
+        let acc = match mode {
+            ProbeMode::NonBlocking => self.lt.0.control.probe_tls(),
+            ProbeMode::Blocking => {
+                let _gate = self.lt.0.block_recording();
+                self.lt.0.control.probe_tls()
+            }
+        };
         self.lt.0.report_timings(acc)
     }
 
+    /// Returns the latencies recorded since the previous call to this method, or since the function being measured
+    /// started for the first call, e.g., to periodically report the recent latencies of a long-running service
+    /// rather than cumulative ones. The latencies are obtained with [`Self::probe_latencies`], and the histogram
+    /// counts of the previous probe are subtracted from those of the current one for each span group.
+    ///
+    /// Span groups that were not in the previous probe are reported with their full histograms, and span groups with
+    /// no new latencies are reported with empty histograms.
+    ///
+    /// Partial latencies are obtained with [`ProbeMode::NonBlocking`], so spans that close during a probe may be
+    /// reflected in it for some threads and not others. Should a count of the previous probe exceed the corresponding
+    /// count of the current one as a result, the subtraction is saturated at zero for that count, so the delta
+    /// latencies of the affected span groups may omit a few values that are then included in the next delta.
+    pub fn report_delta(&self) -> Timings {
+        let current = self.probe_latencies();
+        let mut last_probe = self
+            .last_probe
+            .lock()
+            .expect("ProbedTrace last_probe Mutex poisoned");
+        let delta = match last_probe.as_ref() {
+            None => current.clone(),
+            Some(previous) => current
+                .iter()
+                .map(|(sg, timing)| {
+                    let delta = match previous.get(sg) {
+                        Some(prev_timing) => subtract_timing(timing, prev_timing),
+                        None => timing.clone(),
+                    };
+                    (sg.clone(), delta)
+                })
+                .collect::<BTreeMap<_, _>>()
+                .into(),
+        };
+        *last_probe = Some(current);
+        delta
+    }
+
+    /// Starts a thread that passes partial latencies, obtained with [`Self::probe_latencies`], to `f` every
+    /// `interval` until the function being measured completes, e.g., to periodically log the latencies of a
+    /// long-running service. Each call to `f` receives the cumulative latencies at the time of the call.
+    ///
+    /// Once the function being measured completes, `f` is called a final time with the complete latencies and the
+    /// thread exits. [`Self::wait_and_report`] waits for that final call before returning.
+    pub fn report_every(&self, interval: Duration, f: impl Fn(Timings) + Send + 'static) {
+        let pt = self.clone();
+        let reporter_handle = thread::spawn(move || {
+            let mut next_report = Instant::now() + interval;
+            loop {
+                if pt.is_finished() {
+                    f(pt.probe_latencies());
+                    break;
+                }
+                let now = Instant::now();
+                if now >= next_report {
+                    f(pt.probe_latencies());
+                    next_report += interval;
+                } else {
+                    thread::sleep(COMPLETION_POLL_INTERVAL.min(next_report - now));
+                }
+            }
+        });
+        self.reporter_handles
+            .lock()
+            .expect("ProbedTrace reporter_handles Mutex poisoned")
+            .push(reporter_handle);
+    }
+
     /// Blocks until the function being measured completes, and then returns the collected latency information.
+    /// Also waits for the threads started by [`Self::report_every`] to deliver their final reports.
     ///
     /// Should only be called at most once, from main thread. May panic otherwise.
     pub fn wait_and_report(&self) -> Timings {
-        // try_lock() below should always succeed because this function is the only one that should be joining
-        // the handle and it should only be called once from the main thread.
+        // The lock is only held briefly by other threads checking `is_finished`, and this function is the only one
+        // that should be joining the handle and it should only be called once from the main thread.
         let join_handle = self
             .join_handle
-            .try_lock()
-            .expect("ProbedTrace lock should not be contended")
+            .lock()
+            .expect("ProbedTrace join_handle Mutex poisoned")
             .take()
             .expect("`join_handle` set by constructor, may only be taken once");
         join_handle
             .join()
             .expect("ProbedTrace execution thread exited abnormally");
+        self.joined.store(true, Ordering::Release);
+        let reporter_handles = std::mem::take(
+            &mut *self
+                .reporter_handles
+                .lock()
+                .expect("ProbedTrace reporter_handles Mutex poisoned"),
+        );
+        for reporter_handle in reporter_handles {
+            reporter_handle
+                .join()
+                .expect("ProbedTrace reporting thread exited abnormally");
+        }
         let acc = self.lt.0.take_acc_timings();
         self.lt.0.report_timings(acc)
     }
 }
+
+/// Returns the histogram counts of `timing` minus those of `previous`, saturating each count at zero.
+fn subtract_timing(timing: &Timing, previous: &Timing) -> Timing {
+    let mut delta = timing.clone();
+    if delta.subtract(previous.value()).is_ok() {
+        return delta;
+    }
+
+    let mut hist = Histogram::new_from(timing.value());
+    for v in timing.iter_recorded() {
+        let value = v.value_iterated_to();
+        let count = v.count_at_value().saturating_sub(previous.count_at(value));
+        if count > 0 {
+            hist.record_n(value, count)
+                .expect("value was recorded in a histogram with the same range");
+        }
+    }
+    Timing::new(hist, timing.unit())
+}
@@ -0,0 +1,61 @@
+//! Publicly exported signal-related methods of [`ProbedTrace`].
+//! Present only on Unix platforms when the **"signals"** feature flag is enabled.
+
+use crate::{probed_trace::ProbedTrace, Timings};
+use std::{
+    io,
+    os::raw::c_int,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Interval at which the dump thread started by [`ProbedTrace::dump_on_signal`] checks whether a signal was received.
+const SIGNAL_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+impl ProbedTrace {
+    /// Installs a handler for `signal` (e.g., `libc::SIGUSR1`) so that each time the process receives the signal,
+    /// partial latencies are obtained with [`Self::probe_latencies`] and passed to `f`. This allows reports of a
+    /// long-running service to be dumped on demand, e.g., with `kill -USR1 <pid>`, without a dedicated endpoint.
+    /// Present only on Unix platforms when the **"signals"** feature flag is enabled.
+    ///
+    /// The signal handler only sets a flag; the probe and `f` run on a dedicated thread that checks the flag
+    /// periodically, so `f` need not be async-signal-safe. Signals received while a dump is in progress may be
+    /// coalesced into a single subsequent dump.
+    ///
+    /// The dedicated thread exits, and the handler is removed, once the function being measured completes. While the
+    /// handler is installed, it replaces the default action for `signal` (e.g., process termination for
+    /// `SIGUSR1`), and after it is removed the signal is ignored.
+    ///
+    /// # Errors
+    /// - If the handler can't be installed, e.g., for a forbidden signal such as `SIGKILL`.
+    pub fn dump_on_signal(
+        &self,
+        signal: c_int,
+        f: impl Fn(&Timings) + Send + 'static,
+    ) -> Result<(), io::Error> {
+        let received = Arc::new(AtomicBool::new(false));
+        let sig_id = signal_hook::flag::register(signal, received.clone())?;
+        let pt = self.clone();
+
+        thread::spawn(move || {
+            loop {
+                // Checked before the flag so that a signal received before completion is still dumped.
+                let finished = pt.is_finished();
+                if received.swap(false, Ordering::Relaxed) {
+                    f(&pt.probe_latencies());
+                }
+                if finished {
+                    break;
+                }
+                thread::sleep(SIGNAL_POLL_INTERVAL);
+            }
+            signal_hook::low_level::unregister(sig_id);
+        });
+
+        Ok(())
+    }
+}
@@ -1,4 +1,8 @@
-use std::{collections::BTreeMap, fmt};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+    sync::RwLock,
+};
 use tracing::{
     field::{Field, Visit},
     span::Attributes,
@@ -23,6 +27,31 @@ impl Visit for FieldReader {
     }
 }
 
+/// Reads the string value of a single named field, from span attributes or from event fields.
+pub(crate) struct StrFieldReader<'a> {
+    name: &'a str,
+    value: Option<String>,
+}
+
+impl<'a> StrFieldReader<'a> {
+    /// Returns the value of the field `name` visited by `record`, if it is present and is a string.
+    pub(crate) fn read(name: &'a str, record: impl FnOnce(&mut dyn Visit)) -> Option<String> {
+        let mut reader = StrFieldReader { name, value: None };
+        record(&mut reader);
+        reader.value
+    }
+}
+
+impl Visit for StrFieldReader<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == self.name {
+            self.value = Some(value.to_owned());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
 /// Custom span grouper used to group spans by callsite, ancestors, and all span fields and their values.
 pub fn group_by_all_fields(attrs: &Attributes) -> Vec<(String, String)> {
     let reader = &mut FieldReader::new();
@@ -35,7 +64,9 @@ pub fn group_by_all_fields(attrs: &Attributes) -> Vec<(String, String)> {
 }
 
 /// Custom span grouper used to group spans by callsite, ancestors, and a given list of span field names. Spans with the same
-/// callsites, ancestors, and values for the specified fields are grouped together.
+/// callsites, ancestors, and values for the specified fields are grouped together. The field values are rendered with
+/// their `Debug` representations, and fields that are not present on a span are omitted from its props, e.g.,
+/// `group_by_given_fields(&["endpoint", "shard_id"])` groups spans by `endpoint` and `shard_id` where present.
 pub fn group_by_given_fields<'a>(
     given_names: &'a [&'a str],
 ) -> impl Fn(&Attributes) -> Vec<(String, String)> + Send + Sync + 'a {
@@ -45,8 +76,128 @@ pub fn group_by_given_fields<'a>(
         reader
             .0
             .iter()
-            .filter(|(k, _)| (given_names.contains(*k)))
+            .filter(|(k, _)| given_names.contains(*k))
+            .map(|(k, v)| ((*k).to_owned(), v.to_owned()))
+            .collect()
+    }
+}
+
+/// Same as [`group_by_given_fields`] but with owned field names.
+#[cfg(feature = "serde")]
+pub(crate) fn group_by_given_owned_fields(
+    given_names: Vec<String>,
+) -> impl Fn(&Attributes) -> Vec<(String, String)> + Send + Sync {
+    move |attrs: &Attributes| {
+        let reader = &mut FieldReader::new();
+        attrs.values().record(reader);
+        reader
+            .0
+            .iter()
+            .filter(|(k, _)| given_names.iter().any(|name| name == *k))
             .map(|(k, v)| ((*k).to_owned(), v.to_owned()))
             .collect()
     }
 }
+
+/// Map of span field names to their values, used by [`composite_grouper`].
+pub type FieldMap = BTreeMap<&'static str, String>;
+
+/// Reads span fields into a [`FieldMap`], without quoting string values.
+struct FieldMapReader(FieldMap);
+
+impl Visit for FieldMapReader {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+/// Custom span grouper that collects all span fields and their values into a [`FieldMap`] and then groups spans
+/// by callsite, ancestors, and the name-value pairs built by `f` from the [`FieldMap`]. This makes it
+/// straightforward to combine multiple fields into readable labels, e.g., `"GET /users [cache=miss]"`.
+///
+/// Unlike [`group_by_all_fields`] and [`group_by_given_fields`], string field values in the [`FieldMap`] are
+/// not quoted. Other values are formatted with [`fmt::Debug`].
+pub fn composite_grouper(
+    f: impl Fn(&FieldMap) -> Vec<(String, String)> + Send + Sync,
+) -> impl Fn(&Attributes) -> Vec<(String, String)> + Send + Sync {
+    move |attrs: &Attributes| {
+        let reader = &mut FieldMapReader(FieldMap::new());
+        attrs.values().record(reader);
+        f(&reader.0)
+    }
+}
+
+/// Custom span grouper used to group spans by callsite, ancestors, and the first `depth` components of the span's
+/// module path (e.g., `a::b` for module path `a::b::c` and `depth` 2), in a `"module"` property. The whole module path
+/// is used if it has fewer than `depth` components. Spans without a module path are grouped by their target instead.
+///
+/// This allows latencies to be rolled up by crate (`depth` 1), module, or submodule with
+/// [`TimingsView::aggregate`](crate::TimingsView::aggregate).
+pub fn module_level_grouper(
+    depth: usize,
+) -> impl Fn(&Attributes) -> Vec<(String, String)> + Send + Sync {
+    move |attrs: &Attributes| {
+        let meta = attrs.metadata();
+        let module_path = meta.module_path().unwrap_or_else(|| meta.target());
+        let module = module_path
+            .split("::")
+            .take(depth)
+            .collect::<Vec<_>>()
+            .join("::");
+        vec![("module".to_owned(), module)]
+    }
+}
+
+/// Name of the property of the span groups of spans whose properties are mapped to the overflow label by
+/// [`bounded_cardinality`].
+pub const OVERFLOW_PROP: &str = "overflow";
+
+/// Span grouper wrapper that bounds the number of distinct property sets produced by the span grouper `inner`, e.g.,
+/// to group spans by a high-cardinality field such as a user ID without unbounded memory use. The first `max_groups`
+/// distinct property sets returned by `inner` are passed through unchanged; after that, spans for which `inner`
+/// returns any other property set are grouped with the single property `(`[`OVERFLOW_PROP`]`, overflow_label)`.
+///
+/// The distinct property sets are tracked across all callsites, so the number of span groups per callsite and
+/// ancestors is at most `max_groups + 1`. Empty property sets are always passed through and don't count towards
+/// `max_groups`, as they don't add span groups.
+///
+/// The returned grouper is thread-safe: the property sets seen so far are kept behind a [`RwLock`], which is
+/// read-locked once per span creation with a nonempty property set and write-locked only to admit a new one, so
+/// spans for which `inner` returns no properties, e.g., with the [`default_span_grouper`], don't lock at all.
+/// Which property sets are admitted depends on the order in which spans are created, which
+/// is nondeterministic across threads, but each property set is consistently mapped once admitted: it keeps its own
+/// span group for the lifetime of the grouper, and every property set that arrives after `max_groups` have been
+/// admitted is always mapped to the overflow label.
+pub fn bounded_cardinality(
+    inner: impl Fn(&Attributes) -> Vec<(String, String)> + Send + Sync,
+    max_groups: usize,
+    overflow_label: impl Into<String>,
+) -> impl Fn(&Attributes) -> Vec<(String, String)> + Send + Sync {
+    let overflow_label = overflow_label.into();
+    let seen: RwLock<HashSet<Vec<(String, String)>>> = RwLock::new(HashSet::new());
+    move |attrs: &Attributes| {
+        let props = inner(attrs);
+        if props.is_empty()
+            || seen
+                .read()
+                .expect("bounded_cardinality lock poisoned")
+                .contains(&props)
+        {
+            return props;
+        }
+        let mut seen = seen.write().expect("bounded_cardinality lock poisoned");
+        // Checked again as the property set may have been admitted since the read lock was released.
+        if seen.contains(&props) {
+            return props;
+        }
+        if seen.len() < max_groups {
+            seen.insert(props.clone());
+            return props;
+        }
+        vec![(OVERFLOW_PROP.to_owned(), overflow_label.clone())]
+    }
+}
@@ -1,7 +1,13 @@
-use crate::Timing;
+use hdrhistogram::Histogram;
+use std::fmt::Display;
+
+use crate::{TimeUnit, Timing};
+
+/// Percentiles included in [`SummaryStats::percentiles`] by [`summary_stats`] and [`SummaryStats::new`].
+pub const DEFAULT_PERCENTILES: [f64; 9] = [1.0, 5.0, 10.0, 25.0, 50.0, 75.0, 90.0, 95.0, 99.0];
 
 /// Common summary statistics useful in latency testing/benchmarking.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SummaryStats {
     pub count: u64,
     pub mean: f64,
@@ -17,11 +23,30 @@ pub struct SummaryStats {
     pub p95: u64,
     pub p99: u64,
     pub max: u64,
+    /// The unit of the statistics other than `count`.
+    pub unit: TimeUnit,
+    /// The requested percentiles and their values, in the requested order (see [`summary_stats_with_percentiles`]).
+    pub percentiles: Vec<(f64, u64)>,
 }
 
 impl SummaryStats {
-    /// Computes summary statistics from the given histogram.
-    pub fn new(hist: &Timing) -> Self {
+    /// Computes summary statistics from the given [`Timing`], in its [`Timing::unit`].
+    pub fn new(timing: &Timing) -> Self {
+        Self::new_with_unit(timing, timing.unit())
+    }
+
+    /// Computes summary statistics from the given histogram, whose values are in `unit`.
+    pub fn new_with_unit(hist: &Histogram<u64>, unit: TimeUnit) -> Self {
+        Self::new_with_percentiles(hist, unit, &DEFAULT_PERCENTILES)
+    }
+
+    /// Computes summary statistics from the given histogram, whose values are in `unit`, including the values of
+    /// the given `percentiles` (in the range `0.0..=100.0`) in [`Self::percentiles`].
+    pub fn new_with_percentiles(
+        hist: &Histogram<u64>,
+        unit: TimeUnit,
+        percentiles: &[f64],
+    ) -> Self {
         Self {
             count: hist.len(),
             mean: hist.mean(),
@@ -37,11 +62,191 @@ impl SummaryStats {
             p95: hist.value_at_quantile(0.95),
             p99: hist.value_at_quantile(0.99),
             max: hist.max(),
+            unit,
+            percentiles: percentiles
+                .iter()
+                .map(|p| (*p, hist.value_at_percentile(*p)))
+                .collect(),
+        }
+    }
+
+    /// Returns the coefficient of variation, i.e., `stdev / mean`, a unitless measure of dispersion that can be used to
+    /// flag high-variance span groups. Returns `0.0` if `mean` is `0.0`, e.g., if `count` is `0`.
+    pub fn cv(&self) -> f64 {
+        if self.mean == 0.0 {
+            0.0
+        } else {
+            self.stdev / self.mean
+        }
+    }
+
+    /// Returns the value of the percentile `p` if it is in [`Self::percentiles`].
+    pub fn percentile(&self, p: f64) -> Option<u64> {
+        self.percentiles
+            .iter()
+            .find(|(p1, _)| *p1 == p)
+            .map(|(_, value)| *value)
+    }
+
+    /// Returns the changes of the mean, minimum, fixed percentiles, and maximum of `self` relative to `baseline`,
+    /// e.g., to detect latency regressions between two runs. Both are assumed to be in the same unit.
+    pub fn diff(&self, baseline: &SummaryStats) -> SummaryStatsDiff {
+        let change = |current: u64, baseline: u64| StatChange::new(current as f64, baseline as f64);
+        SummaryStatsDiff {
+            count: (self.count, baseline.count),
+            mean: StatChange::new(self.mean, baseline.mean),
+            min: change(self.min, baseline.min),
+            p1: change(self.p1, baseline.p1),
+            p5: change(self.p5, baseline.p5),
+            p10: change(self.p10, baseline.p10),
+            p25: change(self.p25, baseline.p25),
+            median: change(self.median, baseline.median),
+            p75: change(self.p75, baseline.p75),
+            p90: change(self.p90, baseline.p90),
+            p95: change(self.p95, baseline.p95),
+            p99: change(self.p99, baseline.p99),
+            max: change(self.max, baseline.max),
         }
     }
+
+    /// Returns `self` with its unit set to `unit`, without changing the statistics' values.
+    pub fn with_unit(self, unit: TimeUnit) -> Self {
+        Self { unit, ..self }
+    }
+}
+
+impl Display for SummaryStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let u = self.unit;
+        write!(
+            f,
+            "count={}, mean={:.1}{u}, stdev={:.1}{u}, min={}{u}, p1={}{u}, p5={}{u}, p10={}{u}, p25={}{u}, \
+            median={}{u}, p75={}{u}, p90={}{u}, p95={}{u}, p99={}{u}, max={}{u}",
+            self.count,
+            self.mean,
+            self.stdev,
+            self.min,
+            self.p1,
+            self.p5,
+            self.p10,
+            self.p25,
+            self.median,
+            self.p75,
+            self.p90,
+            self.p95,
+            self.p99,
+            self.max,
+        )
+    }
+}
+
+/// Change of a statistic relative to a baseline value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StatChange {
+    pub current: f64,
+    pub baseline: f64,
+    /// `current - baseline`.
+    pub abs: f64,
+    /// `abs` as a percentage of `baseline`, `None` if `baseline` is `0`.
+    pub pct: Option<f64>,
+}
+
+impl StatChange {
+    /// Computes the change from `baseline` to `current`.
+    pub fn new(current: f64, baseline: f64) -> Self {
+        let abs = current - baseline;
+        Self {
+            current,
+            baseline,
+            abs,
+            pct: (baseline != 0.0).then(|| abs / baseline * 100.0),
+        }
+    }
+}
+
+/// Changes of the statistics of a [`SummaryStats`] relative to a baseline, returned by [`SummaryStats::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummaryStatsDiff {
+    /// The current and baseline counts.
+    pub count: (u64, u64),
+    pub mean: StatChange,
+    pub min: StatChange,
+    pub p1: StatChange,
+    pub p5: StatChange,
+    pub p10: StatChange,
+    pub p25: StatChange,
+    pub median: StatChange,
+    pub p75: StatChange,
+    pub p90: StatChange,
+    pub p95: StatChange,
+    pub p99: StatChange,
+    pub max: StatChange,
+}
+
+/// Comparison of a span group between two [`Timings`](crate::Timings), returned by
+/// [`Timings::diff`](crate::Timings::diff).
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpanGroupDiff {
+    /// The span group is only in the current [`Timings`](crate::Timings), with the given statistics.
+    Added(SummaryStats),
+    /// The span group is only in the baseline [`Timings`](crate::Timings), with the given statistics.
+    Removed(SummaryStats),
+    /// The span group is in both, with the given changes.
+    Changed(Box<SummaryStatsDiff>),
+}
+
+/// Summary statistics that only include the full [`SummaryStats`] if there are enough samples for its quantiles to
+/// be meaningful, e.g., a `p99` computed from 3 samples is just the maximum and should not be interpreted as a tail
+/// latency.
+#[derive(Debug, Clone)]
+pub struct SummaryStatsMinCount {
+    pub count: u64,
+    pub mean: f64,
+    /// The full summary statistics, `None` if `count` is below the minimum count.
+    pub stats: Option<SummaryStats>,
+}
+
+impl SummaryStatsMinCount {
+    /// Computes summary statistics from the given [`Timing`], in its [`Timing::unit`], omitting the full
+    /// [`SummaryStats`] if the histogram has fewer than `min_count` samples.
+    pub fn new(timing: &Timing, min_count: u64) -> Self {
+        Self {
+            count: timing.len(),
+            mean: timing.mean(),
+            stats: (timing.len() >= min_count).then(|| SummaryStats::new(timing)),
+        }
+    }
+
+    /// Returns the median, if there are enough samples.
+    pub fn median(&self) -> Option<u64> {
+        self.stats.as_ref().map(|stats| stats.median)
+    }
+
+    /// Returns the 90th percentile, if there are enough samples.
+    pub fn p90(&self) -> Option<u64> {
+        self.stats.as_ref().map(|stats| stats.p90)
+    }
+
+    /// Returns the 95th percentile, if there are enough samples.
+    pub fn p95(&self) -> Option<u64> {
+        self.stats.as_ref().map(|stats| stats.p95)
+    }
+
+    /// Returns the 99th percentile, if there are enough samples.
+    pub fn p99(&self) -> Option<u64> {
+        self.stats.as_ref().map(|stats| stats.p99)
+    }
+}
+
+/// Computes a [`SummaryStats`] from a [`Timing`], in its [`Timing::unit`], with the [`DEFAULT_PERCENTILES`].
+/// Use [`SummaryStats::new_with_unit`] for any other [`Histogram<u64>`].
+pub fn summary_stats(timing: &Timing) -> SummaryStats {
+    SummaryStats::new(timing)
 }
 
-/// Computes a [`SummaryStats`] from a [`Timing`].
-pub fn summary_stats(hist: &Timing) -> SummaryStats {
-    SummaryStats::new(hist)
+/// Computes a [`SummaryStats`] from a [`Timing`], in its [`Timing::unit`], with the given `percentiles` (in the range
+/// `0.0..=100.0`), e.g., `&[50.0, 90.0, 99.0, 99.9]` for tail-latency analysis. Use
+/// [`SummaryStats::new_with_percentiles`] for any other [`Histogram<u64>`].
+pub fn summary_stats_with_percentiles(timing: &Timing, percentiles: &[f64]) -> SummaryStats {
+    SummaryStats::new_with_percentiles(timing, timing.unit(), percentiles)
 }
@@ -0,0 +1,66 @@
+//! Utilities to test the instrumentation of code with [`LatencyTrace`].
+//! Present only when the **"testing"** feature flag is enabled.
+//!
+//! [`LatencyTrace::activated`] sets the global default [`tracing::Subscriber`], which can only be set once per
+//! process, so tests that need different configurations or independent measurements can't use it. The
+//! functions in this module measure latencies without setting the global default subscriber.
+//!
+//! ```rust
+//! use latency_trace::{testing, LatencyTraceCfg};
+//! use tracing::{info_span, trace_span};
+//!
+//! fn f() {
+//!     info_span!("root").in_scope(|| {
+//!         for _ in 0..2 {
+//!             trace_span!("child").in_scope(|| {});
+//!         }
+//!     });
+//! }
+//!
+//! // Each measurement is independent of the others.
+//! for _ in 0..2 {
+//!     let latencies = testing::measure_latencies_isolated(LatencyTraceCfg::default(), f);
+//!     testing::assert_span_counts(&latencies, &[("root", 1), ("child", 2)]);
+//! }
+//! ```
+
+use crate::{LatencyTrace, LatencyTraceCfg, Timings};
+use std::collections::BTreeMap;
+
+/// Measures the latencies of `f` with [`LatencyTrace::with_scoped`], i.e., on a new thread whose default
+/// [`tracing::Subscriber`] is a [`LatencyTrace`] with the given `config`. Unlike the `activated*` methods of
+/// [`LatencyTrace`], this does not set the global default subscriber, so it can be used multiple times in the same
+/// process, with different configurations.
+///
+/// Only spans created on the new thread are measured, i.e., spans created on threads spawned by `f` are not.
+///
+/// # Panics
+/// If `f` panics or the `config`'s histogram parameters are invalid.
+pub fn measure_latencies_isolated(config: LatencyTraceCfg, f: impl FnOnce() + Send) -> Timings {
+    LatencyTrace::with_scoped(config, f).expect("invalid histogram configuration")
+}
+
+/// Returns the number of recorded spans for each span name, summed across the span groups with the same name.
+pub fn counts_by_name(timings: &Timings) -> BTreeMap<&'static str, u64> {
+    let mut counts = BTreeMap::new();
+    for (sg, timing) in timings.iter() {
+        *counts.entry(sg.name()).or_insert(0) += timing.len();
+    }
+    counts
+}
+
+/// Asserts that the number of recorded spans for each of the given span names, as computed by
+/// [`counts_by_name`], is as expected. Span names that are not given are not checked.
+///
+/// # Panics
+/// If any of the counts is not as expected.
+pub fn assert_span_counts(timings: &Timings, expected: &[(&str, u64)]) {
+    let counts = counts_by_name(timings);
+    for (name, expected_count) in expected {
+        let count = counts.get(name).copied().unwrap_or(0);
+        assert_eq!(
+            count, *expected_count,
+            "span count for `{name}`: actual={count}, expected={expected_count}; all counts: {counts:?}"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+//! Lock wrappers that record lock acquisition latency, a common cause of latency that is otherwise hard to attribute.
+
+use std::sync::{LockResult, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Target of the spans created by [`TracedMutex`] and [`TracedRwLock`].
+pub(crate) const LOCK_TARGET: &str = "latency_trace::traced_lock";
+
+/// Name of the span field and span group property that holds the [`TracedMutex`] or [`TracedRwLock`] name.
+pub(crate) const LOCK_FIELD: &str = "lock";
+
+/// Wrapper of a [`Mutex`] that records the latency of acquiring the lock.
+///
+/// Each call to [`Self::lock`] acquires the lock inside a `trace`-level span, so the time spent waiting for the lock
+/// is recorded in a span group. The span group is a child of the span group of the span that is current when the lock
+/// is acquired. Its [`SpanGroup::name`](crate::SpanGroup::name) is `"lock"` and its
+/// [`SpanGroup::props`](crate::SpanGroup::props) are `[("lock", name)]`, regardless of the configured span grouper.
+///
+/// Only the acquisition is measured, not the time the guard is held. As with other spans, nothing is recorded if
+/// `trace`-level spans are filtered out.
+#[derive(Debug)]
+pub struct TracedMutex<T> {
+    name: &'static str,
+    inner: Mutex<T>,
+}
+
+impl<T> TracedMutex<T> {
+    /// Creates a mutex holding `value`, recording its acquisition latency in the span group with the given `name`.
+    pub fn new(name: &'static str, value: T) -> Self {
+        Self {
+            name,
+            inner: Mutex::new(value),
+        }
+    }
+
+    /// Returns the name of the span group in which the acquisition latency is recorded.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Acquires the lock as [`Mutex::lock`] does, recording the acquisition latency.
+    pub fn lock(&self) -> LockResult<MutexGuard<'_, T>> {
+        let span = tracing::trace_span!(target: LOCK_TARGET, "lock", lock = self.name);
+        span.in_scope(|| self.inner.lock())
+    }
+
+    /// Returns a mutable reference to the wrapped value, as [`Mutex::get_mut`] does.
+    pub fn get_mut(&mut self) -> LockResult<&mut T> {
+        self.inner.get_mut()
+    }
+
+    /// Unwraps the value, as [`Mutex::into_inner`] does.
+    pub fn into_inner(self) -> LockResult<T> {
+        self.inner.into_inner()
+    }
+}
+
+/// Wrapper of a [`RwLock`] that records the latency of acquiring the lock.
+///
+/// The same as [`TracedMutex`], except that [`Self::read`] and [`Self::write`] record their acquisition latencies
+/// in separate span groups, whose [`SpanGroup::name`](crate::SpanGroup::name)s are `"read_lock"` and `"write_lock"`
+/// respectively.
+#[derive(Debug)]
+pub struct TracedRwLock<T> {
+    name: &'static str,
+    inner: RwLock<T>,
+}
+
+impl<T> TracedRwLock<T> {
+    /// Creates a lock holding `value`, recording its acquisition latencies in the span groups with the given `name`.
+    pub fn new(name: &'static str, value: T) -> Self {
+        Self {
+            name,
+            inner: RwLock::new(value),
+        }
+    }
+
+    /// Returns the name of the span groups in which the acquisition latencies are recorded.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Acquires a shared lock as [`RwLock::read`] does, recording the acquisition latency.
+    pub fn read(&self) -> LockResult<RwLockReadGuard<'_, T>> {
+        let span = tracing::trace_span!(target: LOCK_TARGET, "read_lock", lock = self.name);
+        span.in_scope(|| self.inner.read())
+    }
+
+    /// Acquires an exclusive lock as [`RwLock::write`] does, recording the acquisition latency.
+    pub fn write(&self) -> LockResult<RwLockWriteGuard<'_, T>> {
+        let span = tracing::trace_span!(target: LOCK_TARGET, "write_lock", lock = self.name);
+        span.in_scope(|| self.inner.write())
+    }
+
+    /// Returns a mutable reference to the wrapped value, as [`RwLock::get_mut`] does.
+    pub fn get_mut(&mut self) -> LockResult<&mut T> {
+        self.inner.get_mut()
+    }
+
+    /// Unwraps the value, as [`RwLock::into_inner`] does.
+    pub fn into_inner(self) -> LockResult<T> {
+        self.inner.into_inner()
+    }
+}
@@ -0,0 +1,73 @@
+//! Longitudinal view of latencies across multiple measurement runs.
+
+use crate::{SpanGroup, Timings, Wrapper};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{Display, Write},
+};
+
+/// Per-span-group time series of a latency quantile across labeled runs, produced by [`quantile_trend`].
+///
+/// Each series is a list of `(label, value)` pairs in the order of the runs passed to [`quantile_trend`].
+pub type QuantileTrend<L> = Wrapper<BTreeMap<SpanGroup, Vec<(L, u64)>>>;
+
+/// Produces, for each span group, the time series of the value at `quantile` of its [`Timing`](crate::Timing)
+/// across the given labeled runs, e.g., [`Timings`] from multiple CI runs labeled by commit.
+///
+/// Span groups are matched across runs by [`SpanGroup::id`]. A span group that is absent from some runs has no
+/// points for those runs. Span groups with no recorded latencies in a run (e.g., parents of probed span groups
+/// that have not yet closed) also have no points for that run.
+pub fn quantile_trend<'a, L>(
+    runs: impl IntoIterator<Item = (L, &'a Timings)>,
+    quantile: f64,
+) -> QuantileTrend<L>
+where
+    L: Clone,
+{
+    let mut id_to_sg: HashMap<&'a str, &'a SpanGroup> = HashMap::new();
+    let mut series: BTreeMap<SpanGroup, Vec<(L, u64)>> = BTreeMap::new();
+    for (label, timings) in runs {
+        for (sg, timing) in timings.iter().filter(|(_, timing)| !timing.is_empty()) {
+            let sg = *id_to_sg.entry(sg.id()).or_insert(sg);
+            series
+                .entry(sg.clone())
+                .or_default()
+                .push((label.clone(), timing.value_at_quantile(quantile)));
+        }
+    }
+    series.into()
+}
+
+impl<L> QuantileTrend<L> {
+    /// Renders `self` as CSV suitable for plotting, with header `span_group_id,span_group_name,label,value`
+    /// and one row per point.
+    pub fn to_csv(&self) -> String
+    where
+        L: Display,
+    {
+        let mut csv = String::from("span_group_id,span_group_name,label,value\n");
+        for (sg, points) in self.iter() {
+            for (label, value) in points {
+                writeln!(
+                    csv,
+                    "{},{},{},{}",
+                    csv_field(sg.id()),
+                    csv_field(sg.name()),
+                    csv_field(&label.to_string()),
+                    value
+                )
+                .expect("writing to a String can't fail");
+            }
+        }
+        csv
+    }
+}
+
+/// Quotes a CSV field if needed, according to RFC 4180.
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}